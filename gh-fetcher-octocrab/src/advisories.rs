@@ -0,0 +1,255 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Affected version interval: from `introduced` up to `end` (inclusive when `last_affected`)
+#[derive(Debug, Clone)]
+struct AffectedRange {
+    introduced: String,
+    end: Option<(String, bool)>,
+}
+
+#[derive(Debug, Clone)]
+struct Advisory {
+    id: String,
+    ranges: Vec<AffectedRange>,
+    versions: Vec<String>, // Explicitly listed affected versions
+}
+
+/// Known-vulnerable package versions, loaded from OSV-format JSON
+/// (e.g. an osv.dev ecosystem export or a GitHub advisory database checkout)
+#[derive(Debug, Default)]
+pub struct AdvisoryDatabase {
+    advisories: HashMap<(String, String), Vec<Advisory>>, // (ecosystem, package), lowercased
+}
+
+impl AdvisoryDatabase {
+    /// Loads `IDENZERO_ADVISORY_DB` (a JSON file or a directory of them); empty when unset or unreadable
+    pub fn from_env() -> Self {
+        let Ok(path) = std::env::var("IDENZERO_ADVISORY_DB") else { return Self::default() };
+        match Self::load(Path::new(&path)) {
+            Ok(database) => {
+                tracing::info!("Loaded {} advisories from {}", database.len(), path);
+                database
+            }
+            Err(e) => {
+                tracing::warn!("Could not load advisory database at {}: {}", path, e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Reads OSV records from a file (one record, an array, or `{"vulns": [...]}`) or every
+    /// `.json` file below a directory
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let mut database = Self::default();
+        if path.is_dir() {
+            let mut pending = vec![path.to_path_buf()];
+            while let Some(dir) = pending.pop() {
+                for entry in std::fs::read_dir(&dir)?.filter_map(|e| e.ok()) {
+                    let entry_path = entry.path();
+                    if entry_path.is_dir() {
+                        pending.push(entry_path);
+                    } else if entry_path.extension().is_some_and(|e| e == "json") {
+                        database.add_json(&std::fs::read_to_string(&entry_path)?);
+                    }
+                }
+            }
+        } else {
+            database.add_json(&std::fs::read_to_string(path)?);
+        }
+        Ok(database)
+    }
+
+    fn add_json(&mut self, content: &str) {
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(content) else { return };
+        let records = match &json {
+            serde_json::Value::Array(records) => records.clone(),
+            _ if json["vulns"].is_array() => json["vulns"].as_array().cloned().unwrap_or_default(),
+            _ => vec![json],
+        };
+        for record in &records {
+            self.add_record(record);
+        }
+    }
+
+    fn add_record(&mut self, record: &serde_json::Value) {
+        let Some(id) = record["id"].as_str() else { return };
+        // Withdrawn advisories no longer apply
+        if record["withdrawn"].is_string() {
+            return;
+        }
+
+        for affected in record["affected"].as_array().into_iter().flatten() {
+            let (Some(ecosystem), Some(name)) = (affected["package"]["ecosystem"].as_str(), affected["package"]["name"].as_str()) else {
+                continue;
+            };
+            let ranges = affected["ranges"].as_array().into_iter().flatten()
+                .filter(|range| range["type"].as_str() != Some("GIT"))
+                .flat_map(|range| ranges_from_events(range["events"].as_array().map(|e| e.as_slice()).unwrap_or_default()))
+                .collect();
+            let versions = affected["versions"].as_array().into_iter().flatten()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect();
+
+            // "crates.io", "npm", "PyPI", "Go", ...; Debian-style suffixes ("Debian:12") are dropped
+            let ecosystem = ecosystem.split(':').next().unwrap_or(ecosystem).to_lowercase();
+            self.advisories.entry((ecosystem, name.to_lowercase())).or_default().push(Advisory {
+                id: id.to_string(),
+                ranges,
+                versions,
+            });
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.advisories.values().map(|a| a.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.advisories.is_empty()
+    }
+
+    /// IDs of advisories covering this package version
+    pub fn affecting(&self, ecosystem: &str, package: &str, version: &str) -> Vec<String> {
+        let Some(advisories) = self.advisories.get(&(ecosystem.to_lowercase(), package.to_lowercase())) else {
+            return Vec::new();
+        };
+        let mut ids: Vec<String> = advisories.iter()
+            .filter(|advisory| {
+                advisory.versions.iter().any(|v| compare_versions(v, version) == Ordering::Equal)
+                    || advisory.ranges.iter().any(|range| range.contains(version))
+            })
+            .map(|advisory| advisory.id.clone())
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+}
+
+impl AffectedRange {
+    fn contains(&self, version: &str) -> bool {
+        let after_start = self.introduced == "0" || compare_versions(version, &self.introduced) != Ordering::Less;
+        let before_end = match &self.end {
+            Some((end, true)) => compare_versions(version, end) != Ordering::Greater,
+            Some((end, false)) => compare_versions(version, end) == Ordering::Less,
+            None => true,
+        };
+        after_start && before_end
+    }
+}
+
+/// OSV events in order: each "introduced" opens a range that "fixed" or "last_affected" closes
+fn ranges_from_events(events: &[serde_json::Value]) -> Vec<AffectedRange> {
+    let mut ranges = Vec::new();
+    let mut open: Option<String> = None;
+    for event in events {
+        if let Some(introduced) = event["introduced"].as_str() {
+            if let Some(start) = open.replace(introduced.to_string()) {
+                ranges.push(AffectedRange { introduced: start, end: None });
+            }
+        } else if let Some(fixed) = event["fixed"].as_str() {
+            if let Some(start) = open.take() {
+                ranges.push(AffectedRange { introduced: start, end: Some((fixed.to_string(), false)) });
+            }
+        } else if let Some(last) = event["last_affected"].as_str() {
+            if let Some(start) = open.take() {
+                ranges.push(AffectedRange { introduced: start, end: Some((last.to_string(), true)) });
+            }
+        }
+    }
+    if let Some(start) = open {
+        ranges.push(AffectedRange { introduced: start, end: None });
+    }
+    ranges
+}
+
+/// Orders dotted versions numerically ("1.10.0" > "1.9.3"); a pre-release sorts before its release
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let split = |v: &str| {
+        let v = v.trim().trim_start_matches('v');
+        let v = v.split('+').next().unwrap_or(v);
+        match v.split_once('-') {
+            Some((core, pre)) => (core.to_string(), Some(pre.to_string())),
+            None => (v.to_string(), None),
+        }
+    };
+    let ((core_a, pre_a), (core_b, pre_b)) = (split(a), split(b));
+
+    let parts_a: Vec<&str> = core_a.split('.').collect();
+    let parts_b: Vec<&str> = core_b.split('.').collect();
+    for i in 0..parts_a.len().max(parts_b.len()) {
+        let (x, y) = (parts_a.get(i).copied().unwrap_or("0"), parts_b.get(i).copied().unwrap_or("0"));
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    match (pre_a, pre_b) {
+        (None, None) => Ordering::Equal,
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(x), Some(y)) => x.cmp(&y),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(introduced: &str, end: Option<(&str, bool)>) -> AffectedRange {
+        AffectedRange { introduced: introduced.to_string(), end: end.map(|(v, inclusive)| (v.to_string(), inclusive)) }
+    }
+
+    #[test]
+    fn versions_compare_numerically() {
+        assert_eq!(compare_versions("1.10.0", "1.9.3"), Ordering::Greater);
+        assert_eq!(compare_versions("v2.0", "2.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0.0+build.5", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0.0-rc.1", "1.0.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0.0-alpha", "1.0.0-beta"), Ordering::Less);
+        assert_eq!(compare_versions("0.0.9", "0.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn fixed_ranges_exclude_the_fix() {
+        let fixed = range("1.2.0", Some(("1.4.1", false)));
+        assert!(!fixed.contains("1.1.9"));
+        assert!(fixed.contains("1.2.0"));
+        assert!(fixed.contains("1.4.1-rc.1"));
+        assert!(!fixed.contains("1.4.1"));
+    }
+
+    #[test]
+    fn last_affected_and_open_ranges() {
+        let last = range("0", Some(("2.0.3", true)));
+        assert!(last.contains("0.0.1"));
+        assert!(last.contains("2.0.3"));
+        assert!(!last.contains("2.0.4"));
+
+        let open = range("3.0.0", None);
+        assert!(!open.contains("2.9.9"));
+        assert!(open.contains("10.0.0"));
+    }
+
+    #[test]
+    fn osv_records_match_ranges_and_listed_versions() {
+        let mut database = AdvisoryDatabase::default();
+        database.add_json(r#"{"vulns": [
+            {"id": "GHSA-1", "affected": [{"package": {"ecosystem": "npm", "name": "Lodash"},
+                "ranges": [{"type": "SEMVER", "events": [{"introduced": "0"}, {"fixed": "4.17.21"}]}]}]},
+            {"id": "GHSA-2", "affected": [{"package": {"ecosystem": "npm", "name": "lodash"}, "versions": ["4.17.21"]}]},
+            {"id": "GHSA-3", "withdrawn": "2024-01-01T00:00:00Z",
+                "affected": [{"package": {"ecosystem": "npm", "name": "lodash"}, "versions": ["4.17.20"]}]}
+        ]}"#);
+        assert_eq!(database.len(), 2);
+        assert_eq!(database.affecting("npm", "lodash", "4.17.20"), vec!["GHSA-1"]);
+        assert_eq!(database.affecting("NPM", "lodash", "4.17.21"), vec!["GHSA-2"]);
+        assert!(database.affecting("npm", "lodash", "4.17.22").is_empty());
+    }
+}
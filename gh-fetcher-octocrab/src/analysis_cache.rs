@@ -0,0 +1,530 @@
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::analyzer::SkillAnalysis;
+use crate::improved_analyzer::AnalysisDepth;
+use crate::ownership::OwnershipVerification;
+use crate::profile_source::SourceResult;
+use crate::wallet_binding::WalletBinding;
+
+/// Default on-disk location when `IDENZERO_CACHE_PATH` is unset
+pub const DEFAULT_CACHE_PATH: &str = "idenzero-cache.db";
+
+/// Responses not fetched or revalidated for this long are dropped
+const DEFAULT_RESPONSE_MAX_AGE_DAYS: i64 = 30;
+/// Most responses kept; the least recently validated go first
+const DEFAULT_MAX_RESPONSES: usize = 50_000;
+/// Stored responses between two pruning passes
+const RESPONSE_PRUNE_INTERVAL: usize = 100;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS analyses (
+    source      TEXT NOT NULL,
+    username    TEXT NOT NULL COLLATE NOCASE,
+    analysis    TEXT NOT NULL,
+    analyzed_at TEXT NOT NULL,
+    PRIMARY KEY (source, username)
+);
+CREATE TABLE IF NOT EXISTS responses (
+    route      TEXT PRIMARY KEY,
+    etag       TEXT NOT NULL,
+    body       TEXT NOT NULL,
+    fetched_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS responses_fetched_at ON responses (fetched_at);
+CREATE TABLE IF NOT EXISTS owner_verifications (
+    username     TEXT PRIMARY KEY COLLATE NOCASE,
+    verification TEXT NOT NULL,
+    verified_at  TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS wallet_bindings (
+    username TEXT PRIMARY KEY COLLATE NOCASE,
+    wallet   TEXT NOT NULL,
+    binding  TEXT NOT NULL,
+    bound_at TEXT NOT NULL
+);
+";
+
+/// How old a cached analysis may be before each depth recomputes it
+#[derive(Debug, Clone)]
+pub struct CacheTtls {
+    pub basic: Duration,
+    pub standard: Duration,
+    pub detailed: Duration,
+    pub frontend: Duration,
+}
+
+impl Default for CacheTtls {
+    fn default() -> Self {
+        Self {
+            basic: Duration::hours(72),
+            standard: Duration::hours(24),
+            detailed: Duration::hours(12),
+            frontend: Duration::hours(24),
+        }
+    }
+}
+
+impl CacheTtls {
+    /// Defaults overridden by `IDENZERO_CACHE_TTL_{BASIC,STANDARD,DETAILED,FRONTEND}_HOURS`
+    pub fn from_env() -> Self {
+        let hours = |name: &str, default: Duration| {
+            std::env::var(format!("IDENZERO_CACHE_TTL_{}_HOURS", name)).ok()
+                .and_then(|v| v.parse::<i64>().ok())
+                .map(Duration::hours)
+                .unwrap_or(default)
+        };
+        let defaults = Self::default();
+
+        Self {
+            basic: hours("BASIC", defaults.basic),
+            standard: hours("STANDARD", defaults.standard),
+            detailed: hours("DETAILED", defaults.detailed),
+            frontend: hours("FRONTEND", defaults.frontend),
+        }
+    }
+
+    pub fn for_depth(&self, depth: &AnalysisDepth) -> Duration {
+        match depth {
+            AnalysisDepth::Basic => self.basic,
+            AnalysisDepth::Standard => self.standard,
+            AnalysisDepth::Detailed => self.detailed,
+            AnalysisDepth::Frontend => self.frontend,
+        }
+    }
+}
+
+/// A response body stored with the ETag GitHub sent for it
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub etag: String,
+    pub body: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CachedAnalysisInfo {
+    pub source: String,
+    pub analyzed_at: DateTime<Utc>,
+    pub age_hours: f64,
+    pub total_repositories: u32,
+    pub overall_score: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CachedResponseInfo {
+    pub route: String,
+    pub etag: String,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Everything cached for one user, as reported by the inspect endpoint
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CacheEntries {
+    pub username: String,
+    pub analyses: Vec<CachedAnalysisInfo>,
+    pub responses: Vec<CachedResponseInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CachePurge {
+    pub analyses_removed: usize,
+    pub responses_removed: usize,
+}
+
+/// SQLite-backed store for finished analyses and ETag'd API responses, so
+/// restarts do not re-fetch every profile from scratch. Ownership verifications and
+/// wallet bindings live here too but are not cache entries, so purging leaves them alone.
+pub struct AnalysisCache {
+    conn: Mutex<Connection>,
+    response_max_age: Duration,
+    max_responses: usize,
+    responses_since_prune: AtomicUsize,
+}
+
+impl AnalysisCache {
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        // Several analyzers in one server share the file
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        Self::with_connection(conn)
+    }
+
+    /// Process-local cache that disappears with the analyzer
+    pub fn in_memory() -> Self {
+        Connection::open_in_memory()
+            .and_then(Self::with_connection)
+            .expect("in-memory SQLite is always available")
+    }
+
+    /// Opens `IDENZERO_CACHE_PATH` (or the default file); falls back to memory if that fails.
+    /// Response limits come from `IDENZERO_CACHE_RESPONSE_MAX_AGE_DAYS` and `IDENZERO_CACHE_MAX_RESPONSES`
+    pub fn from_env() -> Self {
+        let path = std::env::var("IDENZERO_CACHE_PATH").unwrap_or_else(|_| DEFAULT_CACHE_PATH.to_string());
+        let cache = match Self::open(&path) {
+            Ok(cache) => cache,
+            Err(e) => {
+                tracing::warn!("Could not open analysis cache at {}: {}, using memory", path, e);
+                Self::in_memory()
+            }
+        };
+
+        let max_age = std::env::var("IDENZERO_CACHE_RESPONSE_MAX_AGE_DAYS").ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .map(Duration::days)
+            .unwrap_or(cache.response_max_age);
+        let max_responses = std::env::var("IDENZERO_CACHE_MAX_RESPONSES").ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(cache.max_responses);
+        cache.with_response_limits(max_age, max_responses)
+    }
+
+    fn with_connection(conn: Connection) -> rusqlite::Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            response_max_age: Duration::days(DEFAULT_RESPONSE_MAX_AGE_DAYS),
+            max_responses: DEFAULT_MAX_RESPONSES,
+            responses_since_prune: AtomicUsize::new(0),
+        })
+    }
+
+    /// Keep responses at most `max_age` after they were last validated, and at most `max_responses` of them
+    pub fn with_response_limits(mut self, max_age: Duration, max_responses: usize) -> Self {
+        self.response_max_age = max_age;
+        self.max_responses = max_responses;
+        self
+    }
+
+    /// Cached analysis if it is younger than `max_age`
+    pub fn get_analysis(&self, source: &str, username: &str, max_age: Duration) -> Option<SkillAnalysis> {
+        self.latest_analysis(source, username)
+            .filter(|analysis| crate::clock::now() - analysis.analyzed_at < max_age)
+    }
+
+    /// Most recent analysis regardless of age, the baseline for incremental re-analysis
+    pub fn latest_analysis(&self, source: &str, username: &str) -> Option<SkillAnalysis> {
+        let conn = self.conn.lock().ok()?;
+        let raw: String = conn.query_row(
+            "SELECT analysis FROM analyses WHERE source = ?1 AND username = ?2",
+            params![source, username],
+            |row| row.get(0),
+        ).optional().ok()??;
+
+        serde_json::from_str(&raw).ok()
+    }
+
+    pub fn put_analysis(&self, source: &str, analysis: &SkillAnalysis) {
+        let Ok(raw) = serde_json::to_string(analysis) else { return };
+        let Ok(conn) = self.conn.lock() else { return };
+        if let Err(e) = conn.execute(
+            "INSERT OR REPLACE INTO analyses (source, username, analysis, analyzed_at) VALUES (?1, ?2, ?3, ?4)",
+            params![source, analysis.github_username, raw, analysis.analyzed_at.to_rfc3339()],
+        ) {
+            tracing::warn!("Failed to cache analysis for {}: {}", analysis.github_username, e);
+        }
+    }
+
+    pub fn get_response(&self, route: &str) -> Option<CachedResponse> {
+        let conn = self.conn.lock().ok()?;
+        conn.query_row(
+            "SELECT etag, body FROM responses WHERE route = ?1",
+            params![route],
+            |row| Ok(CachedResponse { etag: row.get(0)?, body: row.get(1)? }),
+        ).optional().ok()?
+    }
+
+    pub fn put_response(&self, route: &str, etag: &str, body: &str) {
+        let Ok(conn) = self.conn.lock() else { return };
+        if let Err(e) = conn.execute(
+            "INSERT OR REPLACE INTO responses (route, etag, body, fetched_at) VALUES (?1, ?2, ?3, ?4)",
+            params![route, etag, body, Utc::now().to_rfc3339()],
+        ) {
+            tracing::warn!("Failed to cache response for {}: {}", route, e);
+        }
+        drop(conn);
+
+        if self.responses_since_prune.fetch_add(1, Ordering::Relaxed) + 1 >= RESPONSE_PRUNE_INTERVAL {
+            self.responses_since_prune.store(0, Ordering::Relaxed);
+            self.prune_responses();
+        }
+    }
+
+    /// Marks a response as still current after GitHub answered 304, so pruning keeps it
+    pub fn touch_response(&self, route: &str) {
+        let Ok(conn) = self.conn.lock() else { return };
+        if let Err(e) = conn.execute(
+            "UPDATE responses SET fetched_at = ?2 WHERE route = ?1",
+            params![route, Utc::now().to_rfc3339()],
+        ) {
+            tracing::warn!("Failed to refresh cached response for {}: {}", route, e);
+        }
+    }
+
+    /// Drops responses past the maximum age, then the least recently validated beyond the maximum count
+    pub fn prune_responses(&self) -> usize {
+        let Ok(conn) = self.conn.lock() else { return 0 };
+        let cutoff = (Utc::now() - self.response_max_age).to_rfc3339();
+        let expired = conn.execute("DELETE FROM responses WHERE fetched_at < ?1", params![cutoff]).unwrap_or(0);
+        let excess = conn.execute(
+            "DELETE FROM responses WHERE route IN (SELECT route FROM responses ORDER BY fetched_at DESC LIMIT -1 OFFSET ?1)",
+            params![self.max_responses as i64],
+        ).unwrap_or(0);
+        if expired + excess > 0 {
+            tracing::debug!("Pruned {} expired and {} excess cached responses", expired, excess);
+        }
+        expired + excess
+    }
+
+    pub fn put_owner_verification(&self, verification: &OwnershipVerification) {
+        let Ok(raw) = serde_json::to_string(verification) else { return };
+        let Ok(conn) = self.conn.lock() else { return };
+        if let Err(e) = conn.execute(
+            "INSERT OR REPLACE INTO owner_verifications (username, verification, verified_at) VALUES (?1, ?2, ?3)",
+            params![verification.username, raw, verification.verified_at.to_rfc3339()],
+        ) {
+            tracing::warn!("Failed to store ownership verification for {}: {}", verification.username, e);
+        }
+    }
+
+    pub fn owner_verification(&self, username: &str) -> Option<OwnershipVerification> {
+        let conn = self.conn.lock().ok()?;
+        let raw: String = conn.query_row(
+            "SELECT verification FROM owner_verifications WHERE username = ?1",
+            params![username],
+            |row| row.get(0),
+        ).optional().ok()??;
+
+        serde_json::from_str(&raw).ok()
+    }
+
+    /// Stores the user's first verified wallet; fails if one is already bound
+    pub fn insert_wallet_binding(&self, binding: &WalletBinding) -> SourceResult<()> {
+        let raw = serde_json::to_string(binding)?;
+        let conn = self.conn.lock().map_err(|_| "analysis cache unavailable")?;
+        match conn.execute(
+            "INSERT INTO wallet_bindings (username, wallet, binding, bound_at) VALUES (?1, ?2, ?3, ?4)",
+            params![binding.username, binding.wallet_address, raw, binding.bound_at.to_rfc3339()],
+        ) {
+            Ok(_) => Ok(()),
+            Err(rusqlite::Error::SqliteFailure(e, _)) if e.code == rusqlite::ErrorCode::ConstraintViolation => {
+                Err(format!("{} already has a bound wallet", binding.username).into())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Replaces the user's binding only while it is still for `previous_wallet`
+    pub fn replace_wallet_binding(&self, binding: &WalletBinding, previous_wallet: &str) -> SourceResult<()> {
+        let raw = serde_json::to_string(binding)?;
+        let conn = self.conn.lock().map_err(|_| "analysis cache unavailable")?;
+        let updated = conn.execute(
+            "UPDATE wallet_bindings SET wallet = ?3, binding = ?4, bound_at = ?5 WHERE username = ?1 AND wallet = ?2",
+            params![binding.username, previous_wallet, binding.wallet_address, raw, binding.bound_at.to_rfc3339()],
+        )?;
+        if updated == 0 {
+            return Err(format!("the wallet bound to {} changed while rebinding", binding.username).into());
+        }
+        Ok(())
+    }
+
+    /// Drops the user's binding, returning it; the only way to rebind once the bound wallet's key is lost
+    pub fn remove_wallet_binding(&self, username: &str) -> SourceResult<Option<WalletBinding>> {
+        let conn = self.conn.lock().map_err(|_| "analysis cache unavailable")?;
+        let raw: Option<String> = conn.query_row(
+            "DELETE FROM wallet_bindings WHERE username = ?1 RETURNING binding",
+            params![username],
+            |row| row.get(0),
+        ).optional()?;
+
+        Ok(raw.and_then(|raw| serde_json::from_str(&raw).ok()))
+    }
+
+    pub fn wallet_binding(&self, username: &str) -> Option<WalletBinding> {
+        let conn = self.conn.lock().ok()?;
+        let raw: String = conn.query_row(
+            "SELECT binding FROM wallet_bindings WHERE username = ?1",
+            params![username],
+            |row| row.get(0),
+        ).optional().ok()??;
+
+        serde_json::from_str(&raw).ok()
+    }
+
+    pub fn entries(&self, username: &str) -> CacheEntries {
+        let mut entries = CacheEntries {
+            username: username.to_string(),
+            analyses: Vec::new(),
+            responses: Vec::new(),
+        };
+        if !is_valid_username(username) {
+            return entries;
+        }
+        let Ok(conn) = self.conn.lock() else { return entries };
+        let now = crate::clock::now();
+
+        if let Ok(mut stmt) = conn.prepare("SELECT source, analysis FROM analyses WHERE username = ?1 ORDER BY source") {
+            let rows = stmt.query_map(params![username], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)));
+            if let Ok(rows) = rows {
+                entries.analyses = rows.filter_map(|row| row.ok())
+                    .filter_map(|(source, raw)| {
+                        let analysis: SkillAnalysis = serde_json::from_str(&raw).ok()?;
+                        Some(CachedAnalysisInfo {
+                            source,
+                            analyzed_at: analysis.analyzed_at,
+                            age_hours: (now - analysis.analyzed_at).num_minutes() as f64 / 60.0,
+                            total_repositories: analysis.total_repositories,
+                            overall_score: analysis.overall_score,
+                        })
+                    })
+                    .collect();
+            }
+        }
+
+        let (sql, patterns) = user_route_filter(username);
+        if let Ok(mut stmt) = conn.prepare(&format!("SELECT route, etag, fetched_at FROM responses WHERE {} ORDER BY route", sql)) {
+            let rows = stmt.query_map(rusqlite::params_from_iter(patterns.iter()), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+            });
+            if let Ok(rows) = rows {
+                entries.responses = rows.filter_map(|row| row.ok())
+                    .filter_map(|(route, etag, fetched_at)| {
+                        Some(CachedResponseInfo {
+                            route,
+                            etag,
+                            fetched_at: DateTime::parse_from_rfc3339(&fetched_at).ok()?.with_timezone(&Utc),
+                        })
+                    })
+                    .collect();
+            }
+        }
+
+        entries
+    }
+
+    /// Drops every analysis and API response cached for a user
+    pub fn purge(&self, username: &str) -> CachePurge {
+        if !is_valid_username(username) {
+            return CachePurge::default();
+        }
+        let Ok(conn) = self.conn.lock() else { return CachePurge::default() };
+        let (sql, patterns) = user_route_filter(username);
+
+        CachePurge {
+            analyses_removed: conn.execute("DELETE FROM analyses WHERE username = ?1", params![username]).unwrap_or(0),
+            responses_removed: conn.execute(&format!("DELETE FROM responses WHERE {}", sql), rusqlite::params_from_iter(patterns.iter()))
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// GitHub login: letters, digits and hyphens, at most 39 characters
+pub fn is_valid_username(username: &str) -> bool {
+    !username.is_empty() && username.len() <= 39 && username.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// WHERE clause matching the user's own routes and routes of repositories they own. Logins are
+/// case-insensitive, like the `analyses` table: `=` compares with NOCASE and LIKE ignores ASCII case
+fn user_route_filter(username: &str) -> (&'static str, [String; 4]) {
+    // `_` and `%` are LIKE wildcards; a name made of them would match other users' routes
+    let escaped = username.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    (
+        "route = ?1 COLLATE NOCASE OR route LIKE ?2 ESCAPE '\\' OR route LIKE ?3 ESCAPE '\\' OR route LIKE ?4 ESCAPE '\\'",
+        [
+            format!("/users/{}", username),
+            format!("/users/{}/%", escaped),
+            format!("/users/{}?%", escaped),
+            format!("/repos/{}/%", escaped),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache_with_responses(routes: &[&str]) -> AnalysisCache {
+        let cache = AnalysisCache::in_memory();
+        for route in routes {
+            cache.put_response(route, "\"etag\"", "{}");
+        }
+        cache
+    }
+
+    #[test]
+    fn purges_only_the_users_routes() {
+        let cache = cache_with_responses(&["/users/octocat", "/users/octocat/repos?page=1", "/repos/octocat/hello/languages", "/users/octo", "/repos/octocats/x/readme"]);
+        let purged = cache.purge("octocat");
+        assert_eq!(purged.responses_removed, 3);
+        assert!(cache.get_response("/users/octo").is_some());
+        assert!(cache.get_response("/repos/octocats/x/readme").is_some());
+    }
+
+    #[test]
+    fn purges_routes_whatever_the_case() {
+        let cache = cache_with_responses(&["/users/OctoCat", "/users/OctoCat/repos", "/repos/OctoCat/hello/readme"]);
+        assert_eq!(cache.entries("octocat").responses.len(), 3);
+        assert_eq!(cache.purge("OCTOCAT").responses_removed, 3);
+    }
+
+    #[test]
+    fn wildcard_names_match_nothing() {
+        let cache = cache_with_responses(&["/users/abc/repos", "/repos/abc/x/readme", "/users/a_c/repos"]);
+        assert_eq!(cache.purge("___").responses_removed, 0);
+        assert_eq!(cache.purge("%").responses_removed, 0);
+        assert!(cache.entries("a%").responses.is_empty());
+        assert_eq!(cache.entries("abc").responses.len(), 2);
+
+        // Escaped even when called directly: `_` only matches itself
+        let (sql, patterns) = user_route_filter("a_c");
+        let conn = cache.conn.lock().unwrap();
+        let matched: i64 = conn.query_row(&format!("SELECT COUNT(*) FROM responses WHERE {}", sql), rusqlite::params_from_iter(patterns.iter()), |row| row.get(0)).unwrap();
+        assert_eq!(matched, 1);
+    }
+
+    #[test]
+    fn validates_usernames() {
+        assert!(is_valid_username("octo-cat42"));
+        assert!(!is_valid_username(""));
+        assert!(!is_valid_username("___"));
+        assert!(!is_valid_username("a/b"));
+        assert!(!is_valid_username(&"a".repeat(40)));
+    }
+
+    #[test]
+    fn prunes_stale_and_excess_responses() {
+        let cache = cache_with_responses(&["/users/a", "/users/b", "/users/c", "/users/d"])
+            .with_response_limits(Duration::days(30), 2);
+        {
+            let conn = cache.conn.lock().unwrap();
+            let age = |route: &str, days: i64| {
+                conn.execute("UPDATE responses SET fetched_at = ?2 WHERE route = ?1", params![route, (Utc::now() - Duration::days(days)).to_rfc3339()]).unwrap();
+            };
+            age("/users/a", 40);
+            age("/users/b", 3);
+            age("/users/c", 2);
+            age("/users/d", 1);
+        }
+        // Revalidating keeps an entry that would otherwise be the oldest
+        cache.touch_response("/users/b");
+
+        assert_eq!(cache.prune_responses(), 2);
+        assert!(cache.get_response("/users/a").is_none());
+        assert!(cache.get_response("/users/c").is_none());
+        assert!(cache.get_response("/users/b").is_some() && cache.get_response("/users/d").is_some());
+    }
+
+    #[test]
+    fn storing_responses_prunes_periodically() {
+        let cache = AnalysisCache::in_memory().with_response_limits(Duration::days(30), 10);
+        for i in 0..RESPONSE_PRUNE_INTERVAL {
+            cache.put_response(&format!("/users/u{}", i), "etag", "{}");
+        }
+        let count: i64 = cache.conn.lock().unwrap().query_row("SELECT COUNT(*) FROM responses", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 10);
+    }
+}
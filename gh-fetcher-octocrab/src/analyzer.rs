@@ -1,8 +1,35 @@
-use chrono::{DateTime, Utc};
-use octocrab::{models::Repository, Octocrab, Result};
+use chrono::{DateTime, Datelike, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use tokio::time::{sleep, Duration};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use futures::stream::{self, StreamExt};
+use std::sync::Arc;
+use tokio::time::{timeout, Duration};
+
+use crate::advisories::AdvisoryDatabase;
+use crate::analysis_cache::{AnalysisCache, CacheTtls};
+use crate::api_usage::ApiUsage;
+use crate::authorship::{AuthorIdentity, Authorship};
+use crate::complexity_metrics::{analyze_source, sample_paths, ComplexityMetrics};
+use crate::contribution_calendar::ContributionActivity;
+use crate::dependency_manifests::{count_technologies, manifest_kind, ManifestKind, MAX_MANIFESTS_PER_REPO};
+use crate::issue_activity::IssueActivity;
+use crate::onchain_activity::{OnchainActivity, OnchainAnalyzer};
+use crate::readme_analysis::{has_docs_directory, ReadmeAnalysis};
+use crate::review_activity::ReviewActivity;
+use crate::security_hygiene::{
+    is_lockfile_path, is_secret_prone_path, is_workflow_path, SecurityHygiene,
+    MAX_LOCKFILES_PER_REPO, MAX_SECRET_SCAN_FILES_PER_REPO, MAX_WORKFLOWS_PER_REPO,
+};
+use crate::smart_contracts::{
+    is_contract_source, is_contract_test, is_deployment_record, SmartContractAnalysis,
+    MAX_CONTRACTS_PER_REPO, MAX_CONTRACT_TESTS_PER_REPO, MAX_DEPLOYMENT_RECORDS_PER_REPO,
+};
+use crate::tree_inspection::TreeInspection;
+use crate::token_pool::TokenPool;
+use crate::graphql_fetcher::ContributionStats;
+use crate::profile_source::{
+    CommitRecord, ExternalContribution, GitHubSource, ProfileSource, RepositoryData, SourceResult, MAX_COMMITS_PER_REPO,
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UserProfile {
@@ -17,6 +44,8 @@ pub struct LanguageSkill {
     pub lines_of_code: u64,
     pub commit_count: u32,
     pub project_count: u32,
+    #[serde(default)]
+    pub complexity_metrics: Option<ComplexityMetrics>, // From sampled source files, when any were readable
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -40,17 +69,43 @@ pub struct Specialization {
 pub struct SkillAnalysis {
     pub github_username: String,
     pub wallet_address: Option<String>,
+    #[serde(default)]
+    pub wallet_bound: bool, // The wallet signed a challenge naming this GitHub account
+    #[serde(default)]
+    pub verified_owner: bool, // Someone proved control of the GitHub account via its bio or a gist
     pub overall_score: f64,
     pub consistency_score: f64,
     pub complexity_score: f64,
     pub collaboration_score: f64,
     pub web3_expertise: f64,
     pub commit_quality_score: f64,
-    pub language_breakdown: HashMap<String, LanguageSkill>,
+    pub language_breakdown: BTreeMap<String, LanguageSkill>, // Ordered so serialized analyses are reproducible
     pub repository_analysis: Vec<RepositoryAnalysis>,
     pub specializations: Vec<Specialization>,
     pub years_active: f64,
     pub total_repositories: u32,
+    #[serde(default)]
+    pub contribution_stats: Option<ContributionStats>,
+    #[serde(default)]
+    pub external_contributions: Vec<ExternalContribution>, // Merged PRs and commits in repos the user does not own
+    #[serde(default)]
+    pub commits_last_year: u32,
+    #[serde(default)]
+    pub avg_commits_per_month: f64,
+    #[serde(default)]
+    pub contribution_activity: Option<ContributionActivity>, // Streaks and rhythm from the contribution calendar
+    #[serde(default)]
+    pub review_activity: Option<ReviewActivity>, // Pull requests authored and reviews given
+    #[serde(default)]
+    pub issue_activity: Option<IssueActivity>, // Issues opened and commented on, triage and discussions answered
+    #[serde(default)]
+    pub technology_breakdown: BTreeMap<String, TechnologySkill>, // Frameworks and tools declared in dependency manifests
+    #[serde(default)]
+    pub complexity_metrics: Option<ComplexityMetrics>, // Across every sampled source file
+    #[serde(default)]
+    pub onchain_activity: Option<OnchainActivity>, // None without a wallet or a configured EVM endpoint
+    #[serde(default)]
+    pub api_requests: Option<u32>, // Requests made producing this analysis; None when the source does not meter them
     pub analyzed_at: DateTime<Utc>,
 }
 
@@ -59,7 +114,7 @@ pub struct RepositoryAnalysis {
     pub name: String,
     pub description: Option<String>,
     pub primary_language: Option<String>,
-    pub languages: HashMap<String, u64>,
+    pub languages: BTreeMap<String, u64>,
     pub stars: u32,
     pub forks: u32,
     pub created_at: Option<DateTime<Utc>>,
@@ -68,20 +123,98 @@ pub struct RepositoryAnalysis {
     pub documentation_score: f64,
     pub testing_coverage: f64,
     pub is_web3_project: bool,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub commit_count: Option<u32>,
+    #[serde(default)]
+    pub pushed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub commit_analysis: Option<CommitAnalysis>, // None when the source could not list commits
+    #[serde(default)]
+    pub readme: Option<ReadmeAnalysis>, // None when the repository has no README we could read
+    #[serde(default)]
+    pub tree_inspection: Option<TreeInspection>, // None when the source could not list files
+    #[serde(default)]
+    pub technologies: BTreeMap<String, u32>, // Technology -> declaring dependencies in the repository's manifests
+    #[serde(default)]
+    pub complexity: BTreeMap<String, ComplexityMetrics>, // Language -> metrics of its sampled source files
+    #[serde(default)]
+    pub security_hygiene: Option<SecurityHygiene>, // None when the source could not list files
+    #[serde(default)]
+    pub smart_contracts: Option<SmartContractAnalysis>, // None without contracts or a contract framework
+    #[serde(default)]
+    pub analyzed_in_depth: bool, // False when scored from metadata alone (past the deep limit or timed out)
 }
 
+/// The user's commits to one repository over the last year
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CommitAnalysis {
+    pub total_commits: u32,
+    pub monthly_commits: BTreeMap<String, u32>, // "YYYY-MM" -> commits, so windows can be re-derived later
+    pub message_quality: f64, // 0-100
+    pub last_commit_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub co_authored_commits: u32, // Included in total_commits
+    #[serde(default)]
+    pub repository_commits: u32, // Everyone's commits in the same window, extrapolated when the listing was capped
+    #[serde(default)]
+    pub signed_commits: Option<u32>, // None when the source does not report signatures
+}
+
+impl CommitAnalysis {
+    /// Fraction of the repository's recent commits that are the user's, when known
+    pub fn authorship_share(&self) -> Option<f64> {
+        (self.repository_commits > 0).then(|| self.total_commits as f64 / self.repository_commits as f64)
+    }
+}
+
+/// Repositories analyzed at once unless `IDENZERO_REPO_CONCURRENCY` says otherwise
+const DEFAULT_REPO_CONCURRENCY: usize = 8;
+
+/// Repositories whose files and commits are read unless `IDENZERO_DEEP_REPO_LIMIT` says otherwise
+const DEFAULT_DEEP_REPO_LIMIT: usize = 30;
+
+/// Longest a single repository may take before we fall back to its metadata alone,
+/// unless `IDENZERO_REPO_TIMEOUT_SECS` says otherwise
+const DEFAULT_REPO_TIMEOUT_SECS: u64 = 60;
+
+/// A technology counts as in recent use when a repository declaring it was pushed this recently
+const RECENT_TECHNOLOGY_DAYS: i64 = 180;
+
 pub struct GitHubAnalyzer {
-    pub client: Octocrab,
-    cache: HashMap<String, SkillAnalysis>,
+    source: Arc<dyn ProfileSource>,
+    cache: Arc<AnalysisCache>,
+    cache_ttls: CacheTtls,
     language_weights: HashMap<String, f64>,
+    max_concurrency: usize,
+    deep_repo_limit: usize,
+    repo_timeout: Duration,
+    advisories: Arc<AdvisoryDatabase>,
+    onchain: Option<Arc<OnchainAnalyzer>>,
 }
 
 impl GitHubAnalyzer {
-    pub fn new(token: String) -> Result<Self> {
-        let client = Octocrab::builder()
-            .personal_token(token)
-            .build()?;
+    pub fn new(token: String) -> octocrab::Result<Self> {
+        // Analyses and API responses persist across restarts
+        let cache = Arc::new(AnalysisCache::from_env());
+        let source = GitHubSource::new(token)?.with_response_cache(cache.clone());
+        Ok(Self::with_source(Arc::new(source)).with_cache(cache))
+    }
 
+    /// GitHub analyzer drawing on a pool of tokens, which may be shared with other analyzers
+    pub fn with_token_pool(tokens: Arc<TokenPool>) -> Self {
+        Self::with_token_pool_and_cache(tokens, Arc::new(AnalysisCache::from_env()))
+    }
+
+    /// Like `with_token_pool`, keeping analyses and responses in `cache`, which other analyzers may share
+    pub fn with_token_pool_and_cache(tokens: Arc<TokenPool>, cache: Arc<AnalysisCache>) -> Self {
+        let source = GitHubSource::with_token_pool(tokens).with_response_cache(cache.clone());
+        Self::with_source(Arc::new(source)).with_cache(cache)
+    }
+
+    /// Analyzer backed by any `ProfileSource` (GitHub, GitLab, Gitea, Bitbucket, ...)
+    pub fn with_source(source: Arc<dyn ProfileSource>) -> Self {
         // TrustHire Web3-focused language weights as specified in README
         let language_weights = [
             ("Solidity".to_string(), 2.5), ("Rust".to_string(), 2.2), ("Move".to_string(), 2.0), ("Cairo".to_string(), 2.0),
@@ -92,59 +225,199 @@ impl GitHubAnalyzer {
             ("MATLAB".to_string(), 1.0), ("Shell".to_string(), 1.0), ("HTML".to_string(), 1.0), ("CSS".to_string(), 1.0),
         ].into_iter().collect();
 
-        Ok(Self {
-            client,
-            cache: HashMap::new(),
+        let max_concurrency = std::env::var("IDENZERO_REPO_CONCURRENCY").ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_REPO_CONCURRENCY);
+        let deep_repo_limit = std::env::var("IDENZERO_DEEP_REPO_LIMIT").ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_DEEP_REPO_LIMIT);
+        let repo_timeout = std::env::var("IDENZERO_REPO_TIMEOUT_SECS").ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_REPO_TIMEOUT_SECS);
+
+        Self {
+            source,
+            cache: Arc::new(AnalysisCache::in_memory()),
+            cache_ttls: CacheTtls::from_env(),
             language_weights,
-        })
+            max_concurrency,
+            deep_repo_limit,
+            repo_timeout: Duration::from_secs(repo_timeout),
+            advisories: Arc::new(AdvisoryDatabase::from_env()),
+            onchain: OnchainAnalyzer::from_env().map(Arc::new),
+        }
     }
 
-    pub async fn analyze(&mut self, profile: UserProfile) -> Result<SkillAnalysis> {
-        let username = &profile.github_username;
-        
-        if let Some(cached) = self.cache.get(username) {
-            if (Utc::now() - cached.analyzed_at).num_hours() < 24 {
-                return Ok(cached.clone());
-            }
-        }
+    /// Store analyses in `cache` instead of a private in-memory one
+    pub fn with_cache(mut self, cache: Arc<AnalysisCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Check lockfiles against `advisories` instead of the `IDENZERO_ADVISORY_DB` one
+    pub fn with_advisories(mut self, advisories: Arc<AdvisoryDatabase>) -> Self {
+        self.advisories = advisories;
+        self
+    }
+
+    /// Analyze the profile's wallet with `onchain` instead of the `IDENZERO_EVM_RPC_URL` endpoint
+    pub fn with_onchain_analyzer(mut self, onchain: Arc<OnchainAnalyzer>) -> Self {
+        self.onchain = Some(onchain);
+        self
+    }
+
+    pub fn cache(&self) -> &Arc<AnalysisCache> {
+        &self.cache
+    }
+
+    pub fn cache_ttls(&self) -> &CacheTtls {
+        &self.cache_ttls
+    }
+
+    /// Number of repositories analyzed in parallel (at least one)
+    pub fn with_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency;
+        self
+    }
+
+    /// Number of changed repositories, most recently pushed first, whose tree, files and commits are
+    /// read; the others are scored from their metadata alone
+    pub fn with_deep_repo_limit(mut self, deep_repo_limit: usize) -> Self {
+        self.deep_repo_limit = deep_repo_limit;
+        self
+    }
+
+    /// Longest a single repository may take before it is scored from its metadata alone
+    pub fn with_repo_timeout(mut self, repo_timeout: Duration) -> Self {
+        self.repo_timeout = repo_timeout;
+        self
+    }
 
-        tracing::info!("Fetching comprehensive GitHub data for user: {}", username);
+    pub fn source(&self) -> &Arc<dyn ProfileSource> {
+        &self.source
+    }
 
-        // Fetch ALL repositories (public)
-        let repositories = self.fetch_all_repositories(username).await?;
-        tracing::debug!("Found {} repositories to analyze", repositories.len());
+    /// Requests made and rate-limit budget of the underlying source, if it meters them
+    pub fn api_usage(&self) -> Option<ApiUsage> {
+        self.source.api_usage()
+    }
+
+    pub async fn analyze(&mut self, profile: UserProfile) -> SourceResult<SkillAnalysis> {
+        let max_age = self.cache_ttls.standard;
+        self.analyze_with_max_age(profile, max_age).await
+    }
+
+    /// Analysis reusing a cached result younger than `max_age`
+    pub async fn analyze_with_max_age(&mut self, profile: UserProfile, max_age: chrono::Duration) -> SourceResult<SkillAnalysis> {
+        let username = &profile.github_username;
+
+        // On-chain evidence belongs to the wallet, so a different one or a new binding needs a fresh analysis
+        let wallet_bound = self.is_wallet_bound(&profile);
+        let cached = self.cache.get_analysis(self.source.name(), username, max_age)
+            .filter(|cached| cached.wallet_address == profile.wallet_address && cached.wallet_bound == wallet_bound);
+        if let Some(mut cached) = cached {
+            cached.verified_owner = self.cache.owner_verification(username).is_some();
+            return Ok(cached);
+        }
+        // An expired snapshot still lets unchanged repositories skip their fetches
+        let previous = self.cache.latest_analysis(self.source.name(), username);
+        let requests_before = self.api_usage().map(|u| u.requests_made);
+
+        tracing::info!("Fetching comprehensive {} data for user: {}", self.source.name(), username);
+
+        // Fetch ALL repositories (public); a failed listing fails the analysis rather than reading as an empty account
+        let repositories = filter_forks(self.source.fetch_repositories(username).await?);
+        // Whether every account-level fetch succeeded
+        let mut complete = true;
+        let contribution_stats = self.source.fetch_contribution_stats(username).await.unwrap_or(None);
+        let external_contributions = self.source.fetch_external_contributions(username).await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to fetch external contributions for {}: {}", username, e);
+            complete = false;
+            Vec::new()
+        });
+        let contribution_activity = match self.source.fetch_contribution_calendar(username).await {
+            Ok(calendar) => calendar.map(|c| ContributionActivity::from_calendar(&c, crate::clock::now().date_naive())),
+            Err(e) => {
+                tracing::warn!("Failed to fetch contribution calendar for {}: {}", username, e);
+                complete = false;
+                None
+            }
+        };
+        let review_activity = self.source.fetch_review_activity(username).await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to fetch review activity for {}: {}", username, e);
+            complete = false;
+            None
+        });
+        let issue_activity = self.source.fetch_issue_activity(username).await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to fetch issue activity for {}: {}", username, e);
+            complete = false;
+            None
+        });
+        tracing::debug!(
+            "Found {} repositories to analyze and {} external contributions",
+            repositories.len(), external_contributions.len()
+        );
 
         if repositories.is_empty() {
             tracing::warn!("No repositories found for user: {}", username);
-            return Ok(self.create_empty_analysis(&profile));
+            let mut analysis = self.create_empty_analysis(&profile);
+            analysis.language_breakdown = self.calculate_language_skills(&[], &external_contributions);
+            analysis.external_contributions = external_contributions;
+            analysis.contribution_activity = contribution_activity;
+            analysis.collaboration_score = self.calculate_collaboration_score(&[], review_activity.as_ref(), issue_activity.as_ref());
+            analysis.review_activity = review_activity;
+            analysis.issue_activity = issue_activity;
+            analysis.onchain_activity = self.analyze_onchain(&profile, &[]).await;
+            let bound_activity = analysis.onchain_activity.as_ref().filter(|_| wallet_bound);
+            analysis.web3_expertise = self.calculate_web3_expertise(&analysis.language_breakdown, &[], bound_activity);
+            analysis.api_requests = self.requests_since(requests_before);
+            // An account with nothing else to show is only cached when every fetch behind it succeeded
+            if complete {
+                self.cache.put_analysis(self.source.name(), &analysis);
+            }
+            return Ok(analysis);
         }
 
-        // Analyze repositories
-        let repo_analyses = self.analyze_repositories(username, &repositories).await;
+        // Commits are credited only when they match the user's identity
+        let identity = self.source.fetch_author_identity(username).await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to fetch author identity for {}: {}", username, e);
+            AuthorIdentity::for_login(username)
+        });
 
-        if repo_analyses.is_empty() {
-            tracing::warn!("Failed to analyze any repositories for user: {}", username);
-            return Ok(self.create_empty_analysis(&profile));
-        }
+        // Analyze repositories, reusing entries whose last push matches the previous snapshot
+        let repo_analyses = self.analyze_repositories_incremental(&identity, &repositories, previous.as_ref()).await;
 
         // Calculate comprehensive language skills
-        let language_breakdown = self.calculate_language_skills(&repo_analyses);
+        let language_breakdown = self.calculate_language_skills(&repo_analyses, &external_contributions);
         
-        // Determine specializations (simplified for now)
-        let specializations = self.determine_specializations_simple(&repo_analyses);
+        // Technologies from dependency manifests drive specializations; Web3 keywords cover repos without manifests
+        let technology_breakdown = self.identify_technologies(&repo_analyses);
+        let mut specializations = self.determine_specializations(&repo_analyses, &technology_breakdown);
+        for fallback in self.determine_specializations_simple(&repo_analyses) {
+            if !specializations.iter().any(|s| s.area == fallback.area) {
+                specializations.push(fallback);
+            }
+        }
         
         // Calculate TrustHire-specific scores
         let overall_score = self.calculate_overall_score(&language_breakdown, &repo_analyses);
         let years_active = self.calculate_years_active(&repo_analyses);
         let consistency_score = self.calculate_consistency_score(&repo_analyses);
-        let complexity_score = self.calculate_complexity_score(&language_breakdown);
-        let collaboration_score = self.calculate_collaboration_score(&repo_analyses);
-        let web3_expertise = self.calculate_web3_expertise(&language_breakdown, &repo_analyses);
+        let complexity_metrics = ComplexityMetrics::combine(repo_analyses.iter().flat_map(|r| r.complexity.values()));
+        let complexity_score = self.calculate_complexity_score(&language_breakdown, complexity_metrics.as_ref());
+        let collaboration_score = self.calculate_collaboration_score(&repo_analyses, review_activity.as_ref(), issue_activity.as_ref());
+        let onchain_activity = self.analyze_onchain(&profile, &repo_analyses).await;
+        // Anyone can name any wallet, so only a bound one counts towards expertise
+        let bound_activity = onchain_activity.as_ref().filter(|_| wallet_bound);
+        let web3_expertise = self.calculate_web3_expertise(&language_breakdown, &repo_analyses, bound_activity);
         let commit_quality_score = self.calculate_commit_quality_score(&repo_analyses);
+        let commits_last_year: u32 = repo_analyses.iter().map(commits_last_year).sum();
 
         let analysis = SkillAnalysis {
             github_username: profile.github_username.clone(),
             wallet_address: profile.wallet_address.clone(),
+            wallet_bound,
+            verified_owner: self.cache.owner_verification(username).is_some(),
             overall_score,
             consistency_score,
             complexity_score,
@@ -156,110 +429,281 @@ impl GitHubAnalyzer {
             specializations,
             years_active,
             total_repositories: repo_analyses.len() as u32,
-            analyzed_at: Utc::now(),
+            contribution_stats,
+            external_contributions,
+            commits_last_year,
+            avg_commits_per_month: commits_last_year as f64 / 12.0,
+            contribution_activity,
+            review_activity,
+            issue_activity,
+            technology_breakdown,
+            complexity_metrics,
+            onchain_activity,
+            api_requests: self.requests_since(requests_before),
+            analyzed_at: crate::clock::now(),
         };
 
         // Cache the result
-        self.cache.insert(username.to_string(), analysis.clone());
+        self.cache.put_analysis(self.source.name(), &analysis);
         
-        tracing::info!("Analysis complete for user: {}", username);
+        match analysis.api_requests {
+            Some(requests) => tracing::info!("Analysis complete for user: {} ({} API requests)", username, requests),
+            None => tracing::info!("Analysis complete for user: {}", username),
+        }
         Ok(analysis)
     }
 
+    /// Requests the source made since `before` was read
+    fn requests_since(&self, before: Option<u32>) -> Option<u32> {
+        Some(self.api_usage()?.requests_made.saturating_sub(before?))
+    }
+
+    /// Whether the profile's wallet has a verified binding to its GitHub account
+    fn is_wallet_bound(&self, profile: &UserProfile) -> bool {
+        let Some(wallet) = profile.wallet_address.as_deref() else { return false };
+        self.cache.wallet_binding(&profile.github_username)
+            .is_some_and(|binding| binding.wallet_address.eq_ignore_ascii_case(wallet.trim()))
+    }
+
+    async fn analyze_onchain(&self, profile: &UserProfile, repo_analyses: &[RepositoryAnalysis]) -> Option<OnchainActivity> {
+        let (onchain, wallet) = (self.onchain.as_ref()?, profile.wallet_address.as_deref()?);
+        match onchain.analyze(wallet, repo_analyses).await {
+            Ok(activity) => Some(activity),
+            Err(e) => {
+                tracing::warn!("Failed to analyze on-chain activity of {}: {}", wallet, e);
+                None
+            }
+        }
+    }
+
     fn create_empty_analysis(&self, profile: &UserProfile) -> SkillAnalysis {
         SkillAnalysis {
             github_username: profile.github_username.clone(),
             wallet_address: profile.wallet_address.clone(),
+            wallet_bound: self.is_wallet_bound(profile),
+            verified_owner: self.cache.owner_verification(&profile.github_username).is_some(),
             overall_score: 0.0,
             consistency_score: 0.0,
             complexity_score: 0.0,
             collaboration_score: 0.0,
             web3_expertise: 0.0,
             commit_quality_score: 0.0,
-            language_breakdown: HashMap::new(),
+            language_breakdown: BTreeMap::new(),
             repository_analysis: Vec::new(),
             specializations: Vec::new(),
             years_active: 0.0,
             total_repositories: 0,
-            analyzed_at: Utc::now(),
+            contribution_stats: None,
+            external_contributions: Vec::new(),
+            commits_last_year: 0,
+            avg_commits_per_month: 0.0,
+            contribution_activity: None,
+            review_activity: None,
+            issue_activity: None,
+            technology_breakdown: BTreeMap::new(),
+            complexity_metrics: None,
+            onchain_activity: None,
+            api_requests: None,
+            analyzed_at: crate::clock::now(),
         }
     }
 
-    async fn fetch_all_repositories(&self, username: &str) -> Result<Vec<Repository>> {
-        let mut all_repos = Vec::new();
-        let mut page = 1u32;
-        let per_page = 100u8;
-
-        loop {
-            let repos_result = self.client
-                .users(username)
-                .repos()
-                .per_page(per_page)
-                .page(page)
-                .send()
-                .await;
+    async fn analyze_repositories_incremental(
+        &self,
+        identity: &AuthorIdentity,
+        repos: &[RepositoryData],
+        previous: Option<&SkillAnalysis>,
+    ) -> Vec<RepositoryAnalysis> {
+        let previous: HashMap<&str, &RepositoryAnalysis> = previous
+            .map(|p| p.repository_analysis.iter().map(|r| (r.name.as_str(), r)).collect())
+            .unwrap_or_default();
+
+        let (unchanged, changed): (Vec<&RepositoryData>, Vec<&RepositoryData>) = repos.iter()
+            .partition(|repo| {
+                // Metadata-only entries get their full analysis as soon as the budget allows
+                previous.get(repo.name.as_str())
+                    .is_some_and(|prev| prev.analyzed_in_depth && prev.pushed_at.is_some() && prev.pushed_at == repo.pushed_at)
+            });
+        if !previous.is_empty() {
+            tracing::info!("Re-analyzing {} changed repositories, reusing {} unchanged", changed.len(), unchanged.len());
+        }
 
-            let repos = match repos_result {
-                Ok(repos) => repos,
-                Err(e) => {
-                    tracing::error!("Failed to fetch repositories for {}: {}", username, e);
-                    break;
-                }
-            };
+        let changed: Vec<RepositoryData> = changed.into_iter().cloned().collect();
+        let mut fresh: HashMap<String, RepositoryAnalysis> = self.analyze_repositories(identity, &changed).await
+            .into_iter()
+            .map(|analysis| (analysis.name.clone(), analysis))
+            .collect();
 
-            if repos.items.is_empty() {
-                break;
-            }
+        // Keep the source's repository order
+        repos.iter()
+            .filter_map(|repo| match fresh.remove(&repo.name) {
+                Some(analysis) => Some(analysis),
+                None => previous.get(repo.name.as_str()).map(|prev| self.refresh_repository_analysis(prev, repo)),
+            })
+            .collect()
+    }
 
-            let items_len = repos.items.len();
-            all_repos.extend(repos.items);
-            
-            if items_len < per_page as usize {
-                break; // Last page
-            }
-            
-            page += 1;
-            sleep(Duration::from_millis(200)).await; // Rate limiting
+    /// Unchanged code, but stars, forks and descriptions move without a push
+    fn refresh_repository_analysis(&self, previous: &RepositoryAnalysis, repo: &RepositoryData) -> RepositoryAnalysis {
+        RepositoryAnalysis {
+            description: repo.description.clone(),
+            stars: repo.stars,
+            forks: repo.forks,
+            updated_at: repo.updated_at,
+            topics: repo.topics.clone(),
+            commit_count: repo.commit_count.or(previous.commit_count),
+            architecture_score: self.calculate_architecture_score(repo, previous.tree_inspection.as_ref()),
+            documentation_score: self.calculate_documentation_score(repo, previous.readme.as_ref()),
+            is_web3_project: self.is_web3_project(&repo.name, &repo.description, &previous.primary_language)
+                || previous.smart_contracts.is_some(),
+            ..previous.clone()
         }
+    }
 
-        // Filter out forks unless they have significant activity
-        let filtered_repos: Vec<Repository> = all_repos.into_iter()
-            .filter(|repo| {
-                !repo.fork.unwrap_or(false) || repo.stargazers_count.unwrap_or(0) > 5 || 
-                repo.forks_count.unwrap_or(0) > 2
-            })
+    async fn analyze_repositories(&self, identity: &AuthorIdentity, repos: &[RepositoryData]) -> Vec<RepositoryAnalysis> {
+        // Pacing under a low rate-limit budget happens per request in the source, so
+        // concurrency only bounds how many repositories are in flight.
+        // `buffered` keeps results in repository order.
+        let deep = deep_repositories(repos, self.deep_repo_limit);
+        if deep.len() < repos.len() {
+            tracing::info!("Reading files and commits of {} repositories, scoring {} from metadata", deep.len(), repos.len() - deep.len());
+        }
+        let tasks: Vec<_> = repos.iter().enumerate()
+            .map(|(index, repo)| self.analyze_repository_bounded(identity, repo, index, repos.len(), deep.contains(repo.name.as_str())))
             .collect();
 
-        Ok(filtered_repos)
+        stream::iter(tasks)
+            .buffered(self.max_concurrency.max(1))
+            .collect()
+            .await
     }
 
-    async fn analyze_repositories(&self, _username: &str, repos: &[Repository]) -> Vec<RepositoryAnalysis> {
-        let mut analyses = Vec::new();
-
-        for (index, repo) in repos.iter().enumerate() {
-            tracing::debug!("Analyzing repository {}/{}: {}", index + 1, repos.len(), repo.name);
-            
-            let analysis = self.analyze_repository_simple(repo).await;
-            analyses.push(analysis);
-            
-            // Rate limiting
-            sleep(Duration::from_millis(100)).await;
+    async fn analyze_repository_bounded(
+        &self,
+        identity: &AuthorIdentity,
+        repo: &RepositoryData,
+        index: usize,
+        total: usize,
+        deep: bool,
+    ) -> RepositoryAnalysis {
+        tracing::debug!("Analyzing repository {}/{}: {}", index + 1, total, repo.name);
+        if !deep {
+            return self.build_repository_analysis(repo, fallback_languages(repo), None, None, BTreeMap::new());
         }
 
-        analyses
+        match timeout(self.repo_timeout, self.analyze_repository_simple(identity, repo)).await {
+            Ok(analysis) => analysis,
+            Err(_) => {
+                tracing::warn!("Analysis of {}/{} timed out, using repository metadata only", repo.owner, repo.name);
+                self.build_repository_analysis(repo, fallback_languages(repo), None, None, BTreeMap::new())
+            }
+        }
     }
 
-    async fn analyze_repository_simple(&self, repo: &Repository) -> RepositoryAnalysis {
-        // Extract basic repository information
-        let primary_language = repo.language.as_ref()
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
+    async fn analyze_repository_simple(&self, identity: &AuthorIdentity, repo: &RepositoryData) -> RepositoryAnalysis {
+        // Full language byte breakdown (already present when the source batches it)
+        let languages = match &repo.languages {
+            Some(languages) => languages.clone(),
+            None => self.fetch_repository_languages(repo).await,
+        };
+
+        // File listing lets tests and CI be judged from the repository itself
+        let file_tree = self.source.fetch_file_tree(repo).await.unwrap_or_else(|e| {
+            tracing::debug!("No file tree for {}/{}: {}", repo.owner, repo.name, e);
+            None
+        });
+
+        let readme = match self.source.fetch_readme(repo).await {
+            Ok(readme) => readme.map(|content| {
+                let has_docs = file_tree.as_deref().is_some_and(has_docs_directory);
+                ReadmeAnalysis::from_markdown(&content, has_docs)
+            }),
+            Err(e) => {
+                tracing::debug!("No README for {}/{}: {}", repo.owner, repo.name, e);
+                None
+            }
+        };
+
+        let technologies = match &file_tree {
+            Some(paths) => self.detect_repository_technologies(repo, paths).await,
+            None => BTreeMap::new(),
+        };
+
+        let complexity = match &file_tree {
+            Some(paths) => self.sample_complexity(repo, paths, &languages).await,
+            None => BTreeMap::new(),
+        };
+
+        let smart_contracts = match &file_tree {
+            Some(paths) => self.analyze_smart_contracts(repo, paths, &technologies).await,
+            None => None,
+        };
 
-        // Create simple language map from primary language
-        let mut languages = HashMap::new();
-        if let Some(ref lang) = primary_language {
-            languages.insert(lang.clone(), 1000u64); // Default size
+        let mut analysis = self.build_repository_analysis(repo, languages, file_tree.as_deref(), readme, technologies);
+        analysis.commit_analysis = self.analyze_commits(identity, repo).await;
+        analysis.complexity = complexity;
+        if let Some(paths) = &file_tree {
+            analysis.security_hygiene = Some(self.inspect_security(repo, paths, analysis.commit_analysis.as_ref()).await);
         }
+        analysis.is_web3_project |= smart_contracts.is_some();
+        analysis.smart_contracts = smart_contracts;
+        analysis.analyzed_in_depth = true;
+        analysis
+    }
+
+    async fn analyze_commits(&self, identity: &AuthorIdentity, repo: &RepositoryData) -> Option<CommitAnalysis> {
+        let since = crate::clock::now() - chrono::Duration::days(365);
+        if repo.pushed_at.is_some_and(|pushed| pushed < since) {
+            return Some(CommitAnalysis::default()); // Nothing can have landed in the window
+        }
+
+        // Everyone's commits: co-authored work and the user's share of the repository count too
+        let commits = match self.source.fetch_commits(repo, None, Some(since)).await {
+            Ok(commits) => commits,
+            Err(e) => {
+                tracing::debug!("No commit history for {}/{}: {}", repo.owner, repo.name, e);
+                return None;
+            }
+        };
+
+        // A capped listing only reaches the newest commits; the user's older ones are fetched by author
+        let own = match &identity.login {
+            Some(login) if commits.len() >= MAX_COMMITS_PER_REPO => {
+                match self.source.fetch_commits(repo, Some(login), Some(since)).await {
+                    Ok(own) => Some(own),
+                    Err(e) => {
+                        tracing::debug!("No authored commits for {}/{}: {}", repo.owner, repo.name, e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        Some(summarize_commits(identity, &commits, own.as_deref()))
+    }
+
+    fn build_repository_analysis(
+        &self,
+        repo: &RepositoryData,
+        languages: HashMap<String, u64>,
+        file_tree: Option<&[String]>,
+        readme: Option<ReadmeAnalysis>,
+        technologies: BTreeMap<String, u32>,
+    ) -> RepositoryAnalysis {
+        let languages: BTreeMap<String, u64> = languages.into_iter().collect();
+
+        // Some sources only report languages, so derive the primary one from the largest share
+        let primary_language = repo.primary_language.clone().or_else(|| {
+            languages.iter()
+                .max_by_key(|(_, bytes)| **bytes)
+                .map(|(lang, _)| lang.clone())
+        });
+
+        let tree_inspection = file_tree.map(TreeInspection::from_paths);
+        let testing_coverage = match &tree_inspection {
+            Some(inspection) => inspection.testing_score(),
+            None => self.calculate_testing_coverage_simple(repo),
+        };
 
         // Detect if this is a Web3 project
         let is_web3_project = self.is_web3_project(&repo.name, &repo.description, &primary_language);
@@ -269,55 +713,187 @@ impl GitHubAnalyzer {
             description: repo.description.clone(),
             primary_language,
             languages,
-            stars: repo.stargazers_count.unwrap_or(0),
-            forks: repo.forks_count.unwrap_or(0),
+            stars: repo.stars,
+            forks: repo.forks,
             created_at: repo.created_at,
             updated_at: repo.updated_at,
-            architecture_score: self.calculate_architecture_score_simple(repo),
-            documentation_score: self.calculate_documentation_score_simple(repo),
-            testing_coverage: self.calculate_testing_coverage_simple(repo),
+            architecture_score: self.calculate_architecture_score(repo, tree_inspection.as_ref()),
+            documentation_score: self.calculate_documentation_score(repo, readme.as_ref()),
+            testing_coverage,
             is_web3_project,
+            topics: repo.topics.clone(),
+            commit_count: repo.commit_count,
+            pushed_at: repo.pushed_at,
+            commit_analysis: None,
+            readme,
+            tree_inspection,
+            technologies,
+            complexity: BTreeMap::new(),
+            security_hygiene: None,
+            smart_contracts: None,
+            analyzed_in_depth: false,
         }
     }
 
-    fn detect_technologies_simple(&self, repo_name: &str, description: &Option<String>) -> Vec<String> {
-        let mut technologies = Vec::new();
-        let search_text = format!("{} {}", 
-            repo_name.to_lowercase(), 
-            description.as_deref().unwrap_or("").to_lowercase()
-        );
+    /// Reads the shallowest dependency manifests in the tree and maps their dependencies to technologies
+    async fn detect_repository_technologies(&self, repo: &RepositoryData, file_tree: &[String]) -> BTreeMap<String, u32> {
+        let mut manifest_paths = Vec::new();
+        let mut tool_files = Vec::new();
+        for path in file_tree {
+            match manifest_kind(path) {
+                Some(ManifestKind::Dependencies(_)) => manifest_paths.push(path),
+                Some(ManifestKind::Tool(technology)) => tool_files.push(technology),
+                None => {}
+            }
+        }
+        tool_files.sort_unstable();
+        tool_files.dedup();
 
-        // Technology detection keywords
-        let tech_keywords = [
-            ("react", "React"), ("vue", "Vue"), ("angular", "Angular"),
-            ("node", "Node.js"), ("express", "Express"), ("django", "Django"),
-            ("flask", "Flask"), ("spring", "Spring"), ("docker", "Docker"),
-            ("kubernetes", "Kubernetes"), ("aws", "AWS"), ("azure", "Azure"),
-            ("tensorflow", "TensorFlow"), ("pytorch", "PyTorch"),
-            ("blockchain", "Blockchain"), ("ethereum", "Ethereum"),
-            ("api", "API"), ("rest", "REST"), ("graphql", "GraphQL"),
-            ("microservice", "Microservices"), ("mobile", "Mobile"),
-            ("web", "Web Development"), ("game", "Game Development"),
-            ("ai", "Artificial Intelligence"), ("ml", "Machine Learning"),
-        ];
+        let mut manifests = Vec::new();
+        for path in shallowest(manifest_paths, MAX_MANIFESTS_PER_REPO) {
+            if let Some(content) = self.read_file(repo, path).await {
+                manifests.push((path.clone(), content));
+            }
+        }
+        count_technologies(&manifests, &tool_files)
+    }
+
+    /// Secrets, update bots, pinned actions, vulnerable lockfile entries and signed commits
+    async fn inspect_security(&self, repo: &RepositoryData, file_tree: &[String], commits: Option<&CommitAnalysis>) -> SecurityHygiene {
+        let mut hygiene = SecurityHygiene::from_tree(file_tree);
 
-        for (keyword, tech_name) in tech_keywords {
-            if search_text.contains(keyword) {
-                technologies.push(tech_name.to_string());
+        for path in shallowest(file_tree.iter().filter(|p| is_workflow_path(p)).collect(), MAX_WORKFLOWS_PER_REPO) {
+            if let Some(content) = self.read_file(repo, path).await {
+                hygiene.add_workflow(&content);
+                hygiene.scan_for_secrets(path, &content);
+            }
+        }
+        for path in shallowest(file_tree.iter().filter(|p| is_secret_prone_path(p)).collect(), MAX_SECRET_SCAN_FILES_PER_REPO) {
+            if let Some(content) = self.read_file(repo, path).await {
+                hygiene.scan_for_secrets(path, &content);
             }
         }
+        // Without advisories a lockfile proves nothing either way, so skip the reads
+        if !self.advisories.is_empty() {
+            for path in shallowest(file_tree.iter().filter(|p| is_lockfile_path(p)).collect(), MAX_LOCKFILES_PER_REPO) {
+                if let Some(content) = self.read_file(repo, path).await {
+                    hygiene.check_lockfile(path, &content, &self.advisories);
+                }
+            }
+        }
+        if let Some((total, signed)) = commits.and_then(|c| Some((c.total_commits, c.signed_commits?))) {
+            hygiene.record_signatures(total, signed);
+        }
+        hygiene.finish()
+    }
+
+    /// Test suites, fuzzing, OpenZeppelin, proxies, deployment scripts and audits of a contract repository
+    async fn analyze_smart_contracts(
+        &self,
+        repo: &RepositoryData,
+        file_tree: &[String],
+        technologies: &BTreeMap<String, u32>,
+    ) -> Option<SmartContractAnalysis> {
+        let mut analysis = SmartContractAnalysis::from_tree(file_tree)?;
+        analysis.note_repository(&repo.name, repo.description.as_deref());
+        analysis.uses_openzeppelin |= technologies.contains_key("OpenZeppelin");
+
+        for path in shallowest(file_tree.iter().filter(|p| is_contract_source(p)).collect(), MAX_CONTRACTS_PER_REPO) {
+            if let Some(content) = self.read_file(repo, path).await {
+                analysis.add_contract(&content);
+            }
+        }
+        // Fuzz and invariant tests are Solidity functions (Foundry, Echidna)
+        let solidity_tests = file_tree.iter().filter(|p| is_contract_test(p) && p.ends_with(".sol")).collect();
+        for path in shallowest(solidity_tests, MAX_CONTRACT_TESTS_PER_REPO) {
+            if let Some(content) = self.read_file(repo, path).await {
+                analysis.add_test(&content);
+            }
+        }
+        // Committed deployment records let on-chain contracts be traced back to this repository
+        for path in shallowest(file_tree.iter().filter(|p| is_deployment_record(p)).collect(), MAX_DEPLOYMENT_RECORDS_PER_REPO) {
+            if let Some(content) = self.read_file(repo, path).await {
+                analysis.add_deployment_record(&content);
+            }
+        }
+        if !analysis.uses_openzeppelin {
+            for path in file_tree.iter().filter(|p| matches!(p.as_str(), "remappings.txt" | ".gitmodules")) {
+                if let Some(content) = self.read_file(repo, path).await {
+                    analysis.add_dependency_config(&content);
+                }
+            }
+        }
+        Some(analysis.finish())
+    }
 
-        technologies
+    async fn read_file(&self, repo: &RepositoryData, path: &str) -> Option<String> {
+        self.source.fetch_file(repo, path).await.unwrap_or_else(|e| {
+            tracing::debug!("Failed to read {} in {}/{}: {}", path, repo.owner, repo.name, e);
+            None
+        })
     }
 
-    fn calculate_language_skills(&self, repo_analyses: &[RepositoryAnalysis]) -> HashMap<String, LanguageSkill> {
-        let mut language_stats: HashMap<String, (u64, u32)> = HashMap::new();
+    /// Complexity of a few representative source files per top language
+    async fn sample_complexity(&self, repo: &RepositoryData, file_tree: &[String], languages: &HashMap<String, u64>) -> BTreeMap<String, ComplexityMetrics> {
+        let mut samples: BTreeMap<String, Vec<ComplexityMetrics>> = BTreeMap::new();
+        for (path, language) in sample_paths(file_tree, languages) {
+            let Some(content) = self.read_file(repo, &path).await else { continue };
+            if let Some(metrics) = analyze_source(language, &content) {
+                samples.entry(language.to_string()).or_default().push(metrics);
+            }
+        }
+
+        samples.into_iter()
+            .filter_map(|(language, metrics)| ComplexityMetrics::combine(&metrics).map(|m| (language, m)))
+            .collect()
+    }
+
+    async fn fetch_repository_languages(&self, repo: &RepositoryData) -> HashMap<String, u64> {
+        match self.source.fetch_languages(repo).await {
+            Ok(languages) => languages,
+            Err(e) => {
+                tracing::warn!("Failed to fetch languages for {}/{}: {}", repo.owner, repo.name, e);
+                fallback_languages(repo)
+            }
+        }
+    }
+
+    fn calculate_language_skills(
+        &self,
+        repo_analyses: &[RepositoryAnalysis],
+        external_contributions: &[ExternalContribution],
+    ) -> BTreeMap<String, LanguageSkill> {
+        let mut language_stats: BTreeMap<String, (u64, u32)> = BTreeMap::new();
+        let mut language_commits: BTreeMap<String, f64> = BTreeMap::new();
+        let mut language_complexity: BTreeMap<String, Vec<&ComplexityMetrics>> = BTreeMap::new();
 
         for repo in repo_analyses {
+            for (language, metrics) in &repo.complexity {
+                language_complexity.entry(language.clone()).or_default().push(metrics);
+            }
+
+            let repo_bytes: u64 = repo.languages.values().sum();
+            let repo_commits = commits_last_year(repo) as f64;
+            // Team repositories only credit the user's share of the code
+            let share = repo.commit_analysis.as_ref().and_then(|c| c.authorship_share()).unwrap_or(1.0);
+
             for (language, bytes) in &repo.languages {
                 let entry = language_stats.entry(language.clone()).or_insert((0, 0));
-                entry.0 += bytes;
+                entry.0 += (*bytes as f64 * share) as u64;
                 entry.1 += 1; // Project count
+
+                // Commits do not say which files they touched, so split them by byte share
+                if repo_bytes > 0 {
+                    *language_commits.entry(language.clone()).or_insert(0.0) += repo_commits * *bytes as f64 / repo_bytes as f64;
+                }
+            }
+        }
+
+        // Landed work upstream counts as a project in that repo's main language, but its
+        // bytes are mostly other people's code so they do not add to lines of code
+        for contribution in external_contributions {
+            if let Some(language) = &contribution.primary_language {
+                language_stats.entry(language.clone()).or_insert((0, 0)).1 += 1;
             }
         }
 
@@ -329,22 +905,48 @@ impl GitHubAnalyzer {
                 language: language.clone(),
                 score: score.min(100.0),
                 lines_of_code: bytes / 50,
-                commit_count: 0, // Simplified
+                commit_count: language_commits.get(&language).copied().unwrap_or(0.0).round() as u32,
                 project_count,
+                complexity_metrics: language_complexity.get(&language)
+                    .and_then(|metrics| ComplexityMetrics::combine(metrics.iter().copied())),
             })
         }).collect()
     }
 
-    fn identify_technologies(&self, repo_analyses: &[RepositoryAnalysis]) -> HashMap<String, TechnologySkill> {
-        // Simplified - we'll detect based on languages and names
-        HashMap::new()
+    fn identify_technologies(&self, repo_analyses: &[RepositoryAnalysis]) -> BTreeMap<String, TechnologySkill> {
+        let recent_since = crate::clock::now() - chrono::Duration::days(RECENT_TECHNOLOGY_DAYS);
+        let mut technologies: BTreeMap<String, (u32, u32, bool)> = BTreeMap::new();
+
+        for repo in repo_analyses {
+            let recent = repo.pushed_at.or(repo.updated_at).is_some_and(|at| at >= recent_since);
+            for (technology, declarations) in &repo.technologies {
+                let entry = technologies.entry(technology.clone()).or_insert((0, 0, false));
+                entry.0 += declarations;
+                entry.1 += 1;
+                entry.2 |= recent;
+            }
+        }
+
+        technologies.into_iter().map(|(technology, (usage_count, project_count, recent_usage))| {
+            // Projects carry the score; two of them clear the specialization threshold
+            let score = project_count as f64 * 15.0 + (usage_count as f64).ln_1p() * 5.0;
+            let proficiency_score = if recent_usage { score } else { score * 0.8 };
+
+            (technology.clone(), TechnologySkill {
+                technology,
+                usage_count,
+                project_count,
+                proficiency_score: proficiency_score.min(100.0),
+                recent_usage,
+            })
+        }).collect()
     }
 
-    fn determine_specializations(&self, repo_analyses: &[RepositoryAnalysis], tech_breakdown: &HashMap<String, TechnologySkill>) -> Vec<Specialization> {
+    fn determine_specializations(&self, repo_analyses: &[RepositoryAnalysis], tech_breakdown: &BTreeMap<String, TechnologySkill>) -> Vec<Specialization> {
         let mut specializations = Vec::new();
 
         // Web Development
-        let web_techs = ["React", "Vue", "Angular", "Node.js", "Express", "Web Development"];
+        let web_techs = ["React", "Vue", "Angular", "Svelte", "Next.js", "Node.js", "Express", "NestJS", "Django", "Flask", "FastAPI", "Spring", "Gin", "Actix", "Axum", "Web Development"];
         let web_score = self.calculate_specialization_score(tech_breakdown, &web_techs, repo_analyses);
         if web_score > 30.0 {
             specializations.push(Specialization {
@@ -359,7 +961,7 @@ impl GitHubAnalyzer {
         }
 
         // Machine Learning
-        let ml_techs = ["TensorFlow", "PyTorch", "Machine Learning", "Artificial Intelligence"];
+        let ml_techs = ["TensorFlow", "PyTorch", "scikit-learn", "Keras", "JAX", "Hugging Face", "LangChain", "Machine Learning", "Artificial Intelligence"];
         let ml_score = self.calculate_specialization_score(tech_breakdown, &ml_techs, repo_analyses);
         if ml_score > 30.0 {
             specializations.push(Specialization {
@@ -374,11 +976,11 @@ impl GitHubAnalyzer {
         }
 
         // Blockchain
-        let blockchain_techs = ["Blockchain", "Ethereum"];
+        let blockchain_techs = ["Blockchain", "Ethereum", "Hardhat", "Foundry", "Truffle", "OpenZeppelin", "Ethers.js", "Ethers-rs", "Web3.js", "Web3.py", "Web3j", "Viem", "Alloy", "Solana", "Anchor", "Substrate"];
         let blockchain_score = self.calculate_specialization_score(tech_breakdown, &blockchain_techs, repo_analyses);
         if blockchain_score > 30.0 {
             specializations.push(Specialization {
-                area: "Blockchain".to_string(),
+                area: "Blockchain/Web3 Development".to_string(),
                 confidence_score: blockchain_score,
                 supporting_projects: self.get_supporting_projects(repo_analyses, &blockchain_techs),
                 key_technologies: blockchain_techs.iter()
@@ -392,7 +994,7 @@ impl GitHubAnalyzer {
         specializations
     }
 
-    fn calculate_specialization_score(&self, tech_breakdown: &HashMap<String, TechnologySkill>, specialization_techs: &[&str], _repo_analyses: &[RepositoryAnalysis]) -> f64 {
+    fn calculate_specialization_score(&self, tech_breakdown: &BTreeMap<String, TechnologySkill>, specialization_techs: &[&str], repo_analyses: &[RepositoryAnalysis]) -> f64 {
         let mut total_score = 0.0;
         let mut tech_count = 0;
 
@@ -403,28 +1005,26 @@ impl GitHubAnalyzer {
             }
         }
 
-        if tech_count > 0 {
-            total_score / tech_count as f64
-        } else {
-            0.0
+        if tech_count == 0 {
+            return 0.0;
         }
+
+        // Projects using different tools of the same area still add up to a specialization
+        let projects = repo_analyses.iter()
+            .filter(|repo| specialization_techs.iter().any(|tech| repo.technologies.contains_key(*tech)))
+            .count();
+        (total_score / tech_count as f64 + projects as f64 * 8.0).min(100.0)
     }
 
     fn get_supporting_projects(&self, repo_analyses: &[RepositoryAnalysis], specialization_techs: &[&str]) -> Vec<String> {
         repo_analyses.iter()
-            .filter(|repo| {
-                let repo_text = format!("{} {}", 
-                    repo.name.to_lowercase(), 
-                    repo.description.as_deref().unwrap_or("").to_lowercase()
-                );
-                specialization_techs.iter().any(|tech| repo_text.contains(tech))
-            })
+            .filter(|repo| specialization_techs.iter().any(|tech| repo.technologies.contains_key(*tech)))
             .map(|repo| repo.name.clone())
             .take(5)
             .collect()
     }
 
-    fn calculate_overall_score(&self, language_breakdown: &HashMap<String, LanguageSkill>, repo_analyses: &[RepositoryAnalysis]) -> f64 {
+    fn calculate_overall_score(&self, language_breakdown: &BTreeMap<String, LanguageSkill>, repo_analyses: &[RepositoryAnalysis]) -> f64 {
         if language_breakdown.is_empty() {
             return 0.0;
         }
@@ -452,7 +1052,7 @@ impl GitHubAnalyzer {
             .min();
 
         if let Some(oldest_date) = oldest_repo {
-            (Utc::now() - oldest_date).num_days() as f64 / 365.25
+            (crate::clock::now() - oldest_date).num_days() as f64 / 365.25
         } else {
             0.0
         }
@@ -484,7 +1084,7 @@ impl GitHubAnalyzer {
         let recent_activity = repo_analyses.iter()
             .filter(|r| {
                 r.updated_at.map_or(false, |updated| 
-                    (Utc::now() - updated).num_days() < 180
+                    (crate::clock::now() - updated).num_days() < 180
                 )
             })
             .count() as f64 / repo_analyses.len() as f64;
@@ -495,20 +1095,45 @@ impl GitHubAnalyzer {
     // TrustHire-specific scoring methods
     fn calculate_consistency_score(&self, repo_analyses: &[RepositoryAnalysis]) -> f64 {
         if repo_analyses.is_empty() { return 0.0; }
-        
+
+        // Prefer the real commit history: months with commits plus how even they are
+        let months = last_twelve_months();
+        if repo_analyses.iter().any(|r| r.commit_analysis.is_some()) {
+            let monthly: Vec<f64> = months.iter()
+                .map(|month| {
+                    repo_analyses.iter()
+                        .filter_map(|r| r.commit_analysis.as_ref()?.monthly_commits.get(month))
+                        .sum::<u32>() as f64
+                })
+                .collect();
+            let active_months = monthly.iter().filter(|c| **c > 0.0).count() as f64;
+            if active_months == 0.0 {
+                return 0.0;
+            }
+
+            let mean = monthly.iter().sum::<f64>() / monthly.len() as f64;
+            let std_dev = (monthly.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / monthly.len() as f64).sqrt();
+            let evenness = 1.0 / (1.0 + std_dev / mean);
+
+            return (active_months / 12.0 * 70.0 + evenness * 30.0).min(100.0);
+        }
+
         // Simple consistency based on regular commits and updated repositories
         let active_repos = repo_analyses.iter()
-            .filter(|r| r.updated_at.map_or(false, |u| (Utc::now() - u).num_days() < 365))
+            .filter(|r| r.updated_at.is_some_and(|u| (crate::clock::now() - u).num_days() < 365))
             .count() as f64;
             
         let total_repos = repo_analyses.len() as f64;
         (active_repos / total_repos * 100.0).min(100.0)
     }
 
-    fn calculate_complexity_score(&self, language_breakdown: &HashMap<String, LanguageSkill>) -> f64 {
+    fn calculate_complexity_score(&self, language_breakdown: &BTreeMap<String, LanguageSkill>, complexity_metrics: Option<&ComplexityMetrics>) -> f64 {
+        if let Some(metrics) = complexity_metrics {
+            return metrics.score();
+        }
         if language_breakdown.is_empty() { return 0.0; }
-        
-        // Higher score for more complex languages with higher multipliers
+
+        // Without readable source, fall back to how demanding the languages are
         let weighted_score: f64 = language_breakdown.values()
             .map(|skill| {
                 let multiplier = self.language_weights.get(&skill.language).copied().unwrap_or(1.0);
@@ -519,20 +1144,45 @@ impl GitHubAnalyzer {
         weighted_score.min(100.0)
     }
 
-    fn calculate_collaboration_score(&self, repo_analyses: &[RepositoryAnalysis]) -> f64 {
-        if repo_analyses.is_empty() { return 0.0; }
-        
+    fn calculate_collaboration_score(
+        &self,
+        repo_analyses: &[RepositoryAnalysis],
+        review_activity: Option<&ReviewActivity>,
+        issue_activity: Option<&IssueActivity>,
+    ) -> f64 {
         // Score based on stars and forks (community engagement)
         let total_stars: u32 = repo_analyses.iter().map(|r| r.stars).sum();
         let total_forks: u32 = repo_analyses.iter().map(|r| r.forks).sum();
         
         let star_score = (total_stars as f64).ln_1p() * 10.0;
         let fork_score = (total_forks as f64).ln_1p() * 15.0;
-        
-        (star_score + fork_score).min(100.0)
+        let popularity = (star_score + fork_score).min(100.0);
+
+        // Popularity is only a minor signal once real pull request, review or issue data exists
+        if review_activity.is_none() && issue_activity.is_none() {
+            return popularity;
+        }
+        let mut score = popularity * 0.15;
+
+        if let Some(activity) = review_activity {
+            let merge_factor = 0.5 + 0.5 * activity.merge_rate.unwrap_or(0.0);
+            score += ((activity.pull_requests_merged as f64).ln_1p() * 10.0).min(35.0) * merge_factor;
+            score += ((activity.reviews_given as f64).ln_1p() * 10.0).min(35.0)
+                + ((activity.review_comments as f64).ln_1p() * 3.0).min(15.0);
+        }
+        if let Some(activity) = issue_activity {
+            score += activity.communication_score();
+        }
+
+        score.min(100.0)
     }
 
-    fn calculate_web3_expertise(&self, language_breakdown: &HashMap<String, LanguageSkill>, repo_analyses: &[RepositoryAnalysis]) -> f64 {
+    fn calculate_web3_expertise(
+        &self,
+        language_breakdown: &BTreeMap<String, LanguageSkill>,
+        repo_analyses: &[RepositoryAnalysis],
+        onchain_activity: Option<&OnchainActivity>,
+    ) -> f64 {
         // Count Web3-related languages and projects
         let web3_languages = ["Solidity", "Rust", "Move", "Cairo", "Vyper"];
         let web3_lang_score: f64 = language_breakdown.iter()
@@ -540,21 +1190,33 @@ impl GitHubAnalyzer {
             .map(|(_, skill)| skill.score)
             .sum();
             
-        let web3_projects = repo_analyses.iter()
+        // Contract repositories earn by engineering depth, so tutorial forks add little
+        let project_bonus: f64 = repo_analyses.iter()
             .filter(|r| r.is_web3_project)
-            .count() as f64;
-            
-        let project_bonus = web3_projects * 20.0;
+            .map(|r| r.smart_contracts.as_ref().map_or(10.0, |contracts| 5.0 + contracts.score * 0.3))
+            .sum();
         
-        (web3_lang_score + project_bonus).min(100.0)
+        let onchain_bonus = onchain_activity.map_or(0.0, |activity| activity.score * 0.4);
+
+        (web3_lang_score + project_bonus + onchain_bonus).min(100.0)
     }
 
     fn calculate_commit_quality_score(&self, repo_analyses: &[RepositoryAnalysis]) -> f64 {
         if repo_analyses.is_empty() { return 0.0; }
-        
+
+        // Message quality weighted by how many commits each repository contributed
+        let (weighted, commits) = repo_analyses.iter()
+            .filter_map(|r| r.commit_analysis.as_ref())
+            .fold((0.0, 0u32), |(weighted, commits), c| {
+                (weighted + c.message_quality * c.total_commits as f64, commits + c.total_commits)
+            });
+        if commits > 0 {
+            return (weighted / commits as f64).min(100.0);
+        }
+
         // Simple scoring based on repository descriptions and structure
         let documented_repos = repo_analyses.iter()
-            .filter(|r| r.description.as_ref().map_or(false, |desc| !desc.is_empty()))
+            .filter(|r| r.description.as_ref().is_some_and(|desc| !desc.is_empty()))
             .count() as f64;
             
         (documented_repos / repo_analyses.len() as f64 * 100.0).min(100.0)
@@ -601,43 +1263,41 @@ impl GitHubAnalyzer {
         web3_keywords.iter().any(|keyword| search_text.contains(keyword))
     }
 
-    fn calculate_architecture_score_simple(&self, repo: &Repository) -> f64 {
-        // Simple scoring based on stars and repo age
-        let stars_score = (repo.stargazers_count.unwrap_or(0) as f64).ln_1p() * 10.0;
+    fn calculate_architecture_score(&self, repo: &RepositoryData, tree_inspection: Option<&TreeInspection>) -> f64 {
+        // Maturity from stars and repo age, the only signal when the file tree is unknown
+        let stars_score = (repo.stars as f64).ln_1p() * 10.0;
         let age_bonus = if let Some(created) = repo.created_at {
-            let days_old = (Utc::now() - created).num_days();
+            let days_old = (crate::clock::now() - created).num_days();
             if days_old > 365 { 20.0 } else { days_old as f64 / 365.0 * 20.0 }
         } else { 0.0 };
-        
-        (stars_score + age_bonus).min(100.0)
+        let maturity = (stars_score + age_bonus).min(100.0);
+
+        match tree_inspection {
+            Some(inspection) => (inspection.architecture_score() + maturity * 0.1).min(100.0),
+            None => maturity,
+        }
     }
 
-    fn calculate_documentation_score_simple(&self, repo: &Repository) -> f64 {
-        let mut score: f64 = 0.0;
-        
+    fn calculate_documentation_score(&self, repo: &RepositoryData, readme: Option<&ReadmeAnalysis>) -> f64 {
+        // The README carries nearly all of the score; without one only metadata counts
+        let mut score = readme.map(|r| r.score).unwrap_or(0.0);
+
         // Has description
-        if let Some(desc) = &repo.description {
-            if !desc.is_empty() {
-                score += 50.0;
-            }
-        }
-        
-        // Assume README exists if repo has description (simplified)
-        if repo.description.is_some() {
-            score += 30.0;
+        if repo.description.as_deref().is_some_and(|desc| !desc.is_empty()) {
+            score += 5.0;
         }
-        
+
         // Homepage/website
         if repo.homepage.is_some() {
-            score += 20.0;
+            score += 5.0;
         }
-        
+
         score.min(100.0)
     }
 
-    fn calculate_testing_coverage_simple(&self, repo: &Repository) -> f64 {
+    fn calculate_testing_coverage_simple(&self, repo: &RepositoryData) -> f64 {
         // Simple estimation based on language and activity
-        let base_score: f64 = match repo.language.as_ref().and_then(|v| v.as_str()) {
+        let base_score: f64 = match repo.primary_language.as_deref() {
             Some("TypeScript") | Some("JavaScript") | Some("Python") | Some("Rust") => 60.0,
             Some("Java") | Some("C#") | Some("Go") => 70.0,
             Some("Solidity") => 40.0,
@@ -646,15 +1306,434 @@ impl GitHubAnalyzer {
         
         // Bonus for active repositories
         let activity_bonus: f64 = if let Some(updated) = repo.updated_at {
-            if (Utc::now() - updated).num_days() < 90 { 15.0 } else { 0.0 }
+            if (crate::clock::now() - updated).num_days() < 90 { 15.0 } else { 0.0 }
         } else { 0.0 };
         
         (base_score + activity_bonus).min(100.0)
     }
 }
 
+/// The first `limit` paths, shallowest first
+fn shallowest(mut paths: Vec<&String>, limit: usize) -> Vec<&String> {
+    paths.sort_by_key(|path| (path.matches('/').count(), path.as_str()));
+    paths.truncate(limit);
+    paths
+}
+
+/// The user's authored and co-authored commits out of everyone's in the window. `own` holds the
+/// user's commits fetched by author when `all_commits` is a capped listing of the newest ones.
+fn summarize_commits(identity: &AuthorIdentity, all_commits: &[CommitRecord], own: Option<&[CommitRecord]>) -> CommitAnalysis {
+    let mut commits = Vec::new();
+    let mut co_authored_commits = 0;
+    for commit in all_commits {
+        match identity.classify(commit) {
+            Authorship::Author => commits.push(commit),
+            Authorship::CoAuthor => {
+                commits.push(commit);
+                co_authored_commits += 1;
+            }
+            Authorship::Other => {}
+        }
+    }
+    for commit in own.unwrap_or_default() {
+        if !commits.iter().any(|c| c.sha == commit.sha) {
+            commits.push(commit);
+        }
+    }
+
+    let mut monthly_commits = BTreeMap::new();
+    for commit in &commits {
+        *monthly_commits.entry(commit.date.format("%Y-%m").to_string()).or_insert(0) += 1;
+    }
+
+    let message_quality = if commits.is_empty() {
+        0.0
+    } else {
+        commits.iter().map(|c| commit_message_quality(&c.message)).sum::<f64>() / commits.len() as f64
+    };
+
+    // Sources report signatures for every commit or for none
+    let signed_commits = commits.iter().any(|c| c.signed.is_some())
+        .then(|| commits.iter().filter(|c| c.signed == Some(true)).count() as u32);
+
+    CommitAnalysis {
+        total_commits: commits.len() as u32,
+        monthly_commits,
+        message_quality,
+        last_commit_at: commits.iter().map(|c| c.date).max(),
+        co_authored_commits,
+        repository_commits: repository_commits(&commits, all_commits, own.is_some()),
+        signed_commits,
+    }
+}
+
+/// Everyone's commits in the window. A capped listing covers the newest commits only, so the
+/// user's share over its time span is extrapolated to all of the user's commits.
+fn repository_commits(user_commits: &[&CommitRecord], all_commits: &[CommitRecord], capped: bool) -> u32 {
+    let Some(listing_start) = all_commits.iter().map(|c| c.date).min().filter(|_| capped) else {
+        return all_commits.len() as u32;
+    };
+    let listed = all_commits.len() as f64;
+    let total = user_commits.len() as f64;
+    let in_listing = user_commits.iter().filter(|c| c.date >= listing_start).count() as f64;
+    if in_listing == 0.0 {
+        // None of the user's work is recent; all of it lies outside the listing
+        return (listed + total) as u32;
+    }
+    (listed * total / in_listing).round().max(listed) as u32
+}
+
+fn commit_message_quality(message: &str) -> f64 {
+    let first_line = message.lines().next().unwrap_or("").trim();
+    let mut score = 0.0;
+
+    // Length check (not too short, not too long)
+    if (10..=72).contains(&first_line.len()) {
+        score += 25.0;
+    }
+    if is_conventional_commit(first_line) {
+        score += 30.0;
+    }
+    // Body explaining the change
+    if message.lines().skip(1).any(|line| !line.trim().is_empty() && !line.starts_with("Co-authored-by:")) {
+        score += 20.0;
+    }
+    if is_descriptive_message(first_line) {
+        score += 25.0;
+    }
+    score
+}
+
+fn is_conventional_commit(first_line: &str) -> bool {
+    const TYPES: [&str; 11] = ["feat", "fix", "docs", "style", "refactor", "test", "chore", "perf", "ci", "build", "revert"];
+
+    // "type: subject", "type(scope): subject" or "type!: subject"
+    let Some((prefix, _)) = first_line.split_once(':') else { return false };
+    let kind = prefix.split('(').next().unwrap_or(prefix).trim_end_matches('!').to_lowercase();
+    TYPES.contains(&kind.as_str())
+}
+
+fn is_descriptive_message(first_line: &str) -> bool {
+    let lower = first_line.to_lowercase();
+    first_line.split_whitespace().count() >= 3
+        && !lower.contains("wip")
+        && !lower.contains("temp")
+        && !lower.starts_with("update")
+}
+
+/// "YYYY-MM" keys for the current month and the eleven before it
+fn last_twelve_months() -> Vec<String> {
+    let now = crate::clock::now();
+    let current = now.year() * 12 + now.month0() as i32;
+    (0..12)
+        .map(|offset| {
+            let month = current - offset;
+            format!("{:04}-{:02}", month.div_euclid(12), month.rem_euclid(12) + 1)
+        })
+        .collect()
+}
+
+/// Commits in the trailing twelve months, re-derived so reused snapshots stay current
+fn commits_last_year(repo: &RepositoryAnalysis) -> u32 {
+    let Some(commits) = &repo.commit_analysis else { return 0 };
+    last_twelve_months().iter()
+        .filter_map(|month| commits.monthly_commits.get(month))
+        .sum()
+}
+
+/// Names of the `limit` most recently pushed repositories
+fn deep_repositories(repos: &[RepositoryData], limit: usize) -> HashSet<&str> {
+    let mut by_push: Vec<&RepositoryData> = repos.iter().collect();
+    by_push.sort_by_key(|repo| std::cmp::Reverse(repo.pushed_at));
+    by_push.into_iter().take(limit).map(|repo| repo.name.as_str()).collect()
+}
+
+/// Languages known without a network call: the batched breakdown, or the primary
+/// language sized by the repository's on-disk size (KB)
+fn fallback_languages(repo: &RepositoryData) -> HashMap<String, u64> {
+    if let Some(languages) = &repo.languages {
+        return languages.clone();
+    }
+    let mut languages = HashMap::new();
+    if let Some(lang) = &repo.primary_language {
+        let bytes = repo.size_kb.map(|kb| kb as u64 * 1024).unwrap_or(0);
+        languages.insert(lang.clone(), bytes);
+    }
+    languages
+}
+
+/// Drops forks unless they have significant activity
+fn filter_forks(repos: Vec<RepositoryData>) -> Vec<RepositoryData> {
+    repos.into_iter()
+        .filter(|repo| !repo.is_fork || repo.stars > 5 || repo.forks > 2)
+        .collect()
+}
+
 // Public API function as shown in README
-pub async fn analyze_profile(profile: UserProfile, github_token: String) -> Result<SkillAnalysis> {
+pub async fn analyze_profile(profile: UserProfile, github_token: String) -> SourceResult<SkillAnalysis> {
     let mut analyzer = GitHubAnalyzer::new(github_token)?;
     analyzer.analyze(profile).await
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile_source::UserStats;
+    use chrono::TimeZone;
+    use std::sync::Mutex;
+
+    fn commit(sha: &str, login: &str, day: u32, message: &str) -> CommitRecord {
+        CommitRecord {
+            sha: sha.to_string(),
+            message: message.to_string(),
+            author_name: None,
+            author_email: None,
+            author_login: Some(login.to_string()),
+            date: Utc.with_ymd_and_hms(2024, 1, day, 12, 0, 0).unwrap(),
+            signed: None,
+        }
+    }
+
+    #[test]
+    fn share_counts_authored_and_co_authored_commits() {
+        let identity = AuthorIdentity::for_login("octocat");
+        let commits = vec![
+            commit("a", "octocat", 1, "feat: add parser"),
+            commit("b", "hubot", 2, "fix: typo\n\nCo-authored-by: Octo <octocat@users.noreply.github.com>"),
+            commit("c", "hubot", 3, "chore: bump"),
+            commit("d", "hubot", 4, "chore: bump"),
+        ];
+        let analysis = summarize_commits(&identity, &commits, None);
+        assert_eq!((analysis.total_commits, analysis.co_authored_commits, analysis.repository_commits), (2, 1, 4));
+        assert_eq!(analysis.authorship_share(), Some(0.5));
+        assert_eq!(analysis.last_commit_at, Some(commits[1].date));
+    }
+
+    #[test]
+    fn share_is_unknown_without_repository_commits() {
+        let analysis = summarize_commits(&AuthorIdentity::for_login("octocat"), &[], None);
+        assert_eq!(analysis.total_commits, 0);
+        assert_eq!(analysis.authorship_share(), None);
+    }
+
+    #[test]
+    fn capped_listing_is_completed_by_the_users_own_commits() {
+        let identity = AuthorIdentity::for_login("octocat");
+        // Newest four commits of a busy repository, one of them the user's
+        let listing = vec![
+            commit("a", "octocat", 20, "feat: a"),
+            commit("b", "hubot", 21, "chore: b"),
+            commit("c", "hubot", 22, "chore: c"),
+            commit("d", "hubot", 23, "chore: d"),
+        ];
+        // The user's commits by author reach back past the listing
+        let own = vec![commit("a", "octocat", 20, "feat: a"), commit("e", "octocat", 5, "feat: e"), commit("f", "octocat", 2, "feat: f")];
+
+        let analysis = summarize_commits(&identity, &listing, Some(&own));
+        assert_eq!(analysis.total_commits, 3);
+        // One in four over the listing's span, extrapolated to the user's three commits
+        assert_eq!(analysis.repository_commits, 12);
+        assert_eq!(analysis.authorship_share(), Some(0.25));
+    }
+
+    #[test]
+    fn capped_listing_without_recent_user_commits_counts_them_on_top() {
+        let identity = AuthorIdentity::for_login("octocat");
+        let listing = vec![commit("b", "hubot", 21, "chore: b"), commit("c", "hubot", 22, "chore: c")];
+        let own = vec![commit("e", "octocat", 5, "feat: e")];
+
+        let analysis = summarize_commits(&identity, &listing, Some(&own));
+        assert_eq!((analysis.total_commits, analysis.repository_commits), (1, 3));
+    }
+
+    /// Source serving three repositories (none when `empty`) and counting every request it answers
+    #[derive(Default)]
+    struct CountingSource {
+        requests: Mutex<u32>,
+        trees_read: Mutex<Vec<String>>,
+        empty: bool,
+        slow_tree: bool,
+        failed_listings: Mutex<u32>, // Listings still to fail
+        failing_reviews: bool,
+        listings: Mutex<u32>,
+    }
+
+    impl CountingSource {
+        fn request(&self) {
+            *self.requests.lock().unwrap() += 1;
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ProfileSource for CountingSource {
+        fn name(&self) -> &str {
+            "Counting"
+        }
+
+        async fn fetch_repositories(&self, username: &str) -> SourceResult<Vec<RepositoryData>> {
+            self.request();
+            *self.listings.lock().unwrap() += 1;
+            let mut failed_listings = self.failed_listings.lock().unwrap();
+            if *failed_listings > 0 {
+                *failed_listings -= 1;
+                return Err("502 Bad Gateway".into());
+            }
+            if self.empty {
+                return Ok(Vec::new());
+            }
+            Ok([("old", 300), ("recent", 2), ("newest", 1)].into_iter()
+                .map(|(name, days_ago)| RepositoryData {
+                    name: name.to_string(),
+                    owner: username.to_string(),
+                    primary_language: Some("Rust".to_string()),
+                    languages: Some(HashMap::from([("Rust".to_string(), 1000)])),
+                    // Whole days, so pushes stay put between runs
+                    pushed_at: Some(crate::clock::now().date_naive().and_time(chrono::NaiveTime::MIN).and_utc() - chrono::Duration::days(days_ago)),
+                    ..Default::default()
+                })
+                .collect())
+        }
+
+        async fn fetch_languages(&self, _repo: &RepositoryData) -> SourceResult<HashMap<String, u64>> {
+            self.request();
+            Ok(HashMap::new())
+        }
+
+        async fn fetch_commits(&self, _repo: &RepositoryData, _author: Option<&str>, _since: Option<DateTime<Utc>>) -> SourceResult<Vec<CommitRecord>> {
+            self.request();
+            Ok(Vec::new())
+        }
+
+        async fn fetch_user_stats(&self, _username: &str) -> SourceResult<UserStats> {
+            self.request();
+            Ok(UserStats::default())
+        }
+
+        async fn fetch_file_tree(&self, repo: &RepositoryData) -> SourceResult<Option<Vec<String>>> {
+            self.request();
+            self.trees_read.lock().unwrap().push(repo.name.clone());
+            if self.slow_tree {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+            Ok(Some(vec!["src/main.rs".to_string()]))
+        }
+
+        async fn fetch_review_activity(&self, _username: &str) -> SourceResult<Option<ReviewActivity>> {
+            if self.failing_reviews {
+                return Err("RATE_LIMITED".into());
+            }
+            Ok(None)
+        }
+
+        fn api_usage(&self) -> Option<ApiUsage> {
+            Some(ApiUsage { requests_made: *self.requests.lock().unwrap(), ..Default::default() })
+        }
+    }
+
+    #[tokio::test]
+    async fn only_the_most_recently_pushed_repositories_are_read_in_depth() {
+        let source = Arc::new(CountingSource::default());
+        let mut analyzer = GitHubAnalyzer::with_source(source.clone()).with_deep_repo_limit(2);
+        let profile = UserProfile { github_username: "octocat".to_string(), wallet_address: None };
+
+        let analysis = analyzer.analyze(profile).await.unwrap();
+        assert_eq!(analysis.repository_analysis.len(), 3);
+        let mut trees_read = source.trees_read.lock().unwrap().clone();
+        trees_read.sort();
+        assert_eq!(trees_read, vec!["newest", "recent"]);
+
+        let old = analysis.repository_analysis.iter().find(|r| r.name == "old").unwrap();
+        assert!(old.tree_inspection.is_none() && old.commit_analysis.is_none());
+        assert_eq!(analysis.api_requests, Some(*source.requests.lock().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn accounts_without_repositories_are_cached() {
+        let source = Arc::new(CountingSource { empty: true, ..Default::default() });
+        let mut analyzer = GitHubAnalyzer::with_source(source.clone());
+        let profile = UserProfile { github_username: "octocat".to_string(), wallet_address: None };
+
+        let first = analyzer.analyze(profile.clone()).await.unwrap();
+        let requests = *source.requests.lock().unwrap();
+        let second = analyzer.analyze(profile).await.unwrap();
+        assert_eq!(second.total_repositories, 0);
+        assert_eq!(second.analyzed_at, first.analyzed_at);
+        assert_eq!(*source.requests.lock().unwrap(), requests);
+    }
+
+    #[tokio::test]
+    async fn metadata_only_entries_are_analyzed_in_depth_later() {
+        let source = Arc::new(CountingSource::default());
+        let mut analyzer = GitHubAnalyzer::with_source(source.clone()).with_deep_repo_limit(1);
+        let profile = UserProfile { github_username: "octocat".to_string(), wallet_address: None };
+
+        // Nothing is pushed between runs, so only the metadata-only entries are re-analyzed
+        for _ in 0..3 {
+            analyzer.analyze_with_max_age(profile.clone(), chrono::Duration::zero()).await.unwrap();
+        }
+        assert_eq!(*source.trees_read.lock().unwrap(), vec!["newest", "recent", "old"]);
+
+        let analysis = analyzer.analyze_with_max_age(profile, chrono::Duration::zero()).await.unwrap();
+        assert!(analysis.repository_analysis.iter().all(|r| r.analyzed_in_depth));
+        assert_eq!(source.trees_read.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn failed_listings_are_not_cached() {
+        let source = Arc::new(CountingSource { empty: true, failed_listings: Mutex::new(1), ..Default::default() });
+        let mut analyzer = GitHubAnalyzer::with_source(source.clone());
+        let profile = UserProfile { github_username: "octocat".to_string(), wallet_address: None };
+
+        assert!(analyzer.analyze(profile.clone()).await.is_err());
+        assert_eq!(analyzer.analyze(profile.clone()).await.unwrap().total_repositories, 0);
+        assert_eq!(*source.listings.lock().unwrap(), 2);
+        analyzer.analyze(profile).await.unwrap();
+        assert_eq!(*source.listings.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn empty_accounts_with_failed_fetches_are_not_cached() {
+        let source = Arc::new(CountingSource { empty: true, failing_reviews: true, ..Default::default() });
+        let mut analyzer = GitHubAnalyzer::with_source(source.clone());
+        let profile = UserProfile { github_username: "octocat".to_string(), wallet_address: None };
+
+        analyzer.analyze(profile.clone()).await.unwrap();
+        analyzer.analyze(profile).await.unwrap();
+        assert_eq!(*source.listings.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn slow_repositories_fall_back_to_metadata() {
+        let source = Arc::new(CountingSource { slow_tree: true, ..Default::default() });
+        let mut analyzer = GitHubAnalyzer::with_source(source.clone()).with_repo_timeout(Duration::from_millis(50));
+        let profile = UserProfile { github_username: "octocat".to_string(), wallet_address: None };
+
+        let analysis = analyzer.analyze(profile).await.unwrap();
+        assert_eq!(analysis.repository_analysis.len(), 3);
+        assert!(analysis.repository_analysis.iter().all(|r| r.tree_inspection.is_none()));
+    }
+
+    #[test]
+    fn deep_repositories_prefer_recent_pushes() {
+        let repo = |name: &str, day: Option<u32>| RepositoryData {
+            name: name.to_string(),
+            pushed_at: day.map(|d| Utc.with_ymd_and_hms(2024, 1, d, 0, 0, 0).unwrap()),
+            ..Default::default()
+        };
+        let repos = vec![repo("never", None), repo("a", Some(1)), repo("b", Some(9)), repo("c", Some(5))];
+        assert_eq!(deep_repositories(&repos, 2), HashSet::from(["b", "c"]));
+        assert!(deep_repositories(&repos, 0).is_empty());
+        assert_eq!(deep_repositories(&repos, 10).len(), 4);
+    }
+
+    #[test]
+    fn fallback_languages_size_the_primary_language() {
+        let mut repo = RepositoryData { primary_language: Some("Rust".to_string()), size_kb: Some(12), ..Default::default() };
+        assert_eq!(fallback_languages(&repo), HashMap::from([("Rust".to_string(), 12 * 1024)]));
+
+        // A batched breakdown is used as is
+        repo.languages = Some(HashMap::from([("Rust".to_string(), 900), ("Python".to_string(), 100)]));
+        assert_eq!(fallback_languages(&repo).len(), 2);
+        assert!(fallback_languages(&RepositoryData::default()).is_empty());
+    }
+}
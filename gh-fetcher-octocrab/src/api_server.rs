@@ -2,34 +2,57 @@ use warp::{Filter, Reply};
 use serde_json;
 use std::sync::{Arc};
 use tokio::sync::Mutex;
+use warp::http::StatusCode;
 
+use crate::analysis_cache::is_valid_username;
 use crate::improved_analyzer::{ImprovedAnalyzer, AnalysisRequest, AnalysisDepth};
 use crate::streamlined_analyzer::StreamlinedAnalyzer;
+use crate::ownership::{GitHubProofClient, OwnershipVerifier};
+use crate::token_pool::TokenPool;
+use crate::wallet_binding::WalletBinder;
 
 pub struct ApiServer {
     analyzer: Arc<Mutex<ImprovedAnalyzer>>,
     streamlined_analyzer: Arc<Mutex<StreamlinedAnalyzer>>,
+    ownership_verifier: Arc<OwnershipVerifier>,
+    wallet_binder: Arc<WalletBinder>,
+    tokens: Arc<TokenPool>,
 }
 
 impl ApiServer {
-    pub fn new(github_token: String) -> Result<Self, Box<dyn std::error::Error>> {
-        let analyzer = ImprovedAnalyzer::new(github_token.clone())?;
-        let streamlined_analyzer = StreamlinedAnalyzer::new(github_token)?;
+    /// Both analyzers draw on the same pool, so one busy caller cannot drain a single token for everyone
+    pub fn new(tokens: Arc<TokenPool>) -> Result<Self, Box<dyn std::error::Error>> {
+        let analyzer = ImprovedAnalyzer::with_token_pool(tokens.clone());
+        // One cache for both, even when it falls back to memory; verifications and bindings are
+        // stored next to the analyses that report them
+        let streamlined_analyzer = StreamlinedAnalyzer::with_token_pool_and_cache(tokens.clone(), analyzer.cache().clone());
+        let mut proofs = GitHubProofClient::default();
+        if let Some(token) = tokens.personal_token() {
+            proofs = proofs.with_token(token);
+        }
+        let ownership_verifier = OwnershipVerifier::new(analyzer.cache().clone()).with_proof_client(proofs.clone());
+        let wallet_binder = WalletBinder::new(analyzer.cache().clone()).with_proof_client(proofs);
         Ok(Self {
             analyzer: Arc::new(Mutex::new(analyzer)),
             streamlined_analyzer: Arc::new(Mutex::new(streamlined_analyzer)),
+            ownership_verifier: Arc::new(ownership_verifier),
+            wallet_binder: Arc::new(wallet_binder),
+            tokens,
         })
     }
 
     pub async fn serve(self) {
         let analyzer = self.analyzer.clone();
         let streamlined_analyzer = self.streamlined_analyzer.clone();
+        let ownership_verifier = self.ownership_verifier.clone();
+        let wallet_binder = self.wallet_binder.clone();
+        let tokens = self.tokens.clone();
         
         // CORS headers
         let cors = warp::cors()
             .allow_any_origin()
-            .allow_headers(vec!["content-type"])
-            .allow_methods(vec!["GET", "POST", "OPTIONS"]);
+            .allow_headers(vec!["content-type", "authorization"])
+            .allow_methods(vec!["GET", "POST", "DELETE", "OPTIONS"]);
 
         // Health check endpoint
         let health = warp::path("health")
@@ -42,6 +65,71 @@ impl ApiServer {
                 }))
             });
 
+        // GitHub API budget per analyzer and per pooled token
+        let rate_limit = warp::path("rate-limit")
+            .and(warp::get())
+            .and(with_analyzer(analyzer.clone()))
+            .and(with_streamlined_analyzer(streamlined_analyzer.clone()))
+            .and(with_token_pool(tokens))
+            .and_then(handle_rate_limit);
+
+        // Inspect and purge a user's cached analyses and API responses
+        let cache_inspect = warp::path!("cache" / String)
+            .and(warp::get())
+            .and(with_analyzer(analyzer.clone()))
+            .and_then(handle_cache_inspect);
+
+        // Purging needs the admin token; without IDENZERO_ADMIN_TOKEN it is disabled
+        let admin_token = std::env::var("IDENZERO_ADMIN_TOKEN").ok().filter(|token| !token.trim().is_empty());
+        let cache_purge = warp::path!("cache" / String)
+            .and(warp::delete())
+            .and(with_admin(admin_token.clone()))
+            .and(with_analyzer(analyzer.clone()))
+            .and_then(handle_cache_purge);
+
+        // Account ownership: publish a challenge token in the bio or a gist, then ask for the check
+        let ownership_challenge = warp::path!("ownership" / "challenge")
+            .and(warp::post())
+            .and(warp::body::json())
+            .and(with_ownership_verifier(ownership_verifier.clone()))
+            .and_then(handle_ownership_challenge);
+
+        let ownership_verify = warp::path!("ownership" / "verify")
+            .and(warp::post())
+            .and(warp::body::json())
+            .and(with_ownership_verifier(ownership_verifier.clone()))
+            .and_then(handle_ownership_verify);
+
+        let ownership_status = warp::path!("ownership" / String)
+            .and(warp::get())
+            .and(with_ownership_verifier(ownership_verifier.clone()))
+            .and_then(handle_ownership_status);
+
+        // Wallet binding: sign a challenge naming the GitHub account, then submit the signature
+        let wallet_challenge = warp::path!("wallet" / "challenge")
+            .and(warp::post())
+            .and(warp::body::json())
+            .and(with_wallet_binder(wallet_binder.clone()))
+            .and_then(handle_wallet_challenge);
+
+        let wallet_verify = warp::path!("wallet" / "verify")
+            .and(warp::post())
+            .and(warp::body::json())
+            .and(with_wallet_binder(wallet_binder.clone()))
+            .and_then(handle_wallet_verify);
+
+        let wallet_binding = warp::path!("wallet" / String)
+            .and(warp::get())
+            .and(with_wallet_binder(wallet_binder.clone()))
+            .and_then(handle_wallet_binding);
+
+        // Unbinding skips the old wallet's signature, for lost keys, so it is admin only like purging
+        let wallet_unbind = warp::path!("wallet" / String)
+            .and(warp::delete())
+            .and(with_admin(admin_token))
+            .and(with_wallet_binder(wallet_binder.clone()))
+            .and_then(handle_wallet_unbind);
+
         // Streamlined profile endpoint - NEW
         let streamlined = warp::path!("streamlined" / String)
             .and(warp::get())
@@ -79,14 +167,20 @@ impl ApiServer {
             .and(warp::fs::dir("../demo-ui"));
 
         let api = warp::path("api")
-            .and(health.or(streamlined).or(analyze).or(quick_analyze).or(frontend_profile).or(compare))
+            .and(health.or(rate_limit).or(cache_inspect).or(cache_purge).or(streamlined).or(analyze).or(quick_analyze).or(frontend_profile).or(compare)
+                .or(ownership_challenge).or(ownership_verify).or(ownership_status)
+                .or(wallet_challenge).or(wallet_verify).or(wallet_binding).or(wallet_unbind))
             .with(cors.clone());
 
         let routes = api.or(demo).with(cors);
 
         tracing::info!("IdenZero API Server starting on http://localhost:3030");
         tracing::info!("Health endpoint available at: /api/health");
+        tracing::info!("GitHub rate limit usage available at: /api/rate-limit");
+        tracing::info!("Cache endpoints available at: GET/DELETE /api/cache/<username>");
         tracing::info!("Profile endpoints available at: /api/streamlined/<username>, /api/quick/<username>, /api/profile/<username>");
+        tracing::info!("Ownership endpoints available at: POST /api/ownership/challenge, POST /api/ownership/verify, GET /api/ownership/<username>");
+        tracing::info!("Wallet binding endpoints available at: POST /api/wallet/challenge, POST /api/wallet/verify, GET/DELETE /api/wallet/<username>");
         
         warp::serve(routes)
             .run(([0, 0, 0, 0], 3030))
@@ -102,6 +196,139 @@ fn with_streamlined_analyzer(analyzer: Arc<Mutex<StreamlinedAnalyzer>>) -> impl
     warp::any().map(move || analyzer.clone())
 }
 
+fn with_token_pool(tokens: Arc<TokenPool>) -> impl Filter<Extract = (Arc<TokenPool>,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || tokens.clone())
+}
+
+fn with_ownership_verifier(verifier: Arc<OwnershipVerifier>) -> impl Filter<Extract = (Arc<OwnershipVerifier>,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || verifier.clone())
+}
+
+/// The request's `Authorization` header and the configured admin token, if any
+fn with_admin(admin_token: Option<String>) -> impl Filter<Extract = (Option<String>, Option<String>), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("authorization")
+        .and(warp::any().map(move || admin_token.clone()))
+}
+
+fn with_wallet_binder(binder: Arc<WalletBinder>) -> impl Filter<Extract = (Arc<WalletBinder>,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || binder.clone())
+}
+
+async fn handle_ownership_challenge(
+    request: OwnershipChallengeRequest,
+    verifier: Arc<OwnershipVerifier>,
+) -> Result<impl Reply, warp::Rejection> {
+    match verifier.issue_challenge(&request.username) {
+        Ok(challenge) => Ok(warp::reply::json(&serde_json::json!({
+            "success": true,
+            "challenge": challenge
+        }))),
+        Err(e) => Ok(warp::reply::json(&serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        })))
+    }
+}
+
+async fn handle_ownership_verify(
+    request: OwnershipVerifyRequest,
+    verifier: Arc<OwnershipVerifier>,
+) -> Result<impl Reply, warp::Rejection> {
+    match verifier.verify(&request.username, request.gist_url.as_deref()).await {
+        Ok(verification) => Ok(warp::reply::json(&serde_json::json!({
+            "success": true,
+            "verification": verification
+        }))),
+        Err(e) => Ok(warp::reply::json(&serde_json::json!({
+            "success": false,
+            "error": format!("Ownership verification failed: {}", e)
+        })))
+    }
+}
+
+async fn handle_ownership_status(
+    username: String,
+    verifier: Arc<OwnershipVerifier>,
+) -> Result<impl Reply, warp::Rejection> {
+    let verification = verifier.verification(&username);
+    Ok(warp::reply::json(&serde_json::json!({
+        "username": username,
+        "verified_owner": verification.is_some(),
+        "verification": verification
+    })))
+}
+
+async fn handle_wallet_challenge(
+    request: WalletChallengeRequest,
+    binder: Arc<WalletBinder>,
+) -> Result<impl Reply, warp::Rejection> {
+    match binder.issue_challenge(&request.username, &request.wallet_address) {
+        Ok(challenge) => Ok(warp::reply::json(&serde_json::json!({
+            "success": true,
+            "challenge": challenge
+        }))),
+        Err(e) => Ok(warp::reply::json(&serde_json::json!({
+            "success": false,
+            "error": e.to_string()
+        })))
+    }
+}
+
+async fn handle_wallet_verify(
+    request: WalletVerifyRequest,
+    binder: Arc<WalletBinder>,
+) -> Result<impl Reply, warp::Rejection> {
+    match binder.verify(&request.nonce, &request.signature, &request.gist_url, request.previous_signature.as_deref()).await {
+        Ok(binding) => Ok(warp::reply::json(&serde_json::json!({
+            "success": true,
+            "binding": binding
+        }))),
+        Err(e) => Ok(warp::reply::json(&serde_json::json!({
+            "success": false,
+            "error": format!("Wallet binding failed: {}", e)
+        })))
+    }
+}
+
+async fn handle_wallet_binding(
+    username: String,
+    binder: Arc<WalletBinder>,
+) -> Result<impl Reply, warp::Rejection> {
+    let binding = binder.binding(&username);
+    Ok(warp::reply::json(&serde_json::json!({
+        "username": username,
+        "bound": binding.is_some(),
+        "binding": binding
+    })))
+}
+
+async fn handle_wallet_unbind(
+    username: String,
+    authorization: Option<String>,
+    admin_token: Option<String>,
+    binder: Arc<WalletBinder>,
+) -> Result<impl Reply, warp::Rejection> {
+    let reply = |status: StatusCode, body: serde_json::Value| Ok(warp::reply::with_status(warp::reply::json(&body), status));
+    if let Err((status, error)) = check_admin(authorization, admin_token, "wallet unbinding") {
+        return reply(status, serde_json::json!({ "success": false, "error": error }));
+    }
+    if !is_valid_username(&username) {
+        return reply(StatusCode::BAD_REQUEST, serde_json::json!({ "success": false, "error": format!("{} is not a GitHub username", username) }));
+    }
+
+    match binder.unbind(&username) {
+        Ok(removed) => reply(StatusCode::OK, serde_json::json!({
+            "success": true,
+            "username": username,
+            "removed": removed
+        })),
+        Err(e) => reply(StatusCode::INTERNAL_SERVER_ERROR, serde_json::json!({
+            "success": false,
+            "error": format!("Wallet unbinding failed: {}", e)
+        })),
+    }
+}
+
 async fn handle_streamlined_profile(
     username: String,
     analyzer: Arc<Mutex<StreamlinedAnalyzer>>,
@@ -123,6 +350,85 @@ async fn handle_streamlined_profile(
     }
 }
 
+async fn handle_rate_limit(
+    analyzer: Arc<Mutex<ImprovedAnalyzer>>,
+    streamlined_analyzer: Arc<Mutex<StreamlinedAnalyzer>>,
+    tokens: Arc<TokenPool>,
+) -> Result<impl Reply, warp::Rejection> {
+    // Each analyzer counts its own requests against the budget of the pool they share; `tokens` breaks it down
+    let analyzer_usage = analyzer.lock().await.api_usage();
+    let streamlined_usage = streamlined_analyzer.lock().await.api_usage();
+    let pool_usage = tokens.usage();
+
+    Ok(warp::reply::json(&serde_json::json!({
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "requests_made": pool_usage.requests_made,
+        "rate_limit_remaining": pool_usage.rate_limit_remaining(),
+        "analyzer": analyzer_usage,
+        "streamlined": streamlined_usage,
+        "tokens": tokens.status(),
+    })))
+}
+
+async fn handle_cache_inspect(
+    username: String,
+    analyzer: Arc<Mutex<ImprovedAnalyzer>>,
+) -> Result<impl Reply, warp::Rejection> {
+    if !is_valid_username(&username) {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "success": false, "error": format!("{} is not a GitHub username", username) })),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    let cache = analyzer.lock().await.cache().clone();
+    Ok(warp::reply::with_status(warp::reply::json(&cache.entries(&username)), StatusCode::OK))
+}
+
+async fn handle_cache_purge(
+    username: String,
+    authorization: Option<String>,
+    admin_token: Option<String>,
+    analyzer: Arc<Mutex<ImprovedAnalyzer>>,
+) -> Result<impl Reply, warp::Rejection> {
+    let refuse = |status: StatusCode, error: String| {
+        Ok(warp::reply::with_status(warp::reply::json(&serde_json::json!({ "success": false, "error": error })), status))
+    };
+    if let Err((status, error)) = check_admin(authorization, admin_token, "cache purge") {
+        return refuse(status, error);
+    }
+    if !is_valid_username(&username) {
+        return refuse(StatusCode::BAD_REQUEST, format!("{} is not a GitHub username", username));
+    }
+
+    // The streamlined analyzer shares the same cache, so one purge covers both
+    let cache = analyzer.lock().await.cache().clone();
+    let purged = cache.purge(&username);
+    tracing::info!("Purged cache for {}: {:?}", username, purged);
+
+    Ok(warp::reply::with_status(warp::reply::json(&serde_json::json!({
+        "success": true,
+        "username": username,
+        "purged": purged,
+    })), StatusCode::OK))
+}
+
+/// Accepts only `Authorization: Bearer <IDENZERO_ADMIN_TOKEN>`; `action` is disabled while the token is unset
+fn check_admin(authorization: Option<String>, admin_token: Option<String>, action: &str) -> Result<(), (StatusCode, String)> {
+    let Some(admin_token) = admin_token else {
+        return Err((StatusCode::FORBIDDEN, format!("{} is disabled; set IDENZERO_ADMIN_TOKEN to enable it", action)));
+    };
+    let presented = authorization.as_deref().and_then(|value| value.strip_prefix("Bearer ")).unwrap_or_default();
+    if !constant_time_eq(presented.trim().as_bytes(), admin_token.trim().as_bytes()) {
+        return Err((StatusCode::UNAUTHORIZED, "admin token required".to_string()));
+    }
+    Ok(())
+}
+
+/// Compares secrets without returning early on the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
 async fn handle_analyze(
     request: AnalysisRequest,
     analyzer: Arc<Mutex<ImprovedAnalyzer>>,
@@ -204,6 +510,31 @@ struct CompareRequest {
     usernames: Vec<String>,
 }
 
+#[derive(serde::Deserialize)]
+struct OwnershipChallengeRequest {
+    username: String,
+}
+
+#[derive(serde::Deserialize)]
+struct OwnershipVerifyRequest {
+    username: String,
+    gist_url: Option<String>, // Checked instead of the bio when given
+}
+
+#[derive(serde::Deserialize)]
+struct WalletChallengeRequest {
+    username: String,
+    wallet_address: String,
+}
+
+#[derive(serde::Deserialize)]
+struct WalletVerifyRequest {
+    nonce: String,
+    signature: String, // 0x-prefixed r||s||v from `personal_sign`
+    gist_url: String, // Gist owned by the account that contains the signature
+    previous_signature: Option<String>, // The same message signed by the currently bound wallet, to replace it
+}
+
 // Example usage functions for testing
 pub fn example_requests() -> Vec<AnalysisRequest> {
     vec![
@@ -234,4 +565,13 @@ pub fn example_requests() -> Vec<AnalysisRequest> {
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn admin_actions_need_the_configured_token() {
+        let token = Some("s3cret".to_string());
+        assert!(check_admin(Some("Bearer s3cret".into()), token.clone(), "wallet unbinding").is_ok());
+        assert_eq!(check_admin(Some("Bearer guess".into()), token.clone(), "wallet unbinding").unwrap_err().0, StatusCode::UNAUTHORIZED);
+        assert_eq!(check_admin(Some("s3cret".into()), token, "wallet unbinding").unwrap_err().0, StatusCode::UNAUTHORIZED);
+        assert_eq!(check_admin(Some("Bearer s3cret".into()), None, "wallet unbinding").unwrap_err().0, StatusCode::FORBIDDEN);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,221 @@
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tokio::time::{sleep, Duration};
+use warp::http::HeaderMap;
+
+/// Below this many remaining requests we pause until the window resets
+const PAUSE_THRESHOLD: u32 = 50;
+
+/// Below this share of the window we start spacing requests out
+const SLOWDOWN_FRACTION: f64 = 0.1;
+
+/// Longest single pause we are willing to take while waiting for a reset
+pub const MAX_RATE_LIMIT_WAIT_SECS: i64 = 300;
+
+/// Latest view of one GitHub rate-limit bucket (`core`, `graphql`, `search`, ...)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RateLimitWindow {
+    pub limit: u32,
+    pub remaining: u32,
+    pub used: u32,
+    pub reset_at: DateTime<Utc>,
+    pub observed_at: DateTime<Utc>,
+}
+
+/// Requests made through a source and the rate-limit state its responses reported
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ApiUsage {
+    pub requests_made: u32,
+    pub rate_limits: BTreeMap<String, RateLimitWindow>,
+}
+
+impl ApiUsage {
+    /// Tightest remaining budget across every bucket seen so far
+    pub fn rate_limit_remaining(&self) -> Option<u32> {
+        self.rate_limits.values().map(|w| w.remaining).min()
+    }
+
+    /// When the bucket with the tightest remaining budget resets
+    pub fn rate_limit_reset(&self) -> Option<DateTime<Utc>> {
+        self.rate_limits.values()
+            .min_by_key(|w| w.remaining)
+            .map(|w| w.reset_at)
+    }
+}
+
+/// Thread-safe counter for requests and rate-limit headers, shared by every
+/// request path of a source
+#[derive(Debug, Default)]
+pub struct ApiUsageTracker {
+    usage: Mutex<ApiUsage>,
+    // Held while backing off so concurrent requests queue up instead of all
+    // firing once their individual delays elapse
+    pacing: tokio::sync::Mutex<()>,
+}
+
+impl ApiUsageTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn snapshot(&self) -> ApiUsage {
+        self.usage.lock().map(|u| u.clone()).unwrap_or_default()
+    }
+
+    pub fn record_request(&self) {
+        if let Ok(mut usage) = self.usage.lock() {
+            usage.requests_made += 1;
+        }
+    }
+
+    /// Reads GitHub's `x-ratelimit-*` headers; responses without them are ignored
+    pub fn record_headers(&self, headers: &HeaderMap) {
+        let header = |name: &str| -> Option<u64> {
+            headers.get(name)?.to_str().ok()?.trim().parse().ok()
+        };
+
+        let (Some(limit), Some(remaining)) = (header("x-ratelimit-limit"), header("x-ratelimit-remaining")) else {
+            return;
+        };
+        let resource = headers.get("x-ratelimit-resource")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("core")
+            .to_string();
+        let now = crate::clock::now();
+        let reset_at = header("x-ratelimit-reset")
+            .and_then(|secs| Utc.timestamp_opt(secs as i64, 0).single())
+            .unwrap_or(now);
+
+        let window = RateLimitWindow {
+            limit: limit as u32,
+            remaining: remaining as u32,
+            used: header("x-ratelimit-used").map(|u| u as u32).unwrap_or(limit.saturating_sub(remaining) as u32),
+            reset_at,
+            observed_at: now,
+        };
+
+        if let Ok(mut usage) = self.usage.lock() {
+            usage.rate_limits.insert(resource, window);
+        }
+    }
+
+    /// How long to hold off before the next request given the last known budget
+    pub fn backoff_delay(&self, resource: &str) -> Option<Duration> {
+        let window = self.snapshot().rate_limits.get(resource)?.clone();
+        let secs_to_reset = (window.reset_at - Utc::now()).num_seconds();
+        if secs_to_reset <= 0 {
+            return None;
+        }
+
+        if window.remaining <= PAUSE_THRESHOLD {
+            // Nearly exhausted: wait for the window to reset
+            return Some(Duration::from_secs(secs_to_reset.min(MAX_RATE_LIMIT_WAIT_SECS) as u64));
+        }
+        if is_low(&window) {
+            // Running low: spread what is left over the rest of the window
+            let spacing_ms = (secs_to_reset * 1000) / window.remaining as i64;
+            return Some(Duration::from_millis(spacing_ms.clamp(100, 5000) as u64));
+        }
+        None
+    }
+
+    /// Sleeps as long as `backoff_delay` asks for; concurrent callers are paced one at a time
+    pub async fn throttle(&self, resource: &str) {
+        if self.backoff_delay(resource).is_none() {
+            return;
+        }
+
+        let _pacing = self.pacing.lock().await;
+        // Re-check: the budget may have reset while we queued
+        if let Some(delay) = self.backoff_delay(resource) {
+            if delay.as_secs() >= 1 {
+                tracing::warn!("GitHub {} rate limit low, pausing {}s", resource, delay.as_secs());
+            }
+            sleep(delay).await;
+        }
+    }
+}
+
+fn is_low(window: &RateLimitWindow) -> bool {
+    (window.remaining as f64) < window.limit as f64 * SLOWDOWN_FRACTION
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use warp::http::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, String)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    fn rate_limit(resource: &str, limit: u32, remaining: u32, reset_in_secs: i64) -> HeaderMap {
+        headers(&[
+            ("x-ratelimit-limit", limit.to_string()),
+            ("x-ratelimit-remaining", remaining.to_string()),
+            ("x-ratelimit-reset", (Utc::now().timestamp() + reset_in_secs).to_string()),
+            ("x-ratelimit-resource", resource.to_string()),
+        ])
+    }
+
+    #[test]
+    fn records_each_bucket_from_response_headers() {
+        let tracker = ApiUsageTracker::new();
+        tracker.record_request();
+        tracker.record_request();
+        tracker.record_headers(&rate_limit("core", 5000, 4990, 600));
+        tracker.record_headers(&rate_limit("graphql", 5000, 120, 60));
+        tracker.record_headers(&headers(&[("x-ratelimit-limit", "60".to_string())])); // Incomplete: ignored
+
+        let usage = tracker.snapshot();
+        assert_eq!(usage.requests_made, 2);
+        assert_eq!(usage.rate_limits.keys().collect::<Vec<_>>(), ["core", "graphql"]);
+        // Without `x-ratelimit-used` it is derived from the other two
+        assert_eq!(usage.rate_limits["core"].used, 10);
+        assert_eq!(usage.rate_limit_remaining(), Some(120));
+        assert_eq!(usage.rate_limit_reset(), Some(usage.rate_limits["graphql"].reset_at));
+    }
+
+    #[test]
+    fn later_headers_replace_the_bucket_and_default_to_core() {
+        let tracker = ApiUsageTracker::new();
+        tracker.record_headers(&rate_limit("search", 30, 29, 60));
+        let mut update = rate_limit("search", 30, 12, 60);
+        update.insert("x-ratelimit-used", HeaderValue::from_static("18"));
+        tracker.record_headers(&update);
+        tracker.record_headers(&headers(&[("x-ratelimit-limit", "60".to_string()), ("x-ratelimit-remaining", "59".to_string())]));
+
+        let usage = tracker.snapshot();
+        assert_eq!((usage.rate_limits["search"].remaining, usage.rate_limits["search"].used), (12, 18));
+        assert_eq!(usage.rate_limits["core"].limit, 60);
+        assert_eq!(ApiUsage::default().rate_limit_remaining(), None);
+    }
+
+    #[test]
+    fn backs_off_only_when_the_budget_runs_low() {
+        let tracker = ApiUsageTracker::new();
+        assert_eq!(tracker.backoff_delay("core"), None);
+
+        tracker.record_headers(&rate_limit("core", 5000, 4000, 600));
+        assert_eq!(tracker.backoff_delay("core"), None);
+
+        // Under 10% left: the rest of the window is spread over the remaining requests
+        tracker.record_headers(&rate_limit("core", 5000, 400, 600));
+        let spacing = tracker.backoff_delay("core").unwrap();
+        assert!(spacing >= Duration::from_millis(1000) && spacing <= Duration::from_millis(1500));
+
+        // Nearly exhausted: wait for the reset, capped
+        tracker.record_headers(&rate_limit("core", 5000, 10, 3600));
+        assert_eq!(tracker.backoff_delay("core"), Some(Duration::from_secs(MAX_RATE_LIMIT_WAIT_SECS as u64)));
+
+        // A window that already reset does not hold anything up
+        tracker.record_headers(&rate_limit("core", 5000, 0, -5));
+        assert_eq!(tracker.backoff_delay("core"), None);
+    }
+}
@@ -0,0 +1,189 @@
+use crate::profile_source::CommitRecord;
+
+/// How a commit relates to the analyzed person
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Authorship {
+    Author,
+    CoAuthor, // Named in a `Co-authored-by:` trailer
+    Other,
+}
+
+/// Everything that identifies the analyzed person in commit metadata.
+/// Emails and names are stored lowercased.
+#[derive(Debug, Clone, Default)]
+pub struct AuthorIdentity {
+    pub login: Option<String>,
+    pub emails: Vec<String>,
+    pub names: Vec<String>,
+    pub match_all: bool, // Sources with no way to tell authors apart credit every commit
+}
+
+impl AuthorIdentity {
+    /// Forge account; its noreply addresses are recognised from the login alone
+    pub fn for_login(login: &str) -> Self {
+        Self {
+            login: Some(login.to_lowercase()),
+            ..Self::default()
+        }
+    }
+
+    /// Credits every commit to the person
+    pub fn anyone() -> Self {
+        Self {
+            match_all: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_emails<I, S>(mut self, emails: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        push_unique(&mut self.emails, emails);
+        self
+    }
+
+    pub fn with_names<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        push_unique(&mut self.names, names);
+        self
+    }
+
+    pub fn classify(&self, commit: &CommitRecord) -> Authorship {
+        if self.match_all {
+            return Authorship::Author;
+        }
+
+        let login_matches = matches!((&self.login, &commit.author_login), (Some(login), Some(author)) if author.eq_ignore_ascii_case(login));
+        if login_matches
+            || commit.author_email.as_deref().is_some_and(|email| self.matches_email(email))
+            || commit.author_name.as_deref().is_some_and(|name| self.matches_name(name))
+        {
+            return Authorship::Author;
+        }
+
+        if co_authors(&commit.message).iter().any(|(name, email)| self.matches_email(email) || self.matches_name(name)) {
+            return Authorship::CoAuthor;
+        }
+        Authorship::Other
+    }
+
+    pub fn matches_email(&self, email: &str) -> bool {
+        let email = email.trim().to_lowercase();
+        !email.is_empty() && (self.emails.contains(&email) || self.is_own_noreply(&email))
+    }
+
+    fn matches_name(&self, name: &str) -> bool {
+        let name = name.trim().to_lowercase();
+        !name.is_empty() && self.names.contains(&name)
+    }
+
+    /// `login@`, `id+login@` (GitHub) or `id-login@` (GitLab) at a `users.noreply.*` domain
+    fn is_own_noreply(&self, email: &str) -> bool {
+        let Some(login) = &self.login else { return false };
+        let Some((local, domain)) = email.rsplit_once('@') else { return false };
+        if !domain.starts_with("users.noreply.") {
+            return false;
+        }
+
+        local == login || local.strip_suffix(login.as_str())
+            .and_then(|prefix| prefix.strip_suffix(['+', '-']))
+            .is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+    }
+}
+
+/// `(name, email)` of every `Co-authored-by: Name <email>` trailer in a commit message
+pub fn co_authors(message: &str) -> Vec<(String, String)> {
+    message.lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim().split_once(':')?;
+            if !key.trim().eq_ignore_ascii_case("co-authored-by") {
+                return None;
+            }
+            let (name, rest) = value.split_once('<')?;
+            let (email, _) = rest.split_once('>')?;
+            Some((name.trim().to_string(), email.trim().to_lowercase()))
+        })
+        .collect()
+}
+
+fn push_unique<I, S>(values: &mut Vec<String>, new: I)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    for value in new {
+        let value = value.as_ref().trim().to_lowercase();
+        if !value.is_empty() && !values.contains(&value) {
+            values.push(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn commit(login: Option<&str>, name: &str, email: &str, message: &str) -> CommitRecord {
+        CommitRecord {
+            sha: String::new(),
+            message: message.to_string(),
+            author_name: Some(name.to_string()),
+            author_email: Some(email.to_string()),
+            author_login: login.map(|l| l.to_string()),
+            date: Utc::now(),
+            signed: None,
+        }
+    }
+
+    #[test]
+    fn authors_match_by_login_email_or_name() {
+        let identity = AuthorIdentity::for_login("Octocat").with_emails(["Mona@Example.com"]).with_names(["Mona Lisa"]);
+        assert_eq!(identity.classify(&commit(Some("octocat"), "x", "x@example.com", "fix")), Authorship::Author);
+        assert_eq!(identity.classify(&commit(None, "x", " mona@example.com", "fix")), Authorship::Author);
+        assert_eq!(identity.classify(&commit(None, "mona lisa", "x@example.com", "fix")), Authorship::Author);
+        assert_eq!(identity.classify(&commit(Some("hubot"), "Hubot", "hubot@example.com", "fix")), Authorship::Other);
+    }
+
+    #[test]
+    fn own_noreply_addresses_match() {
+        let identity = AuthorIdentity::for_login("octocat");
+        assert!(identity.matches_email("octocat@users.noreply.github.com"));
+        assert!(identity.matches_email("583231+octocat@users.noreply.github.com"));
+        assert!(identity.matches_email("42-octocat@users.noreply.gitlab.com"));
+        assert!(!identity.matches_email("583231+notoctocat@users.noreply.github.com"));
+        assert!(!identity.matches_email("abc+octocat@users.noreply.github.com"));
+        assert!(!identity.matches_email("octocat@example.com"));
+        assert!(!AuthorIdentity::default().matches_email(""));
+    }
+
+    #[test]
+    fn co_author_trailers_credit_the_user() {
+        let identity = AuthorIdentity::for_login("octocat").with_names(["Mona Lisa"]);
+        let pairing = "Pair on parser\n\nco-authored-by: Someone <a@example.com>\nCo-Authored-By: Octo Cat <1+octocat@users.noreply.github.com>";
+        assert_eq!(identity.classify(&commit(Some("hubot"), "Hubot", "h@example.com", pairing)), Authorship::CoAuthor);
+
+        let by_name = "Fix\n\nCo-authored-by: Mona Lisa <mona@elsewhere.dev>";
+        assert_eq!(identity.classify(&commit(None, "Hubot", "h@example.com", by_name)), Authorship::CoAuthor);
+
+        // Trailers naming someone else, or mentioned mid-sentence, do not count
+        let other = "Fix\n\nCo-authored-by: Hubot <hubot@example.com>\nThanks octocat@users.noreply.github.com";
+        assert_eq!(identity.classify(&commit(None, "Hubot", "h@example.com", other)), Authorship::Other);
+    }
+
+    #[test]
+    fn co_authors_parse_name_and_lowercased_email() {
+        let message = "Subject\n\n  Co-authored-by: Mona Lisa <Mona@Example.com>\nCo-authored-by: missing brackets\n";
+        assert_eq!(co_authors(message), vec![("Mona Lisa".to_string(), "mona@example.com".to_string())]);
+    }
+
+    #[test]
+    fn anyone_credits_every_commit() {
+        assert_eq!(AuthorIdentity::anyone().classify(&commit(None, "x", "x@example.com", "fix")), Authorship::Author);
+    }
+}
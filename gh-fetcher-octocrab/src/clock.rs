@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use std::sync::RwLock;
+
+/// Time every analyzer reads instead of `Utc::now()`. Normally wall-clock time;
+/// pinned while recording or replaying fixtures so time-dependent scores
+/// (years active, recent activity, timestamps) come out identical run to run.
+static PINNED_NOW: RwLock<Option<DateTime<Utc>>> = RwLock::new(None);
+
+pub fn now() -> DateTime<Utc> {
+    PINNED_NOW.read()
+        .ok()
+        .and_then(|pinned| *pinned)
+        .unwrap_or_else(Utc::now)
+}
+
+pub fn pin(at: DateTime<Utc>) {
+    if let Ok(mut pinned) = PINNED_NOW.write() {
+        *pinned = Some(at);
+    }
+}
+
+pub fn unpin() {
+    if let Ok(mut pinned) = PINNED_NOW.write() {
+        *pinned = None;
+    }
+}
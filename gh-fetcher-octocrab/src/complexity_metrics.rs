@@ -0,0 +1,511 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::local_source::{is_vendored_path, language_for_path};
+use crate::tree_inspection::is_test_path;
+
+/// Source files read per repository for complexity sampling
+pub const MAX_SAMPLED_FILES_PER_REPO: usize = 4;
+
+/// Languages sampled per repository, largest first
+pub const SAMPLED_LANGUAGES_PER_REPO: usize = 2;
+
+/// Larger files are usually generated or vendored and would dominate the sample
+const MAX_SAMPLED_FILE_BYTES: usize = 200_000;
+
+/// Functions past these limits count towards technical debt
+const DEBT_CYCLOMATIC: u32 = 10;
+const DEBT_COGNITIVE: u32 = 15;
+const DEBT_LINES: u32 = 80;
+
+/// Per-function complexity averaged over sampled source files
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ComplexityMetrics {
+    pub cyclomatic_complexity: f64, // Mean independent paths per function
+    pub cognitive_complexity: f64, // Mean nesting-weighted branching per function
+    pub maintainability_index: f64, // 0-100, from Halstead volume, cyclomatic complexity and length
+    pub technical_debt_ratio: f64, // Share of function lines in overly complex or long functions
+    pub files_sampled: u32,
+    pub functions: u32,
+    pub lines_of_code: u32, // Non-blank code lines in sampled functions
+}
+
+impl ComplexityMetrics {
+    /// Metrics of several samples, weighted by function count (debt by lines)
+    pub fn combine<'a>(parts: impl IntoIterator<Item = &'a ComplexityMetrics>) -> Option<Self> {
+        let parts: Vec<&ComplexityMetrics> = parts.into_iter().filter(|m| m.functions > 0).collect();
+        let functions: u32 = parts.iter().map(|m| m.functions).sum();
+        let lines: u32 = parts.iter().map(|m| m.lines_of_code).sum();
+        if functions == 0 {
+            return None;
+        }
+
+        let by_functions = |value: fn(&ComplexityMetrics) -> f64| {
+            parts.iter().map(|m| value(m) * m.functions as f64).sum::<f64>() / functions as f64
+        };
+        Some(Self {
+            cyclomatic_complexity: by_functions(|m| m.cyclomatic_complexity),
+            cognitive_complexity: by_functions(|m| m.cognitive_complexity),
+            maintainability_index: by_functions(|m| m.maintainability_index),
+            technical_debt_ratio: if lines > 0 {
+                parts.iter().map(|m| m.technical_debt_ratio * m.lines_of_code as f64).sum::<f64>() / lines as f64
+            } else {
+                0.0
+            },
+            files_sampled: parts.iter().map(|m| m.files_sampled).sum(),
+            functions,
+            lines_of_code: lines,
+        })
+    }
+
+    /// 0-100: rewards non-trivial logic that stays maintainable
+    pub fn score(&self) -> f64 {
+        // Branching up to ~5 paths per function reads as real logic; far beyond it as tangled code
+        let cc = self.cyclomatic_complexity;
+        let substance = if cc <= 5.0 { ((cc - 1.0) / 4.0).max(0.0) * 40.0 } else { (40.0 - (cc - 5.0) * 4.0).max(10.0) };
+        let evidence = ((self.functions as f64).ln_1p() / 200f64.ln() * 20.0).min(20.0);
+        let maintainability = self.maintainability_index / 100.0 * 30.0;
+        let debt = (1.0 - self.technical_debt_ratio).clamp(0.0, 1.0) * 10.0;
+
+        (substance + evidence + maintainability + debt).min(100.0)
+    }
+}
+
+/// Comment and nesting conventions the sampler understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Syntax {
+    Braces,
+    Python,
+    Ruby,
+}
+
+fn syntax_for(language: &str) -> Option<Syntax> {
+    match language {
+        "Rust" | "Go" | "TypeScript" | "JavaScript" | "Java" | "Kotlin" | "Scala" | "C" | "C++" | "C#" | "PHP"
+        | "Swift" | "Solidity" | "Dart" | "Move" | "Cairo" => Some(Syntax::Braces),
+        "Python" | "Vyper" => Some(Syntax::Python),
+        "Ruby" => Some(Syntax::Ruby),
+        _ => None,
+    }
+}
+
+/// Source files spread evenly over the tree in the repository's largest supported languages
+pub fn sample_paths(file_tree: &[String], languages: &HashMap<String, u64>) -> Vec<(String, &'static str)> {
+    let mut ranked: Vec<(&String, &u64)> = languages.iter().filter(|(l, _)| syntax_for(l).is_some()).collect();
+    ranked.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    ranked.truncate(SAMPLED_LANGUAGES_PER_REPO);
+    if ranked.is_empty() {
+        return Vec::new();
+    }
+    let per_language = MAX_SAMPLED_FILES_PER_REPO / ranked.len();
+
+    let mut sample = Vec::new();
+    for (language, _) in ranked {
+        let mut candidates: Vec<(&String, &'static str)> = file_tree.iter()
+            .filter(|path| !is_vendored_path(path) && !is_test_path(path) && !is_generated_path(path))
+            .filter_map(|path| language_for_path(path).filter(|l| l == language).map(|l| (path, l)))
+            .collect();
+        candidates.sort();
+
+        // Midpoints of equal slices, so the sample spans the whole tree
+        let picks = per_language.min(candidates.len());
+        for k in 0..picks {
+            let (path, language) = candidates[(2 * k + 1) * candidates.len() / (2 * picks)];
+            sample.push((path.clone(), language));
+        }
+    }
+    sample
+}
+
+fn is_generated_path(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.split('/').any(|segment| matches!(segment, "dist" | "build" | "out" | "generated" | "migrations"))
+        || lower.contains(".min.")
+        || lower.ends_with(".d.ts")
+        || lower.ends_with(".pb.go")
+        || lower.ends_with("_pb2.py")
+}
+
+/// Complexity of one source file; None when the language is unsupported or the file looks generated
+pub fn analyze_source(language: &str, content: &str) -> Option<ComplexityMetrics> {
+    let syntax = syntax_for(language)?;
+    if content.len() > MAX_SAMPLED_FILE_BYTES {
+        return None;
+    }
+    let lines: Vec<String> = strip_comments_and_strings(content, syntax, language).lines().map(|l| l.to_string()).collect();
+    let code_lines = lines.iter().filter(|l| !l.trim().is_empty()).count();
+    if code_lines == 0 || content.len() / code_lines > 200 {
+        return None; // Minified
+    }
+
+    let patterns = Patterns::new(syntax, language);
+    let starts: Vec<usize> = (0..lines.len()).filter(|i| patterns.is_function_start(&lines[*i])).collect();
+    // Scripts without functions are measured as one unit
+    let bounds: Vec<(usize, usize)> = if starts.is_empty() {
+        vec![(0, lines.len())]
+    } else {
+        starts.iter().enumerate().map(|(n, start)| (*start, starts.get(n + 1).copied().unwrap_or(lines.len()))).collect()
+    };
+
+    let depths = brace_depths(&lines);
+    let functions: Vec<FunctionMetrics> = bounds.into_iter()
+        .filter_map(|(start, end)| patterns.measure(&lines, &depths, start, end))
+        .collect();
+    if functions.is_empty() {
+        return None;
+    }
+
+    let count = functions.len() as f64;
+    let lines_of_code: u32 = functions.iter().map(|f| f.lines).sum();
+    let debt_lines: u32 = functions.iter()
+        .filter(|f| f.cyclomatic > DEBT_CYCLOMATIC || f.cognitive > DEBT_COGNITIVE || f.lines > DEBT_LINES)
+        .map(|f| f.lines)
+        .sum();
+
+    Some(ComplexityMetrics {
+        cyclomatic_complexity: functions.iter().map(|f| f.cyclomatic as f64).sum::<f64>() / count,
+        cognitive_complexity: functions.iter().map(|f| f.cognitive as f64).sum::<f64>() / count,
+        maintainability_index: functions.iter().map(|f| f.maintainability_index()).sum::<f64>() / count,
+        technical_debt_ratio: if lines_of_code > 0 { debt_lines as f64 / lines_of_code as f64 } else { 0.0 },
+        files_sampled: 1,
+        functions: functions.len() as u32,
+        lines_of_code,
+    })
+}
+
+struct FunctionMetrics {
+    lines: u32,
+    cyclomatic: u32,
+    cognitive: u32,
+    volume: f64, // Halstead volume over the function's tokens
+}
+
+impl FunctionMetrics {
+    /// Classic maintainability index rescaled to 0-100
+    fn maintainability_index(&self) -> f64 {
+        let raw = 171.0 - 5.2 * self.volume.max(1.0).ln() - 0.23 * self.cyclomatic as f64 - 16.2 * (self.lines.max(1) as f64).ln();
+        (raw * 100.0 / 171.0).clamp(0.0, 100.0)
+    }
+}
+
+struct Patterns {
+    syntax: Syntax,
+    function_start: Regex,
+    branch: Regex, // Adds a path for cyclomatic complexity
+    structure: Regex, // Adds nesting-weighted cognitive complexity
+    boolean: Regex,
+    token: Regex,
+    rust_arms: bool, // Rust match arms are its branches
+}
+
+impl Patterns {
+    fn new(syntax: Syntax, language: &str) -> Self {
+        let function_start = match (syntax, language) {
+            (Syntax::Python, _) => r"^\s*(async\s+)?def\s+\w+",
+            (Syntax::Ruby, _) => r"^\s*def\s+",
+            (_, "Rust" | "Move" | "Cairo") => r"\bfn\s+\w+",
+            (_, "Go" | "Swift") => r"^\s*func\b",
+            (_, "Kotlin") => r"\bfun\s+[\w.<>]+\s*\(",
+            (_, "Scala") => r"\bdef\s+\w+",
+            (_, "Solidity") => r"^\s*(function|modifier|constructor)\b",
+            (_, "JavaScript" | "TypeScript" | "PHP") => {
+                r"\bfunction\b|=\s*(async\s*)?(\([^)]*\)|\w+)\s*=>|^\s*(public |private |protected |static |async |get |set )*\w+\s*\([^)]*\)\s*(:\s*[\w<>\[\]|, ]+)?\s*\{\s*$"
+            }
+            // Java, C, C++, C#, Dart: a return type and name before a parameter list that opens a body
+            _ => r"^\s*([\w<>\[\],.*&:~]+\s+)+[\w:~]+\s*\([^;]*\)\s*(const\s*)?(throws\s+[\w., ]+)?\s*\{?\s*$",
+        };
+        let (branch, structure, boolean) = match syntax {
+            Syntax::Python => (
+                r"\b(if|elif|for|while|except|and|or|case)\b",
+                r"\b(if|elif|else|for|while|except|match)\b",
+                r"\b(and|or)\b",
+            ),
+            Syntax::Ruby => (
+                r"\b(if|elsif|unless|for|while|until|when|rescue|and|or)\b|&&|\|\|",
+                r"\b(if|elsif|else|unless|for|while|until|case|rescue)\b",
+                r"\b(and|or)\b|&&|\|\|",
+            ),
+            Syntax::Braces => (
+                r"\b(if|for|foreach|while|case|catch)\b|&&|\|\|",
+                r"\b(if|else|for|foreach|while|loop|switch|match|catch)\b",
+                r"&&|\|\|",
+            ),
+        };
+
+        Self {
+            syntax,
+            function_start: Regex::new(function_start).expect("valid regex"),
+            branch: Regex::new(branch).expect("valid regex"),
+            structure: Regex::new(structure).expect("valid regex"),
+            boolean: Regex::new(boolean).expect("valid regex"),
+            token: Regex::new(r"[A-Za-z_]\w*|\d[\w.]*|[^\s\w]").expect("valid regex"),
+            rust_arms: language == "Rust",
+        }
+    }
+
+    fn is_function_start(&self, line: &str) -> bool {
+        let first_word = line.split(|c: char| !c.is_alphanumeric() && c != '_').find(|w| !w.is_empty()).unwrap_or("");
+        let control = matches!(first_word, "if" | "for" | "while" | "switch" | "catch" | "return" | "else" | "new" | "throw");
+        !control && self.function_start.is_match(line)
+    }
+
+    fn measure(&self, lines: &[String], depths: &[i32], start: usize, end: usize) -> Option<FunctionMetrics> {
+        let body: Vec<usize> = (start..end).filter(|i| !lines[*i].trim().is_empty()).collect();
+        if body.is_empty() {
+            return None;
+        }
+
+        let base_indent = indentation(&lines[start]);
+        let indent_unit = body.iter().map(|i| indentation(&lines[*i])).find(|indent| *indent > base_indent)
+            .map(|indent| indent - base_indent)
+            .unwrap_or(4);
+
+        let mut cyclomatic = 1;
+        let mut cognitive = 0;
+        let mut tokens = 0usize;
+        let mut distinct = HashSet::new();
+
+        for &i in &body {
+            let line = &lines[i];
+            let nesting = match self.syntax {
+                Syntax::Braces => (depths[i] - depths[start] - 1).max(0) as u32,
+                Syntax::Python | Syntax::Ruby => (indentation(line).saturating_sub(base_indent) / indent_unit.max(1)).saturating_sub(1) as u32,
+            };
+
+            cyclomatic += self.branch.find_iter(line).count() as u32;
+            if self.rust_arms {
+                let arms = line.matches("=>").count() as u32;
+                let matches = line.split(|c: char| !c.is_alphanumeric() && c != '_').filter(|w| *w == "match").count() as u32;
+                cyclomatic = (cyclomatic + arms).saturating_sub(matches);
+            }
+
+            // Sonar-style: else / else-if / elif add one, other structures add one plus their nesting
+            let mut previous_else = false;
+            for found in self.structure.find_iter(line) {
+                match found.as_str() {
+                    "else" => {
+                        cognitive += 1;
+                        previous_else = true;
+                        continue;
+                    }
+                    "if" if previous_else => {}
+                    "elif" | "elsif" => cognitive += 1,
+                    _ => cognitive += 1 + nesting,
+                }
+                previous_else = false;
+            }
+            if self.boolean.is_match(line) {
+                cognitive += 1;
+            }
+
+            for token in self.token.find_iter(line) {
+                tokens += 1;
+                distinct.insert(token.as_str());
+            }
+        }
+
+        Some(FunctionMetrics {
+            lines: body.len() as u32,
+            cyclomatic,
+            cognitive,
+            volume: tokens as f64 * (distinct.len().max(2) as f64).log2(),
+        })
+    }
+}
+
+fn indentation(line: &str) -> usize {
+    line.chars().take_while(|c| c.is_whitespace()).map(|c| if c == '\t' { 4 } else { 1 }).sum()
+}
+
+/// Brace depth at each line, not counting braces that close on the line itself first
+fn brace_depths(lines: &[String]) -> Vec<i32> {
+    let mut depth = 0;
+    lines.iter().map(|line| {
+        let leading_closes = line.trim_start().chars().take_while(|c| *c == '}' || c.is_whitespace()).filter(|c| *c == '}').count() as i32;
+        let at_line = depth - leading_closes;
+        depth += line.matches('{').count() as i32 - line.matches('}').count() as i32;
+        at_line
+    }).collect()
+}
+
+/// Blanks out comments and string contents, keeping line breaks so line numbers hold
+fn strip_comments_and_strings(content: &str, syntax: Syntax, language: &str) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let mut out = String::with_capacity(content.len());
+    let hash_comments = matches!(syntax, Syntax::Python | Syntax::Ruby) || language == "PHP";
+    let slash_comments = syntax == Syntax::Braces;
+    // Rust lifetimes and Scala symbols use a lone quote
+    let single_quote_strings = !matches!(language, "Rust" | "Scala");
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if (slash_comments && c == '/' && next == Some('/')) || (hash_comments && c == '#') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if slash_comments && c == '/' && next == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                if chars[i] == '\n' {
+                    out.push('\n');
+                }
+                i += 1;
+            }
+            i += 2;
+        } else if c == '\'' && !single_quote_strings {
+            // Char literals such as '"' or '\n'; anything else is a lifetime or symbol
+            let end = if next == Some('\\') {
+                (i + 2..chars.len().min(i + 12)).find(|j| chars[*j] == '\'')
+            } else {
+                (chars.get(i + 2) == Some(&'\'')).then_some(i + 2)
+            };
+            out.push(c);
+            match end {
+                Some(end) => {
+                    out.push(c);
+                    i = end + 1;
+                }
+                None => i += 1,
+            }
+        } else if c == '"' || c == '`' || c == '\'' {
+            let triple = syntax == Syntax::Python && next == Some(c) && chars.get(i + 2) == Some(&c);
+            let delimiter_len = if triple { 3 } else { 1 };
+            out.push(c);
+            out.push(c);
+            i += delimiter_len;
+            while i < chars.len() {
+                if chars[i] == '\\' && c != '`' {
+                    i += 2;
+                    continue;
+                }
+                let closes = if triple {
+                    chars[i] == c && chars.get(i + 1) == Some(&c) && chars.get(i + 2) == Some(&c)
+                } else {
+                    chars[i] == c
+                };
+                if closes {
+                    i += delimiter_len;
+                    break;
+                }
+                // Unterminated single-line strings end at the line break
+                if chars[i] == '\n' {
+                    out.push('\n');
+                    if !triple && c != '`' {
+                        i += 1;
+                        break;
+                    }
+                }
+                i += 1;
+            }
+        } else {
+            out.push(c);
+            i += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RUST: &str = r#"
+fn simple() -> u32 {
+    1
+}
+
+fn branchy(x: u32, y: bool) -> u32 {
+    if x > 1 && y {
+        for i in 0..x {
+            if i == 3 {
+                return i;
+            }
+        }
+    } else if x == 0 {
+        return 0;
+    }
+    x
+}
+"#;
+
+    const PYTHON: &str = r#"
+def simple():
+    return 1
+
+def nested(items):
+    for item in items:
+        if item and item > 2:
+            return item
+    return None
+"#;
+
+    #[test]
+    fn brace_language_counts_branches_and_nesting() {
+        let metrics = analyze_source("Rust", RUST).unwrap();
+        assert_eq!(metrics.functions, 2);
+        // simple: 1 path; branchy: 1 + if + && + for + if + else-if
+        assert_eq!(metrics.cyclomatic_complexity, 3.5);
+        // branchy: if (1) + && (1) + for nested once (2) + if nested twice (3) + else (1)
+        assert_eq!(metrics.cognitive_complexity, 4.0);
+        assert_eq!(metrics.lines_of_code, 15);
+    }
+
+    #[test]
+    fn python_nesting_follows_indentation() {
+        let metrics = analyze_source("Python", PYTHON).unwrap();
+        assert_eq!(metrics.functions, 2);
+        // nested: 1 + for + if + and
+        assert_eq!(metrics.cyclomatic_complexity, 2.5);
+        // nested: for (1) + if nested once (2) + and (1)
+        assert_eq!(metrics.cognitive_complexity, 2.0);
+    }
+
+    #[test]
+    fn comments_and_strings_are_not_code() {
+        let source = r#"
+fn first() -> &'static str {
+    // if this were code it would branch
+    let s = "} if { while";
+    /* if {
+       for */
+    s
+}
+
+fn second(x: bool) -> u32 {
+    if x { 1 } else { 2 }
+}
+"#;
+        let stripped = strip_comments_and_strings(source, Syntax::Braces, "Rust");
+        assert_eq!(stripped.lines().count(), source.lines().count());
+        assert!(!stripped.contains("if this") && !stripped.contains("while") && !stripped.contains("for"));
+        assert!(stripped.contains("let s = \"\";"));
+
+        // The quoted brace must not close `first` early
+        let lines: Vec<String> = stripped.lines().map(|l| l.to_string()).collect();
+        assert_eq!(*brace_depths(&lines).last().unwrap(), 0);
+
+        let metrics = analyze_source("Rust", source).unwrap();
+        assert_eq!(metrics.functions, 2);
+        assert_eq!(metrics.cyclomatic_complexity, 1.5);
+    }
+
+    #[test]
+    fn python_comments_and_docstrings_are_not_code() {
+        let source = "def only(x):\n    \"\"\"if x or y:\n    def fake():\"\"\"\n    # while True:\n    label = 'if and or'\n    return x\n";
+        let metrics = analyze_source("Python", source).unwrap();
+        assert_eq!(metrics.functions, 1);
+        assert_eq!(metrics.cyclomatic_complexity, 1.0);
+        assert_eq!(metrics.cognitive_complexity, 0.0);
+    }
+
+    #[test]
+    fn unsupported_or_minified_sources_are_skipped() {
+        assert!(analyze_source("Haskell", "main = pure ()").is_none());
+        let minified = format!("function f(){{{}}}", "a=1;".repeat(100));
+        assert!(analyze_source("JavaScript", &minified).is_none());
+    }
+}
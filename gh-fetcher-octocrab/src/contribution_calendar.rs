@@ -0,0 +1,165 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Years of calendar history requested, counting the current one
+pub const CALENDAR_YEARS: i32 = 5;
+
+/// Daily contribution counts as the source reports them
+#[derive(Debug, Clone, Default)]
+pub struct ContributionCalendar {
+    pub days: BTreeMap<NaiveDate, u32>,
+    pub commit_contributions: u32, // Commits across every year fetched
+}
+
+impl ContributionCalendar {
+    /// Overlapping windows report the same day twice; keep the larger count
+    pub fn add_day(&mut self, date: NaiveDate, count: u32) {
+        let entry = self.days.entry(date).or_insert(0);
+        *entry = (*entry).max(count);
+    }
+}
+
+/// Streaks and rhythm derived from the contribution calendar
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ContributionActivity {
+    pub current_streak: u32, // Consecutive active days ending today (or yesterday)
+    pub longest_streak: u32,
+    pub active_days_last_year: u32,
+    pub contributions_last_year: u32,
+    pub active_days_per_year: BTreeMap<i32, u32>,
+    pub commit_contributions: u32,
+    pub weekday_contributions: [u32; 7], // Monday first, trailing year
+    pub active_weeks_last_year: u32,
+    pub busiest_weekday: Option<String>,
+}
+
+impl ContributionActivity {
+    pub fn from_calendar(calendar: &ContributionCalendar, today: NaiveDate) -> Self {
+        let active: Vec<NaiveDate> = calendar.days.iter()
+            .filter(|(date, count)| **count > 0 && **date <= today)
+            .map(|(date, _)| *date)
+            .collect();
+
+        let mut longest_streak = 0;
+        let mut run = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for date in &active {
+            run = match previous {
+                Some(prev) if *date - prev == Duration::days(1) => run + 1,
+                _ => 1,
+            };
+            longest_streak = longest_streak.max(run);
+            previous = Some(*date);
+        }
+
+        // Today still counts as open, so a streak through yesterday is current
+        let current_streak = match active.last() {
+            Some(last) if today - *last <= Duration::days(1) => run,
+            _ => 0,
+        };
+
+        let mut active_days_per_year = BTreeMap::new();
+        for date in &active {
+            *active_days_per_year.entry(date.year()).or_insert(0) += 1;
+        }
+
+        let year_ago = today - Duration::days(365);
+        let mut weekday_contributions = [0u32; 7];
+        let mut active_weeks = std::collections::BTreeSet::new();
+        let mut active_days_last_year = 0;
+        let mut contributions_last_year = 0;
+        for (date, count) in calendar.days.range(year_ago.succ_opt().unwrap_or(year_ago)..=today) {
+            if *count == 0 {
+                continue;
+            }
+            active_days_last_year += 1;
+            contributions_last_year += count;
+            weekday_contributions[date.weekday().num_days_from_monday() as usize] += count;
+            active_weeks.insert(date.iso_week());
+        }
+
+        let busiest_weekday = weekday_contributions.iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .max_by_key(|(index, count)| (**count, std::cmp::Reverse(*index)))
+            .map(|(index, _)| WEEKDAYS[index].to_string());
+
+        Self {
+            current_streak,
+            longest_streak,
+            active_days_last_year,
+            contributions_last_year,
+            active_days_per_year,
+            commit_contributions: calendar.commit_contributions,
+            weekday_contributions,
+            active_weeks_last_year: active_weeks.len() as u32,
+            busiest_weekday,
+        }
+    }
+}
+
+const WEEKDAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn calendar(days: &[(NaiveDate, u32)]) -> ContributionCalendar {
+        let mut calendar = ContributionCalendar::default();
+        for (date, count) in days {
+            calendar.add_day(*date, *count);
+        }
+        calendar
+    }
+
+    #[test]
+    fn streaks_run_across_the_new_year() {
+        let calendar = calendar(&[
+            (day(2024, 12, 20), 1),
+            (day(2024, 12, 30), 2),
+            (day(2024, 12, 31), 1),
+            (day(2025, 1, 1), 4),
+            (day(2025, 1, 2), 1),
+        ]);
+        let activity = ContributionActivity::from_calendar(&calendar, day(2025, 1, 2));
+        assert_eq!((activity.current_streak, activity.longest_streak), (4, 4));
+        assert_eq!(activity.active_days_per_year, BTreeMap::from([(2024, 3), (2025, 2)]));
+        assert_eq!((activity.active_days_last_year, activity.contributions_last_year), (5, 9));
+    }
+
+    #[test]
+    fn current_streak_survives_an_open_today_only() {
+        let calendar = calendar(&[(day(2024, 3, 1), 1), (day(2024, 3, 2), 1), (day(2024, 3, 3), 1), (day(2024, 3, 10), 1), (day(2024, 3, 11), 1)]);
+        // Nothing yet today: the streak through yesterday still counts
+        assert_eq!(ContributionActivity::from_calendar(&calendar, day(2024, 3, 12)).current_streak, 2);
+        // A missed day ends it; the longest streak is unaffected
+        let lapsed = ContributionActivity::from_calendar(&calendar, day(2024, 3, 13));
+        assert_eq!((lapsed.current_streak, lapsed.longest_streak), (0, 3));
+    }
+
+    #[test]
+    fn future_and_empty_days_do_not_count() {
+        let calendar = calendar(&[(day(2024, 3, 1), 0), (day(2024, 3, 2), 1), (day(2024, 3, 4), 7)]);
+        let activity = ContributionActivity::from_calendar(&calendar, day(2024, 3, 3));
+        assert_eq!((activity.current_streak, activity.longest_streak, activity.contributions_last_year), (1, 1, 1));
+        assert_eq!(activity.busiest_weekday.as_deref(), Some("Saturday"));
+    }
+
+    #[test]
+    fn overlapping_windows_keep_the_larger_count() {
+        let calendar = calendar(&[(day(2024, 12, 31), 2), (day(2024, 12, 31), 5), (day(2024, 12, 31), 1)]);
+        assert_eq!(calendar.days[&day(2024, 12, 31)], 5);
+    }
+
+    #[test]
+    fn empty_calendar_has_no_activity() {
+        let activity = ContributionActivity::from_calendar(&ContributionCalendar::default(), day(2024, 3, 3));
+        assert_eq!((activity.current_streak, activity.longest_streak, activity.active_weeks_last_year), (0, 0, 0));
+        assert_eq!(activity.busiest_weekday, None);
+    }
+}
@@ -0,0 +1,244 @@
+use regex::Regex;
+use std::collections::BTreeMap;
+
+use crate::local_source::is_vendored_path;
+
+/// Manifests read per repository, shallowest first, so monorepos stay cheap
+pub const MAX_MANIFESTS_PER_REPO: usize = 8;
+
+/// Package ecosystem a manifest belongs to; dependency names only mean something within one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ecosystem {
+    Npm,
+    PyPI,
+    Cargo,
+    Go,
+    Maven,
+}
+
+/// Dependencies that identify a technology. A key ending in '/', ':' or '.' matches as a prefix.
+const NPM_TECHNOLOGIES: [(&str, &str); 32] = [
+    ("react", "React"), ("react-dom", "React"), ("react-native", "React Native"), ("next", "Next.js"),
+    ("vue", "Vue"), ("nuxt", "Nuxt"), ("@angular/", "Angular"), ("svelte", "Svelte"),
+    ("express", "Express"), ("@nestjs/", "NestJS"), ("koa", "Koa"), ("fastify", "Fastify"),
+    ("ethers", "Ethers.js"), ("web3", "Web3.js"), ("viem", "Viem"), ("wagmi", "Wagmi"),
+    ("hardhat", "Hardhat"), ("@nomicfoundation/", "Hardhat"), ("@nomiclabs/", "Hardhat"), ("@openzeppelin/", "OpenZeppelin"),
+    ("truffle", "Truffle"), ("@tensorflow/", "TensorFlow"), ("graphql", "GraphQL"), ("@apollo/", "GraphQL"),
+    ("jest", "Jest"), ("mocha", "Mocha"), ("typescript", "TypeScript"), ("tailwindcss", "Tailwind CSS"),
+    ("@prisma/", "Prisma"), ("prisma", "Prisma"), ("mongoose", "MongoDB"), ("electron", "Electron"),
+];
+
+const PYPI_TECHNOLOGIES: [(&str, &str); 20] = [
+    ("django", "Django"), ("flask", "Flask"), ("fastapi", "FastAPI"), ("tensorflow", "TensorFlow"),
+    ("torch", "PyTorch"), ("pytorch-lightning", "PyTorch"), ("scikit-learn", "scikit-learn"), ("pandas", "Pandas"),
+    ("numpy", "NumPy"), ("transformers", "Hugging Face"), ("langchain", "LangChain"), ("keras", "Keras"),
+    ("jax", "JAX"), ("web3", "Web3.py"), ("celery", "Celery"), ("sqlalchemy", "SQLAlchemy"),
+    ("pytest", "Pytest"), ("scrapy", "Scrapy"), ("opencv-python", "OpenCV"), ("graphene", "GraphQL"),
+];
+
+const CARGO_TECHNOLOGIES: [(&str, &str); 20] = [
+    ("tokio", "Tokio"), ("actix-web", "Actix"), ("axum", "Axum"), ("rocket", "Rocket"),
+    ("warp", "Warp"), ("serde", "Serde"), ("diesel", "Diesel"), ("sqlx", "SQLx"),
+    ("ethers", "Ethers-rs"), ("alloy", "Alloy"), ("solana-program", "Solana"), ("solana-sdk", "Solana"),
+    ("anchor-lang", "Anchor"), ("frame-support", "Substrate"), ("ink", "ink!"), ("bevy", "Bevy"),
+    ("tauri", "Tauri"), ("wasm-bindgen", "WebAssembly"), ("tonic", "gRPC"), ("async-graphql", "GraphQL"),
+];
+
+const GO_TECHNOLOGIES: [(&str, &str); 9] = [
+    ("github.com/gin-gonic/gin", "Gin"), ("github.com/gofiber/fiber/", "Fiber"), ("github.com/labstack/echo/", "Echo"),
+    ("github.com/ethereum/go-ethereum", "Ethereum"), ("google.golang.org/grpc", "gRPC"), ("gorm.io/gorm", "GORM"),
+    ("github.com/cosmos/cosmos-sdk", "Cosmos SDK"), ("k8s.io/", "Kubernetes"), ("github.com/99designs/gqlgen", "GraphQL"),
+];
+
+// `parse_pom` yields groupId:artifactId, so group families need both the `:` and the `.` prefix
+const MAVEN_TECHNOLOGIES: [(&str, &str); 11] = [
+    ("org.springframework:", "Spring"), ("org.springframework.", "Spring"), ("io.quarkus:", "Quarkus"),
+    ("org.hibernate:", "Hibernate"), ("org.hibernate.", "Hibernate"), ("junit:", "JUnit"), ("org.junit:", "JUnit"),
+    ("org.junit.", "JUnit"), ("org.web3j:", "Web3j"), ("org.apache.kafka:", "Kafka"), ("io.grpc:", "gRPC"),
+];
+
+/// How a file in the tree contributes to technology detection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestKind {
+    Dependencies(Ecosystem),
+    Tool(&'static str), // Presence alone names the technology (foundry.toml, hardhat.config.*)
+}
+
+pub fn manifest_kind(path: &str) -> Option<ManifestKind> {
+    if is_vendored_path(path) {
+        return None;
+    }
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+
+    match file_name {
+        "package.json" => Some(ManifestKind::Dependencies(Ecosystem::Npm)),
+        "requirements.txt" | "pyproject.toml" => Some(ManifestKind::Dependencies(Ecosystem::PyPI)),
+        "Cargo.toml" => Some(ManifestKind::Dependencies(Ecosystem::Cargo)),
+        "go.mod" => Some(ManifestKind::Dependencies(Ecosystem::Go)),
+        "pom.xml" => Some(ManifestKind::Dependencies(Ecosystem::Maven)),
+        "foundry.toml" => Some(ManifestKind::Tool("Foundry")),
+        name if name.starts_with("hardhat.config.") => Some(ManifestKind::Tool("Hardhat")),
+        _ => None,
+    }
+}
+
+/// Dependency names declared in a manifest, lowercased
+pub fn parse_dependencies(path: &str, content: &str) -> Vec<String> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let names = match file_name {
+        "package.json" => parse_package_json(content),
+        "requirements.txt" => content.lines().filter_map(requirement_name).collect(),
+        "pyproject.toml" => parse_pyproject(content),
+        "Cargo.toml" => parse_cargo_toml(content),
+        "go.mod" => parse_go_mod(content),
+        "pom.xml" => parse_pom(content),
+        _ => Vec::new(),
+    };
+    names.into_iter().map(|n| n.to_lowercase()).collect()
+}
+
+/// Technology a dependency stands for, if it is one we track
+pub fn technology_for(ecosystem: Ecosystem, dependency: &str) -> Option<&'static str> {
+    let table: &[(&str, &str)] = match ecosystem {
+        Ecosystem::Npm => &NPM_TECHNOLOGIES,
+        Ecosystem::PyPI => &PYPI_TECHNOLOGIES,
+        Ecosystem::Cargo => &CARGO_TECHNOLOGIES,
+        Ecosystem::Go => &GO_TECHNOLOGIES,
+        Ecosystem::Maven => &MAVEN_TECHNOLOGIES,
+    };
+    table.iter()
+        .find(|(key, _)| dependency == *key || (key.ends_with(['/', ':', '.']) && dependency.starts_with(key)))
+        .map(|(_, technology)| *technology)
+}
+
+/// Technologies behind a repository's manifests, counted once per declaring dependency
+pub fn count_technologies(manifests: &[(String, String)], tool_files: &[&'static str]) -> BTreeMap<String, u32> {
+    let mut counts = BTreeMap::new();
+    for tool in tool_files {
+        *counts.entry(tool.to_string()).or_insert(0) += 1;
+    }
+    for (path, content) in manifests {
+        let Some(ManifestKind::Dependencies(ecosystem)) = manifest_kind(path) else { continue };
+        if ecosystem == Ecosystem::Npm {
+            *counts.entry("Node.js".to_string()).or_insert(0) += 1;
+        }
+        for dependency in parse_dependencies(path, content) {
+            if let Some(technology) = technology_for(ecosystem, &dependency) {
+                *counts.entry(technology.to_string()).or_insert(0) += 1;
+            }
+        }
+    }
+    counts
+}
+
+fn parse_package_json(content: &str) -> Vec<String> {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(content) else { return Vec::new() };
+    ["dependencies", "devDependencies", "peerDependencies"].iter()
+        .filter_map(|section| json[*section].as_object())
+        .flat_map(|deps| deps.keys().cloned())
+        .collect()
+}
+
+/// "requests>=2.0 ; python_version > '3'" -> "requests"
+fn requirement_name(line: &str) -> Option<String> {
+    let line = line.split('#').next()?.trim();
+    if line.is_empty() || line.starts_with('-') {
+        return None;
+    }
+    let name = line.split(|c: char| "<>=!~;[ @".contains(c)).next()?.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn parse_pyproject(content: &str) -> Vec<String> {
+    let Ok(doc) = content.parse::<toml::Table>() else { return Vec::new() };
+    let mut names: Vec<String> = doc.get("project")
+        .and_then(|p| p.get("dependencies"))
+        .and_then(|d| d.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|d| d.as_str().and_then(requirement_name))
+        .collect();
+
+    // Poetry keeps them as a table, with the interpreter itself listed as "python"
+    if let Some(poetry) = doc.get("tool").and_then(|t| t.get("poetry")).and_then(|p| p.get("dependencies")).and_then(|d| d.as_table()) {
+        names.extend(poetry.keys().filter(|k| *k != "python").cloned());
+    }
+    names
+}
+
+fn parse_cargo_toml(content: &str) -> Vec<String> {
+    let Ok(doc) = content.parse::<toml::Table>() else { return Vec::new() };
+    let sections = [
+        doc.get("dependencies"),
+        doc.get("dev-dependencies"),
+        doc.get("build-dependencies"),
+        doc.get("workspace").and_then(|w| w.get("dependencies")),
+    ];
+    sections.into_iter()
+        .flatten()
+        .filter_map(|s| s.as_table())
+        .flat_map(|deps| deps.keys().cloned())
+        .collect()
+}
+
+fn parse_go_mod(content: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut in_require = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with("require (") {
+            in_require = true;
+        } else if in_require && line.starts_with(')') {
+            in_require = false;
+        } else if in_require {
+            names.extend(line.split_whitespace().next().map(|m| m.to_string()));
+        } else if let Some(module) = line.strip_prefix("require ") {
+            names.extend(module.split_whitespace().next().map(|m| m.to_string()));
+        }
+    }
+    names.retain(|n| !n.starts_with("//"));
+    names
+}
+
+/// "groupId:artifactId" for every <dependency>
+fn parse_pom(content: &str) -> Vec<String> {
+    let dependency = Regex::new(r"(?s)<dependency>(.*?)</dependency>").expect("valid regex");
+    let group = Regex::new(r"<groupId>\s*([^<\s]+)\s*</groupId>").expect("valid regex");
+    let artifact = Regex::new(r"<artifactId>\s*([^<\s]+)\s*</artifactId>").expect("valid regex");
+
+    dependency.captures_iter(content)
+        .filter_map(|block| {
+            let block = block.get(1)?.as_str();
+            Some(format!("{}:{}", group.captures(block)?.get(1)?.as_str(), artifact.captures(block)?.get(1)?.as_str()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_maven_group_families() {
+        assert_eq!(technology_for(Ecosystem::Maven, "org.springframework.boot:spring-boot-starter"), Some("Spring"));
+        assert_eq!(technology_for(Ecosystem::Maven, "org.springframework:spring-core"), Some("Spring"));
+        assert_eq!(technology_for(Ecosystem::Maven, "org.hibernate.orm:hibernate-core"), Some("Hibernate"));
+        assert_eq!(technology_for(Ecosystem::Maven, "org.junit.jupiter:junit-jupiter"), Some("JUnit"));
+        assert_eq!(technology_for(Ecosystem::Maven, "junit:junit"), Some("JUnit"));
+        assert_eq!(technology_for(Ecosystem::Maven, "org.springframeworkx:thing"), None);
+    }
+
+    #[test]
+    fn detects_technologies_in_a_pom() {
+        let pom = r#"<project><dependencies>
+            <dependency><groupId>org.springframework.boot</groupId><artifactId>spring-boot-starter-web</artifactId></dependency>
+            <dependency>
+                <groupId>org.junit.jupiter</groupId>
+                <artifactId>junit-jupiter</artifactId>
+                <scope>test</scope>
+            </dependency>
+        </dependencies></project>"#;
+        let counts = count_technologies(&[("pom.xml".to_string(), pom.to_string())], &[]);
+        assert_eq!(counts.get("Spring"), Some(&1));
+        assert_eq!(counts.get("JUnit"), Some(&1));
+    }
+}
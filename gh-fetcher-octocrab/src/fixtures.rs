@@ -0,0 +1,339 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use warp::http::{HeaderMap, Method, Response, StatusCode};
+use warp::hyper::body::Bytes;
+use warp::Filter;
+
+pub const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// Response headers worth keeping in a fixture; everything else is noise that
+/// would make recordings differ between runs
+const RECORDED_HEADERS: [&str; 8] = [
+    "content-type",
+    "link",
+    "etag",
+    "x-ratelimit-limit",
+    "x-ratelimit-remaining",
+    "x-ratelimit-reset",
+    "x-ratelimit-used",
+    "x-ratelimit-resource",
+];
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FixtureMode {
+    /// Forward every request to `upstream` and save the response to disk
+    Record { upstream: String },
+    /// Serve previously recorded responses; unknown requests get a 404
+    Replay,
+}
+
+/// Written next to the recorded responses; pins the analysis clock on replay
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FixtureManifest {
+    pub recorded_at: DateTime<Utc>,
+    pub upstream: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct RecordedResponse {
+    method: String,
+    path: String,
+    status: u16,
+    headers: BTreeMap<String, String>,
+    body: String,
+}
+
+/// Local stand-in for the GitHub API that records real responses to a fixture
+/// directory or replays them, so analyzer runs are reproducible offline
+pub struct FixtureServer {
+    dir: PathBuf,
+    mode: FixtureMode,
+    manifest: FixtureManifest,
+}
+
+impl FixtureServer {
+    pub fn new(dir: impl Into<PathBuf>, mode: FixtureMode) -> Result<Self, Box<dyn std::error::Error>> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+
+        let manifest = match &mode {
+            FixtureMode::Record { upstream } => {
+                let manifest = FixtureManifest {
+                    recorded_at: Utc::now(),
+                    upstream: upstream.clone(),
+                };
+                std::fs::write(dir.join(MANIFEST_FILE), serde_json::to_string_pretty(&manifest)?)?;
+                manifest
+            }
+            FixtureMode::Replay => {
+                let raw = std::fs::read_to_string(dir.join(MANIFEST_FILE))
+                    .map_err(|e| format!("No fixture manifest in {}: {}", dir.display(), e))?;
+                serde_json::from_str(&raw)?
+            }
+        };
+
+        Ok(Self { dir, mode, manifest })
+    }
+
+    pub fn manifest(&self) -> &FixtureManifest {
+        &self.manifest
+    }
+
+    /// Starts the server on an ephemeral localhost port, pins the analysis clock to
+    /// the recording time and returns the base URL to point `GitHubSource` at
+    pub async fn start(self) -> String {
+        crate::clock::pin(self.manifest.recorded_at);
+        self.listen().await
+    }
+
+    /// Serves the fixtures without touching the clock
+    async fn listen(self) -> String {
+        let state = Arc::new(FixtureState {
+            dir: self.dir,
+            mode: self.mode,
+            http: reqwest::Client::new(),
+            local_base: tokio::sync::OnceCell::new(),
+        });
+        let state_filter = {
+            let state = state.clone();
+            warp::any().map(move || state.clone())
+        };
+
+        let routes = warp::method()
+            .and(warp::path::full())
+            .and(warp::query::raw().or(warp::any().map(String::new)).unify())
+            .and(warp::header::headers_cloned())
+            .and(warp::body::bytes())
+            .and(state_filter)
+            .then(handle_request);
+
+        let (addr, server): (SocketAddr, _) = warp::serve(routes).bind_ephemeral(([127, 0, 0, 1], 0));
+        let base_url = format!("http://{}", addr);
+        let _ = state.local_base.set(base_url.clone());
+        tokio::spawn(server);
+
+        tracing::info!("Fixture server ({:?}) listening on {}", state.mode, base_url);
+        base_url
+    }
+}
+
+struct FixtureState {
+    dir: PathBuf,
+    mode: FixtureMode,
+    http: reqwest::Client,
+    local_base: tokio::sync::OnceCell<String>,
+}
+
+async fn handle_request(
+    method: Method,
+    path: warp::path::FullPath,
+    query: String,
+    headers: HeaderMap,
+    body: Bytes,
+    state: Arc<FixtureState>,
+) -> Response<String> {
+    let path_and_query = if query.is_empty() {
+        path.as_str().to_string()
+    } else {
+        format!("{}?{}", path.as_str(), query)
+    };
+    let fixture_path = state.dir.join(fixture_file_name(method.as_str(), &path_and_query, &body));
+
+    let recorded = match &state.mode {
+        FixtureMode::Replay => match read_fixture(&fixture_path) {
+            Some(recorded) => recorded,
+            None => {
+                tracing::warn!("No fixture for {} {}", method, path_and_query);
+                return json_error(StatusCode::NOT_FOUND, &format!("No fixture recorded for {} {}", method, path_and_query));
+            }
+        },
+        FixtureMode::Record { upstream } => {
+            let local_base = state.local_base.get().cloned().unwrap_or_default();
+            match forward(&state.http, upstream, &local_base, &method, &path_and_query, &headers, body).await {
+                Ok(recorded) => {
+                    if let Err(e) = write_fixture(&fixture_path, &recorded) {
+                        tracing::warn!("Failed to write fixture {}: {}", fixture_path.display(), e);
+                    }
+                    recorded
+                }
+                Err(e) => return json_error(StatusCode::BAD_GATEWAY, &format!("Upstream request failed: {}", e)),
+            }
+        }
+    };
+
+    let mut response = Response::builder()
+        .status(StatusCode::from_u16(recorded.status).unwrap_or(StatusCode::OK));
+    for (name, value) in &recorded.headers {
+        response = response.header(name, value);
+    }
+    response.body(recorded.body)
+        .unwrap_or_else(|_| json_error(StatusCode::INTERNAL_SERVER_ERROR, "Invalid recorded response"))
+}
+
+async fn forward(
+    http: &reqwest::Client,
+    upstream: &str,
+    local_base: &str,
+    method: &Method,
+    path_and_query: &str,
+    headers: &HeaderMap,
+    body: Bytes,
+) -> Result<RecordedResponse, Box<dyn std::error::Error + Send + Sync>> {
+    let mut request = http.request(method.clone(), format!("{}{}", upstream.trim_end_matches('/'), path_and_query));
+    for name in ["authorization", "accept", "content-type", "user-agent"] {
+        if let Some(value) = headers.get(name) {
+            request = request.header(name, value.clone());
+        }
+    }
+
+    let response = request.body(body).send().await?;
+    let status = response.status().as_u16();
+    let recorded_headers = RECORDED_HEADERS.iter()
+        .filter_map(|name| {
+            let value = response.headers().get(*name)?.to_str().ok()?;
+            // Pagination links must point back at the stand-in server to replay correctly
+            Some((name.to_string(), value.replace(upstream.trim_end_matches('/'), local_base)))
+        })
+        .collect();
+    let body = response.text().await?;
+
+    Ok(RecordedResponse {
+        method: method.to_string(),
+        path: path_and_query.to_string(),
+        status,
+        headers: recorded_headers,
+        body,
+    })
+}
+
+/// Stable, readable fixture file name: a slug of the path plus a hash of the full request
+fn fixture_file_name(method: &str, path_and_query: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_bytes());
+    hasher.update(b" ");
+    hasher.update(path_and_query.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    let digest = hex::encode(hasher.finalize());
+
+    let slug: String = path_and_query.split('?').next().unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>()
+        .trim_matches('_')
+        .chars()
+        .take(60)
+        .collect();
+
+    format!("{}-{}-{}.json", method.to_lowercase(), slug, &digest[..16])
+}
+
+fn read_fixture(path: &Path) -> Option<RecordedResponse> {
+    let raw = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn write_fixture(path: &Path, recorded: &RecordedResponse) -> std::io::Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(recorded)?)
+}
+
+fn json_error(status: StatusCode, message: &str) -> Response<String> {
+    let mut response = Response::new(serde_json::json!({ "message": message }).to_string());
+    *response.status_mut() = status;
+    response.headers_mut().insert("content-type", "application/json".parse().expect("static header value"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile_source::{GitHubSource, ProfileSource, RepositoryData};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Upstream API whose languages listing links to a second page on itself; counts requests
+    async fn upstream(hits: Arc<AtomicUsize>) -> String {
+        let route = warp::path!("repos" / "octocat" / "hello" / "languages")
+            .and(warp::host::optional())
+            .map(move |host: Option<warp::host::Authority>| {
+                hits.fetch_add(1, Ordering::SeqCst);
+                let host = host.map(|h| h.to_string()).unwrap_or_default();
+                Response::builder()
+                    .header("content-type", "application/json")
+                    .header("etag", "\"abc\"")
+                    .header("link", format!("<http://{}/repos/octocat/hello/languages?page=2>; rel=\"next\"", host))
+                    .header("date", Utc::now().to_rfc2822())
+                    .body(r#"{"Rust":4096,"Shell":128}"#.to_string())
+                    .unwrap()
+            });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        format!("http://{}", addr)
+    }
+
+    fn hello() -> RepositoryData {
+        RepositoryData { owner: "octocat".to_string(), name: "hello".to_string(), ..Default::default() }
+    }
+
+    #[tokio::test]
+    async fn replays_recordings_identically_without_upstream() {
+        let dir = std::env::temp_dir().join(format!("idenzero-fixtures-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let hits = Arc::new(AtomicUsize::new(0));
+        let upstream = upstream(hits.clone()).await;
+
+        let recorder = FixtureServer::new(&dir, FixtureMode::Record { upstream: upstream.clone() }).unwrap().listen().await;
+        let source = GitHubSource::with_base_url("token".to_string(), &recorder).unwrap();
+        let recorded = source.fetch_languages(&hello()).await.unwrap();
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        // Volatile headers such as `date` are left out of the recording
+        let fixture = std::fs::read_dir(&dir).unwrap()
+            .filter_map(|entry| read_fixture(&entry.unwrap().path()))
+            .next()
+            .unwrap();
+        assert_eq!(fixture.headers.get("etag").map(String::as_str), Some("\"abc\""));
+        assert!(!fixture.headers.contains_key("date"));
+
+        let mut replays = Vec::new();
+        for _ in 0..2 {
+            let replayer = FixtureServer::new(&dir, FixtureMode::Replay).unwrap();
+            assert_eq!(replayer.manifest().upstream, upstream);
+            let base = replayer.listen().await;
+            let source = GitHubSource::with_base_url("token".to_string(), &base).unwrap();
+            let languages = source.fetch_languages(&hello()).await.unwrap();
+
+            let response = reqwest::get(format!("{}/repos/octocat/hello/languages", base)).await.unwrap();
+            let link = response.headers()["link"].to_str().unwrap().to_string();
+            // Links point back at the stand-in
+            assert!(link.starts_with(&format!("<{}/", recorder)) && !link.contains(&upstream));
+            replays.push((languages, link, response.text().await.unwrap()));
+
+            let unknown = reqwest::get(format!("{}/repos/octocat/other/languages", base)).await.unwrap();
+            assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+        }
+
+        assert_eq!(replays[0], replays[1]);
+        assert_eq!(replays[0].0, recorded);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn fixture_names_depend_on_the_whole_request() {
+        let name = fixture_file_name("GET", "/users/octocat/repos?page=1", b"");
+        assert!(name.starts_with("get-users_octocat_repos-"));
+        assert_eq!(name, fixture_file_name("GET", "/users/octocat/repos?page=1", b""));
+        assert_ne!(name, fixture_file_name("GET", "/users/octocat/repos?page=2", b""));
+        assert_ne!(
+            fixture_file_name("POST", "/graphql", br#"{"query":"a"}"#),
+            fixture_file_name("POST", "/graphql", br#"{"query":"b"}"#)
+        );
+    }
+}
@@ -0,0 +1,693 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::authorship::AuthorIdentity;
+use crate::profile_source::{
+    parse_timestamp, CommitRecord, ProfileSource, RepositoryData, SourceResult, UserStats, MAX_COMMITS_PER_REPO,
+};
+
+/// Nominal repository size used to turn GitLab's language percentages into byte counts
+/// when the project statistics are not visible to the token
+const GITLAB_NOMINAL_REPO_BYTES: u64 = 100_000;
+
+fn build_http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .user_agent("idenzero-analyzer")
+        .build()
+        .unwrap_or_default()
+}
+
+async fn get_json(request: reqwest::RequestBuilder) -> SourceResult<Value> {
+    let response = request.send().await?.error_for_status()?;
+    Ok(response.json::<Value>().await?)
+}
+
+fn as_u32(value: &Value) -> u32 {
+    value.as_u64().unwrap_or(0) as u32
+}
+
+fn as_string(value: &Value) -> Option<String> {
+    value.as_str().filter(|s| !s.is_empty()).map(|s| s.to_string())
+}
+
+/// GitLab (gitlab.com or self-hosted) implementation of `ProfileSource`
+pub struct GitLabSource {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl GitLabSource {
+    pub fn new(base_url: impl Into<String>, token: Option<String>) -> Self {
+        Self {
+            http: build_http_client(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token,
+        }
+    }
+
+    fn get(&self, path: &str) -> reqwest::RequestBuilder {
+        let request = self.http.get(format!("{}/api/v4{}", self.base_url, path));
+        match &self.token {
+            Some(token) => request.header("PRIVATE-TOKEN", token),
+            None => request,
+        }
+    }
+
+    async fn user_id(&self, username: &str) -> SourceResult<u64> {
+        let users = get_json(self.get("/users").query(&[("username", username)])).await?;
+        users.get(0)
+            .and_then(|u| u["id"].as_u64())
+            .ok_or_else(|| format!("GitLab user {} not found", username).into())
+    }
+
+    /// What the account's commits carry: its public email when set, otherwise its name
+    async fn author_filter(&self, username: &str) -> SourceResult<String> {
+        let user_id = self.user_id(username).await?;
+        let user = get_json(self.get(&format!("/users/{}", user_id))).await?;
+        as_string(&user["public_email"])
+            .or_else(|| as_string(&user["name"]))
+            .ok_or_else(|| format!("GitLab user {} has neither a public email nor a name", username).into())
+    }
+
+    fn project_path(repo: &RepositoryData) -> String {
+        // GitLab accepts the URL-encoded "namespace/project" path in place of the numeric id
+        format!("{}%2F{}", repo.owner.replace('/', "%2F"), repo.name)
+    }
+}
+
+#[async_trait]
+impl ProfileSource for GitLabSource {
+    fn name(&self) -> &str {
+        "GitLab"
+    }
+
+    async fn fetch_repositories(&self, username: &str) -> SourceResult<Vec<RepositoryData>> {
+        let user_id = self.user_id(username).await?;
+        let mut repos = Vec::new();
+        let mut page = 1u32;
+
+        loop {
+            let projects = get_json(self.get(&format!("/users/{}/projects", user_id))
+                .query(&[("per_page", "100"), ("page", &page.to_string()), ("statistics", "true")]))
+                .await?;
+            let items = projects.as_array().cloned().unwrap_or_default();
+
+            repos.extend(items.iter().map(|p| RepositoryData {
+                name: p["path"].as_str().unwrap_or_default().to_string(),
+                owner: p["namespace"]["full_path"].as_str().unwrap_or(username).to_string(),
+                description: as_string(&p["description"]),
+                primary_language: None,
+                languages: None,
+                topics: p["topics"].as_array()
+                    .map(|t| t.iter().filter_map(as_string).collect())
+                    .unwrap_or_default(),
+                stars: as_u32(&p["star_count"]),
+                forks: as_u32(&p["forks_count"]),
+                is_fork: !p["forked_from_project"].is_null(),
+                homepage: None, // `web_url` is the project's own GitLab page, not a website
+                size_kb: p["statistics"]["repository_size"].as_u64().map(|b| (b / 1024) as u32),
+                default_branch: as_string(&p["default_branch"]),
+                commit_count: p["statistics"]["commit_count"].as_u64().map(|c| c as u32),
+                created_at: parse_timestamp(&p["created_at"]),
+                updated_at: parse_timestamp(&p["last_activity_at"]),
+                pushed_at: parse_timestamp(&p["last_activity_at"]),
+            }));
+
+            if items.len() < 100 {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(repos)
+    }
+
+    async fn fetch_languages(&self, repo: &RepositoryData) -> SourceResult<HashMap<String, u64>> {
+        // GitLab reports percentages rather than bytes
+        let percentages = get_json(self.get(&format!("/projects/{}/languages", Self::project_path(repo)))).await?;
+        let total_bytes = repo.size_kb.map(|kb| kb as u64 * 1024).unwrap_or(GITLAB_NOMINAL_REPO_BYTES);
+
+        Ok(percentages.as_object()
+            .map(|langs| {
+                langs.iter()
+                    .map(|(lang, pct)| (lang.clone(), (pct.as_f64().unwrap_or(0.0) / 100.0 * total_bytes as f64) as u64))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn fetch_commits(
+        &self,
+        repo: &RepositoryData,
+        author: Option<&str>,
+        since: Option<DateTime<Utc>>,
+    ) -> SourceResult<Vec<CommitRecord>> {
+        // GitLab's `author` matches the commit's author name or email, not the account
+        let author_filter = match author {
+            Some(login) => Some(self.author_filter(login).await?),
+            None => None,
+        };
+        let mut commits = Vec::new();
+        let mut page = 1u32;
+
+        loop {
+            let mut query = vec![("per_page", "100".to_string()), ("page", page.to_string())];
+            if let Some(author) = &author_filter {
+                query.push(("author", author.clone()));
+            }
+            if let Some(since) = since {
+                query.push(("since", since.to_rfc3339()));
+            }
+
+            let items = get_json(self.get(&format!("/projects/{}/repository/commits", Self::project_path(repo))).query(&query)).await?;
+            let items = items.as_array().cloned().unwrap_or_default();
+
+            commits.extend(items.iter().filter_map(|c| Some(CommitRecord {
+                sha: c["id"].as_str()?.to_string(),
+                message: c["message"].as_str().unwrap_or_default().to_string(),
+                author_name: as_string(&c["author_name"]),
+                author_email: as_string(&c["author_email"]),
+                author_login: None,
+                date: parse_timestamp(&c["authored_date"])?,
+                signed: None, // Needs a request per commit
+            })));
+
+            if items.len() < 100 || commits.len() >= MAX_COMMITS_PER_REPO {
+                break;
+            }
+            page += 1;
+        }
+
+        commits.truncate(MAX_COMMITS_PER_REPO);
+        Ok(commits)
+    }
+
+    async fn fetch_user_stats(&self, username: &str) -> SourceResult<UserStats> {
+        let user_id = self.user_id(username).await?;
+        let user = get_json(self.get(&format!("/users/{}", user_id))).await?;
+
+        Ok(UserStats {
+            followers: as_u32(&user["followers"]),
+            following: as_u32(&user["following"]),
+            public_repos: 0,
+            created_at: parse_timestamp(&user["created_at"]),
+        })
+    }
+    async fn fetch_author_identity(&self, username: &str) -> SourceResult<AuthorIdentity> {
+        let user_id = self.user_id(username).await?;
+        let user = get_json(self.get(&format!("/users/{}", user_id))).await?;
+
+        // GitLab commits carry no account, only the git name and email
+        Ok(AuthorIdentity::for_login(username)
+            .with_emails(user["public_email"].as_str())
+            .with_names(user["name"].as_str()))
+    }
+}
+
+/// Gitea / Forgejo (including Codeberg) implementation of `ProfileSource`
+pub struct GiteaSource {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl GiteaSource {
+    pub fn new(base_url: impl Into<String>, token: Option<String>) -> Self {
+        Self {
+            http: build_http_client(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token,
+        }
+    }
+
+    fn get(&self, path: &str) -> reqwest::RequestBuilder {
+        let request = self.http.get(format!("{}/api/v1{}", self.base_url, path));
+        match &self.token {
+            Some(token) => request.header("Authorization", format!("token {}", token)),
+            None => request,
+        }
+    }
+}
+
+#[async_trait]
+impl ProfileSource for GiteaSource {
+    fn name(&self) -> &str {
+        "Gitea"
+    }
+
+    async fn fetch_repositories(&self, username: &str) -> SourceResult<Vec<RepositoryData>> {
+        let mut repos = Vec::new();
+        let mut page = 1u32;
+
+        loop {
+            let items = get_json(self.get(&format!("/users/{}/repos", username))
+                .query(&[("limit", "50"), ("page", &page.to_string())]))
+                .await?;
+            let items = items.as_array().cloned().unwrap_or_default();
+
+            repos.extend(items.iter().map(|r| RepositoryData {
+                name: r["name"].as_str().unwrap_or_default().to_string(),
+                owner: r["owner"]["login"].as_str().unwrap_or(username).to_string(),
+                description: as_string(&r["description"]),
+                primary_language: as_string(&r["language"]),
+                languages: None,
+                topics: r["topics"].as_array()
+                    .map(|t| t.iter().filter_map(as_string).collect())
+                    .unwrap_or_default(),
+                stars: as_u32(&r["stars_count"]),
+                forks: as_u32(&r["forks_count"]),
+                is_fork: r["fork"].as_bool().unwrap_or(false),
+                homepage: as_string(&r["website"]),
+                size_kb: r["size"].as_u64().map(|kb| kb as u32),
+                default_branch: as_string(&r["default_branch"]),
+                commit_count: None,
+                created_at: parse_timestamp(&r["created_at"]),
+                updated_at: parse_timestamp(&r["updated_at"]),
+                pushed_at: parse_timestamp(&r["updated_at"]),
+            }));
+
+            if items.len() < 50 {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(repos)
+    }
+
+    async fn fetch_languages(&self, repo: &RepositoryData) -> SourceResult<HashMap<String, u64>> {
+        let languages = get_json(self.get(&format!("/repos/{}/{}/languages", repo.owner, repo.name))).await?;
+        Ok(serde_json::from_value(languages)?)
+    }
+
+    async fn fetch_commits(
+        &self,
+        repo: &RepositoryData,
+        author: Option<&str>,
+        since: Option<DateTime<Utc>>,
+    ) -> SourceResult<Vec<CommitRecord>> {
+        let mut commits = Vec::new();
+        let mut page = 1u32;
+
+        loop {
+            let items = get_json(self.get(&format!("/repos/{}/{}/commits", repo.owner, repo.name))
+                .query(&[("limit", "50"), ("page", &page.to_string()), ("stat", "false")]))
+                .await?;
+            let items = items.as_array().cloned().unwrap_or_default();
+
+            // Gitea has no server-side author/since filters on this endpoint
+            commits.extend(items.iter()
+                .filter_map(|c| Some(CommitRecord {
+                    sha: c["sha"].as_str()?.to_string(),
+                    message: c["commit"]["message"].as_str().unwrap_or_default().to_string(),
+                    author_name: as_string(&c["commit"]["author"]["name"]),
+                    author_email: as_string(&c["commit"]["author"]["email"]),
+                    author_login: as_string(&c["author"]["login"]),
+                    date: parse_timestamp(&c["commit"]["author"]["date"])?,
+                    signed: c["commit"]["verification"]["verified"].as_bool(),
+                }))
+                .filter(|c| author.is_none_or(|a| c.author_login.as_deref() == Some(a)))
+                .filter(|c| since.is_none_or(|s| c.date >= s)));
+
+            let reached_since = since.is_some_and(|s| {
+                items.last()
+                    .and_then(|c| parse_timestamp(&c["commit"]["author"]["date"]))
+                    .is_some_and(|d| d < s)
+            });
+            if items.len() < 50 || reached_since || commits.len() >= MAX_COMMITS_PER_REPO {
+                break;
+            }
+            page += 1;
+        }
+
+        commits.truncate(MAX_COMMITS_PER_REPO);
+        Ok(commits)
+    }
+
+    async fn fetch_user_stats(&self, username: &str) -> SourceResult<UserStats> {
+        let user = get_json(self.get(&format!("/users/{}", username))).await?;
+
+        Ok(UserStats {
+            followers: as_u32(&user["followers_count"]),
+            following: as_u32(&user["following_count"]),
+            public_repos: 0,
+            created_at: parse_timestamp(&user["created"]),
+        })
+    }
+}
+
+/// Bitbucket Cloud implementation of `ProfileSource`. Usernames map to workspaces.
+pub struct BitbucketSource {
+    http: reqwest::Client,
+    base_url: String,
+    credentials: Option<(String, String)>, // username + app password
+}
+
+impl BitbucketSource {
+    pub fn new(credentials: Option<(String, String)>) -> Self {
+        Self::with_base_url("https://api.bitbucket.org/2.0", credentials)
+    }
+
+    pub fn with_base_url(base_url: impl Into<String>, credentials: Option<(String, String)>) -> Self {
+        Self {
+            http: build_http_client(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            credentials,
+        }
+    }
+
+    fn get(&self, url: &str) -> reqwest::RequestBuilder {
+        let url = if url.starts_with("http") { url.to_string() } else { format!("{}{}", self.base_url, url) };
+        let request = self.http.get(url);
+        match &self.credentials {
+            Some((user, password)) => request.basic_auth(user, Some(password)),
+            None => request,
+        }
+    }
+
+    /// Follows Bitbucket's `next` links, collecting every `values` entry
+    async fn get_paged(&self, url: &str, limit: usize) -> SourceResult<Vec<Value>> {
+        let mut values = Vec::new();
+        let mut next = Some(url.to_string());
+
+        while let Some(url) = next {
+            let page = get_json(self.get(&url)).await?;
+            values.extend(page["values"].as_array().cloned().unwrap_or_default());
+            next = as_string(&page["next"]);
+            if values.len() >= limit {
+                break;
+            }
+        }
+
+        Ok(values)
+    }
+}
+
+#[async_trait]
+impl ProfileSource for BitbucketSource {
+    fn name(&self) -> &str {
+        "Bitbucket"
+    }
+
+    async fn fetch_repositories(&self, username: &str) -> SourceResult<Vec<RepositoryData>> {
+        let items = self.get_paged(&format!("/repositories/{}?pagelen=100", username), usize::MAX).await?;
+
+        Ok(items.iter().map(|r| {
+            // Bitbucket only exposes a single, lowercase language per repository
+            let primary_language = as_string(&r["language"]).map(|l| canonical_language(&l));
+            let size = r["size"].as_u64().unwrap_or(0);
+
+            RepositoryData {
+                name: r["slug"].as_str().unwrap_or_default().to_string(),
+                owner: r["workspace"]["slug"].as_str().unwrap_or(username).to_string(),
+                description: as_string(&r["description"]),
+                languages: Some(primary_language.iter().map(|l| (l.clone(), size)).collect()),
+                primary_language,
+                topics: Vec::new(),
+                stars: 0,
+                forks: 0,
+                is_fork: !r["parent"].is_null(),
+                homepage: as_string(&r["website"]),
+                size_kb: Some((size / 1024) as u32),
+                default_branch: as_string(&r["mainbranch"]["name"]),
+                commit_count: None,
+                created_at: parse_timestamp(&r["created_on"]),
+                updated_at: parse_timestamp(&r["updated_on"]),
+                pushed_at: parse_timestamp(&r["updated_on"]),
+            }
+        }).collect())
+    }
+
+    async fn fetch_languages(&self, repo: &RepositoryData) -> SourceResult<HashMap<String, u64>> {
+        Ok(repo.languages.clone().unwrap_or_default())
+    }
+
+    async fn fetch_commits(
+        &self,
+        repo: &RepositoryData,
+        author: Option<&str>,
+        since: Option<DateTime<Utc>>,
+    ) -> SourceResult<Vec<CommitRecord>> {
+        let mut commits = Vec::new();
+        let mut next = Some(format!("/repositories/{}/{}/commits?pagelen=100", repo.owner, repo.name));
+
+        while let Some(url) = next {
+            let page = get_json(self.get(&url)).await?;
+            let items = page["values"].as_array().cloned().unwrap_or_default();
+
+            // No server-side author/since filters either: filter each page so the cap counts matching commits
+            commits.extend(items.iter()
+                .filter_map(bitbucket_commit)
+                .filter(|c| author.is_none_or(|a| c.author_login.as_deref() == Some(a)))
+                .filter(|c| since.is_none_or(|s| c.date >= s)));
+
+            // Newest first, so a page ending before `since` is the last one needed
+            let reached_since = since.is_some_and(|s| {
+                items.last()
+                    .and_then(|c| parse_timestamp(&c["date"]))
+                    .is_some_and(|d| d < s)
+            });
+            next = as_string(&page["next"]);
+            if reached_since || commits.len() >= MAX_COMMITS_PER_REPO {
+                break;
+            }
+        }
+
+        commits.truncate(MAX_COMMITS_PER_REPO);
+        Ok(commits)
+    }
+
+    async fn fetch_user_stats(&self, username: &str) -> SourceResult<UserStats> {
+        let workspace = get_json(self.get(&format!("/workspaces/{}", username))).await?;
+
+        Ok(UserStats {
+            followers: 0, // Bitbucket has no follower graph
+            following: 0,
+            public_repos: 0,
+            created_at: parse_timestamp(&workspace["created_on"]),
+        })
+    }
+}
+
+/// A Bitbucket commit, whose raw author is "Name <email>"
+fn bitbucket_commit(c: &Value) -> Option<CommitRecord> {
+    let raw = c["author"]["raw"].as_str().unwrap_or_default();
+    let (name, email) = match raw.split_once('<') {
+        Some((name, rest)) => (Some(name.trim().to_string()), Some(rest.trim_end_matches('>').trim().to_string())),
+        None => (as_string(&c["author"]["raw"]), None),
+    };
+    Some(CommitRecord {
+        sha: c["hash"].as_str()?.to_string(),
+        message: c["message"].as_str().unwrap_or_default().to_string(),
+        author_name: name,
+        author_email: email,
+        author_login: as_string(&c["author"]["user"]["nickname"]),
+        date: parse_timestamp(&c["date"])?,
+        signed: None,
+    })
+}
+
+/// Maps Bitbucket's lowercase language names onto GitHub's spelling so language weights apply
+fn canonical_language(language: &str) -> String {
+    const SPECIAL_CASES: [&str; 9] = ["JavaScript", "TypeScript", "C++", "C#", "PHP", "HTML", "CSS", "Objective-C", "MATLAB"];
+    if let Some(known) = SPECIAL_CASES.iter().find(|known| known.eq_ignore_ascii_case(language)) {
+        return known.to_string();
+    }
+
+    let mut chars = language.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use std::sync::{Arc, Mutex};
+    use warp::Filter;
+
+    fn repo(owner: &str, name: &str) -> RepositoryData {
+        RepositoryData { owner: owner.to_string(), name: name.to_string(), ..Default::default() }
+    }
+
+    async fn serve<F>(routes: F) -> String
+    where
+        F: Filter + Clone + Send + Sync + 'static,
+        F::Extract: warp::Reply,
+    {
+        let (addr, server) = warp::serve(routes).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn gitlab_filters_commits_by_the_accounts_name() {
+        let authors = Arc::new(Mutex::new(Vec::new()));
+        let recorded = authors.clone();
+        let users = warp::path!("api" / "v4" / "users")
+            .map(|| warp::reply::json(&serde_json::json!([{ "id": 7, "username": "dev" }])));
+        let user = warp::path!("api" / "v4" / "users" / u64)
+            .map(|_id| warp::reply::json(&serde_json::json!({ "id": 7, "name": "Dev Eloper", "public_email": "" })));
+        let commits = warp::path!("api" / "v4" / "projects" / String / "repository" / "commits")
+            .and(warp::query::<HashMap<String, String>>())
+            .map(move |_project: String, query: HashMap<String, String>| {
+                recorded.lock().unwrap().push(query.get("author").cloned());
+                warp::reply::json(&serde_json::json!([{
+                    "id": "abc", "message": "Fix", "author_name": "Dev Eloper", "author_email": "dev@example.com",
+                    "authored_date": "2024-05-01T10:00:00Z"
+                }]))
+            });
+        let source = GitLabSource::new(serve(users.or(user).or(commits)).await, None);
+
+        let own = source.fetch_commits(&repo("dev", "hello"), Some("dev"), None).await.unwrap();
+        assert_eq!(own[0].author_email.as_deref(), Some("dev@example.com"));
+        source.fetch_commits(&repo("dev", "hello"), None, None).await.unwrap();
+        assert_eq!(*authors.lock().unwrap(), [Some("Dev Eloper".to_string()), None]);
+    }
+
+    /// Six pages of 100 commits, one a minute going back from `newest`; only the last page has commits by `dev`
+    async fn bitbucket(newest: DateTime<Utc>, pages_served: Arc<Mutex<Vec<u32>>>) -> BitbucketSource {
+        let route = warp::path!("repositories" / "dev" / "hello" / "commits")
+            .and(warp::query::<HashMap<String, String>>())
+            .map(move |query: HashMap<String, String>| {
+                let page: u32 = query.get("page").and_then(|p| p.parse().ok()).unwrap_or(1);
+                pages_served.lock().unwrap().push(page);
+                let values: Vec<Value> = (0..100).map(|i| {
+                    let n = (page - 1) * 100 + i;
+                    let nickname = if page == 6 && i < 3 { "dev" } else { "someone" };
+                    serde_json::json!({
+                        "hash": format!("c{}", n),
+                        "message": "Change",
+                        "author": { "raw": format!("{} <{}@example.com>", nickname, nickname), "user": { "nickname": nickname } },
+                        "date": (newest - Duration::minutes(n as i64)).to_rfc3339(),
+                    })
+                }).collect();
+                let next = (page < 6).then(|| format!("/repositories/dev/hello/commits?pagelen=100&page={}", page + 1));
+                warp::reply::json(&serde_json::json!({ "values": values, "next": next }))
+            });
+        BitbucketSource::with_base_url(serve(route).await, None)
+    }
+
+    #[tokio::test]
+    async fn bitbucket_keeps_paging_until_the_authors_commits_are_found() {
+        let pages = Arc::new(Mutex::new(Vec::new()));
+        let source = bitbucket(Utc::now(), pages.clone()).await;
+
+        let own = source.fetch_commits(&repo("dev", "hello"), Some("dev"), None).await.unwrap();
+        assert_eq!(own.iter().map(|c| c.sha.as_str()).collect::<Vec<_>>(), ["c500", "c501", "c502"]);
+        assert_eq!(own[0].author_name.as_deref(), Some("dev"));
+        assert_eq!(own[0].author_email.as_deref(), Some("dev@example.com"));
+        assert_eq!(pages.lock().unwrap().len(), 6);
+
+        // Without an author the cap ends the listing
+        pages.lock().unwrap().clear();
+        assert_eq!(source.fetch_commits(&repo("dev", "hello"), None, None).await.unwrap().len(), MAX_COMMITS_PER_REPO);
+        assert_eq!(pages.lock().unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn bitbucket_stops_at_the_first_page_older_than_since() {
+        let pages = Arc::new(Mutex::new(Vec::new()));
+        let newest = Utc::now();
+        let source = bitbucket(newest, pages.clone()).await;
+
+        let since = newest - Duration::minutes(150);
+        let commits = source.fetch_commits(&repo("dev", "hello"), Some("dev"), Some(since)).await.unwrap();
+        assert!(commits.is_empty());
+        assert_eq!(*pages.lock().unwrap(), [1, 2]);
+    }
+
+    #[tokio::test]
+    async fn gitlab_projects_map_onto_repository_data() {
+        let users = warp::path!("api" / "v4" / "users")
+            .map(|| warp::reply::json(&serde_json::json!([{ "id": 7 }])));
+        let projects = warp::path!("api" / "v4" / "users" / u64 / "projects").map(|_id| warp::reply::json(&serde_json::json!([{
+            "path": "hello", "namespace": { "full_path": "group/dev" }, "description": "Greeter",
+            "topics": ["rust", ""], "star_count": 4, "forks_count": 1, "forked_from_project": { "id": 1 },
+            "web_url": "https://gitlab.com/group/dev/hello", "default_branch": "main",
+            "statistics": { "repository_size": 20480, "commit_count": 42 },
+            "created_at": "2023-01-01T00:00:00Z", "last_activity_at": "2024-05-01T00:00:00Z"
+        }])));
+        let languages = warp::path!("api" / "v4" / "projects" / String / "languages").map(|project: String| {
+            assert_eq!(project, "group%2Fdev%2Fhello");
+            warp::reply::json(&serde_json::json!({ "Rust": 75.0, "Shell": 25.0 }))
+        });
+        let source = GitLabSource::new(serve(users.or(projects).or(languages)).await, None);
+
+        let repos = source.fetch_repositories("dev").await.unwrap();
+        let hello = &repos[0];
+        assert_eq!((hello.name.as_str(), hello.owner.as_str()), ("hello", "group/dev"));
+        assert_eq!(hello.topics, ["rust"]);
+        assert_eq!((hello.stars, hello.forks, hello.is_fork), (4, 1, true));
+        assert_eq!(hello.homepage, None);
+        assert_eq!((hello.size_kb, hello.commit_count), (Some(20), Some(42)));
+        assert_eq!(hello.pushed_at, parse_timestamp(&serde_json::json!("2024-05-01T00:00:00Z")));
+
+        // Percentages become bytes of the repository size
+        let languages = source.fetch_languages(hello).await.unwrap();
+        assert_eq!(languages, HashMap::from([("Rust".to_string(), 15360), ("Shell".to_string(), 5120)]));
+    }
+
+    #[tokio::test]
+    async fn gitea_repositories_and_commits_map_onto_source_records() {
+        let repos = warp::path!("api" / "v1" / "users" / String / "repos").map(|_user: String| warp::reply::json(&serde_json::json!([{
+            "name": "hello", "owner": { "login": "dev" }, "description": "", "language": "Go",
+            "topics": ["cli"], "stars_count": 3, "forks_count": 2, "fork": false, "website": "https://hello.dev",
+            "size": 64, "default_branch": "main", "created_at": "2023-01-01T00:00:00Z", "updated_at": "2024-05-01T00:00:00Z"
+        }])));
+        let commits = warp::path!("api" / "v1" / "repos" / "dev" / "hello" / "commits").map(|| warp::reply::json(&serde_json::json!([
+            { "sha": "a1", "author": { "login": "dev" }, "commit": {
+                "message": "Add flag", "author": { "name": "Dev", "email": "dev@example.com", "date": "2024-05-01T00:00:00Z" },
+                "verification": { "verified": true } } },
+            { "sha": "b2", "author": null, "commit": {
+                "message": "Typo", "author": { "name": "Someone", "email": "s@example.com", "date": "2024-04-01T00:00:00Z" } } },
+        ])));
+        let source = GiteaSource::new(serve(repos.or(commits)).await, None);
+
+        let repos = source.fetch_repositories("dev").await.unwrap();
+        let hello = &repos[0];
+        assert_eq!((hello.primary_language.as_deref(), hello.description.as_deref()), (Some("Go"), None));
+        assert_eq!((hello.stars, hello.forks, hello.size_kb), (3, 2, Some(64)));
+        assert_eq!(hello.homepage.as_deref(), Some("https://hello.dev"));
+
+        let all = source.fetch_commits(hello, None, None).await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!((all[0].author_login.as_deref(), all[0].signed), (Some("dev"), Some(true)));
+        assert_eq!((all[1].author_login.as_deref(), all[1].signed), (None, None));
+        let own = source.fetch_commits(hello, Some("dev"), None).await.unwrap();
+        assert_eq!(own.iter().map(|c| c.sha.as_str()).collect::<Vec<_>>(), ["a1"]);
+    }
+
+    #[tokio::test]
+    async fn bitbucket_repositories_use_canonical_language_names() {
+        let route = warp::path!("repositories" / String).map(|_workspace: String| warp::reply::json(&serde_json::json!({
+            "values": [{
+                "slug": "contracts", "workspace": { "slug": "dev" }, "language": "javascript", "size": 4096,
+                "parent": { "slug": "upstream" }, "website": "", "mainbranch": { "name": "main" },
+                "created_on": "2023-01-01T00:00:00Z", "updated_on": "2024-05-01T00:00:00Z"
+            }]
+        })));
+        let source = BitbucketSource::with_base_url(serve(route).await, None);
+
+        let repos = source.fetch_repositories("dev").await.unwrap();
+        let contracts = &repos[0];
+        assert_eq!(contracts.primary_language.as_deref(), Some("JavaScript"));
+        assert_eq!(contracts.languages, Some(HashMap::from([("JavaScript".to_string(), 4096)])));
+        assert_eq!((contracts.is_fork, contracts.size_kb, contracts.homepage.as_deref()), (true, Some(4), None));
+        assert_eq!(contracts.default_branch.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn canonical_language_follows_githubs_spelling() {
+        assert_eq!(canonical_language("javascript"), "JavaScript");
+        assert_eq!(canonical_language("c#"), "C#");
+        assert_eq!(canonical_language("objective-c"), "Objective-C");
+        assert_eq!(canonical_language("rust"), "Rust");
+        assert_eq!(canonical_language(""), "");
+    }
+}
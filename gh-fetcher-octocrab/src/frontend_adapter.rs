@@ -11,6 +11,8 @@ pub struct FrontendProfile {
     pub timeline: Timeline,
     pub skills_visualization: SkillsVisualization,
     pub project_showcase: ProjectShowcase,
+    #[serde(default)]
+    pub verified_owner: bool, // Whoever publishes it proved they control the GitHub account
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -206,6 +208,7 @@ impl FrontendAdapter {
             timeline,
             skills_visualization,
             project_showcase,
+            verified_owner: analysis.verified_owner,
         }
     }
 
@@ -0,0 +1,624 @@
+use chrono::{Datelike, NaiveDate};
+use octocrab::{FromResponse, Octocrab, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::api_usage::ApiUsageTracker;
+use crate::contribution_calendar::{ContributionCalendar, CALENDAR_YEARS};
+use crate::issue_activity::IssueActivity;
+use crate::review_activity::{median_hours_to_merge, merge_rate, ReviewActivity};
+use crate::profile_source::{parse_timestamp, RepositoryData, SourceError, SourceResult};
+use crate::token_pool::TokenPool;
+
+/// Repositories requested per GraphQL page (GitHub caps connections at 100,
+/// but nested language/topic connections make smaller pages cheaper)
+const REPOSITORIES_PER_PAGE: u32 = 50;
+
+const PROFILE_QUERY: &str = r#"
+query($login: String!, $after: String, $pageSize: Int!, $firstPage: Boolean!) {
+  user(login: $login) {
+    followers @include(if: $firstPage) { totalCount }
+    following @include(if: $firstPage) { totalCount }
+    contributionsCollection @include(if: $firstPage) {
+      totalCommitContributions
+      totalPullRequestContributions
+      totalPullRequestReviewContributions
+      totalIssueContributions
+      totalRepositoryContributions
+      restrictedContributionsCount
+    }
+    repositories(first: $pageSize, after: $after, ownerAffiliations: OWNER, orderBy: {field: PUSHED_AT, direction: DESC}) {
+      pageInfo { hasNextPage endCursor }
+      nodes {
+        name
+        owner { login }
+        description
+        isFork
+        stargazerCount
+        forkCount
+        homepageUrl
+        diskUsage
+        createdAt
+        updatedAt
+        pushedAt
+        primaryLanguage { name }
+        languages(first: 25, orderBy: {field: SIZE, direction: DESC}) {
+          edges { size node { name } }
+        }
+        repositoryTopics(first: 20) {
+          nodes { topic { name } }
+        }
+        defaultBranchRef {
+          name
+          target {
+            ... on Commit { history { totalCount } }
+          }
+        }
+      }
+    }
+  }
+}
+"#;
+
+const REVIEW_QUERY: &str = r#"
+query($login: String!) {
+  user(login: $login) {
+    authored: pullRequests { totalCount }
+    merged: pullRequests(states: MERGED) { totalCount }
+    closed: pullRequests(states: CLOSED) { totalCount }
+    recentMerged: pullRequests(states: MERGED, first: 100, orderBy: {field: CREATED_AT, direction: DESC}) {
+      nodes { createdAt mergedAt }
+    }
+    contributionsCollection {
+      totalPullRequestReviewContributions
+      pullRequestReviewContributionsByRepository(maxRepositories: 25) {
+        contributions(first: 100) {
+          nodes {
+            pullRequestReview { comments { totalCount } }
+            pullRequest { author { login } }
+          }
+        }
+      }
+    }
+  }
+}
+"#;
+
+const ISSUE_QUERY: &str = r#"
+query($login: String!, $commented: String!, $closedByPullRequests: String!) {
+  user(login: $login) {
+    issues { totalCount }
+    issueComments { totalCount }
+    repositoryDiscussionComments(onlyAnswers: true) { totalCount }
+  }
+  commented: search(query: $commented, type: ISSUE) { issueCount }
+  closedByPullRequests: search(query: $closedByPullRequests, type: ISSUE) { issueCount }
+}
+"#;
+
+/// Contribution totals reported by GitHub for the last year
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ContributionStats {
+    pub total_commit_contributions: u32,
+    pub total_pull_request_contributions: u32,
+    pub total_pull_request_review_contributions: u32,
+    pub total_issue_contributions: u32,
+    pub total_repository_contributions: u32,
+    pub restricted_contributions: u32,
+}
+
+/// Everything a single batched GraphQL profile fetch returns
+#[derive(Debug, Clone)]
+pub struct GraphQLProfile {
+    pub repositories: Vec<RepositoryData>,
+    pub contribution_stats: ContributionStats,
+    pub followers: u32,
+    pub following: u32,
+    pub requests_made: u32,
+}
+
+/// Batched GitHub GraphQL v4 data path: pulls repositories together with their
+/// languages, topics and commit counts in pages instead of one REST call per repo
+pub struct GraphQLFetcher {
+    client: Octocrab,
+    tokens: Option<(Arc<TokenPool>, Arc<ApiUsageTracker>)>,
+}
+
+impl GraphQLFetcher {
+    pub fn new(client: Octocrab) -> Self {
+        Self { client, tokens: None }
+    }
+
+    /// Send queries through a token pool shared with the REST path, which meters them per token;
+    /// `usage` counts them for the calling source alone
+    pub fn with_token_pool(mut self, tokens: Arc<TokenPool>, usage: Arc<ApiUsageTracker>) -> Self {
+        self.tokens = Some((tokens, usage));
+        self
+    }
+
+    pub async fn fetch_profile(&self, username: &str) -> SourceResult<GraphQLProfile> {
+        let mut profile = GraphQLProfile {
+            repositories: Vec::new(),
+            contribution_stats: ContributionStats::default(),
+            followers: 0,
+            following: 0,
+            requests_made: 0,
+        };
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let payload = serde_json::json!({
+                "query": PROFILE_QUERY,
+                "variables": {
+                    "login": username,
+                    "after": cursor,
+                    "pageSize": REPOSITORIES_PER_PAGE,
+                    "firstPage": cursor.is_none(),
+                }
+            });
+
+            let response = self.post_query(&payload).await?;
+            profile.requests_made += 1;
+
+            let user = &response["data"]["user"];
+            if user.is_null() {
+                return Err(query_failure(&response, username));
+            }
+            if let Some(errors) = response.get("errors") {
+                tracing::warn!("GraphQL errors while fetching {}: {}", username, errors);
+            }
+
+            if profile.requests_made == 1 {
+                profile.followers = count_field(&user["followers"]);
+                profile.following = count_field(&user["following"]);
+                profile.contribution_stats = parse_contribution_stats(&user["contributionsCollection"]);
+            }
+
+            let repositories = &user["repositories"];
+            if let Some(nodes) = repositories["nodes"].as_array() {
+                profile.repositories.extend(nodes.iter().map(|node| parse_repository(node, username)));
+            }
+
+            let page_info = &repositories["pageInfo"];
+            if !page_info["hasNextPage"].as_bool().unwrap_or(false) {
+                break;
+            }
+            cursor = page_info["endCursor"].as_str().map(|s| s.to_string());
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        tracing::debug!(
+            "GraphQL fetched {} repositories for {} in {} requests",
+            profile.repositories.len(), username, profile.requests_made
+        );
+
+        Ok(profile)
+    }
+
+    /// Daily contributions for the current and previous calendar years, one aliased
+    /// collection per year because GitHub caps each window at a year
+    pub async fn fetch_contribution_calendar(&self, username: &str) -> SourceResult<ContributionCalendar> {
+        let now = crate::clock::now();
+        let windows: String = (0..CALENDAR_YEARS)
+            .map(|offset| {
+                let year = now.year() - offset;
+                let to = if offset == 0 { now.format("%Y-%m-%dT%H:%M:%SZ").to_string() } else { format!("{}-12-31T23:59:59Z", year) };
+                format!(
+                    "y{year}: contributionsCollection(from: \"{year}-01-01T00:00:00Z\", to: \"{to}\") {{ \
+                     totalCommitContributions contributionCalendar {{ weeks {{ contributionDays {{ date contributionCount }} }} }} }}\n"
+                )
+            })
+            .collect();
+        let payload = serde_json::json!({
+            "query": format!("query($login: String!) {{ user(login: $login) {{\n{}}} }}", windows),
+            "variables": { "login": username },
+        });
+
+        parse_contribution_calendar(&self.post_query(&payload).await?, username)
+    }
+
+    /// Authored pull requests (all time) and reviews given (last year) in a single query
+    pub async fn fetch_review_activity(&self, username: &str) -> SourceResult<ReviewActivity> {
+        let payload = serde_json::json!({
+            "query": REVIEW_QUERY,
+            "variables": { "login": username },
+        });
+
+        parse_review_activity(&self.post_query(&payload).await?, username)
+    }
+
+    /// Issue and discussion totals; labeling needs the events feed and is left at zero
+    pub async fn fetch_issue_activity(&self, username: &str) -> SourceResult<IssueActivity> {
+        let payload = serde_json::json!({
+            "query": ISSUE_QUERY,
+            "variables": {
+                "login": username,
+                "commented": format!("commenter:{} -author:{} type:issue", username, username),
+                "closedByPullRequests": format!("author:{} type:pr is:merged linked:issue", username),
+            },
+        });
+
+        parse_issue_activity(&self.post_query(&payload).await?, username)
+    }
+
+    async fn post_query(&self, payload: &Value) -> Result<Value> {
+        let Some((tokens, usage)) = &self.tokens else {
+            return self.client.graphql(payload).await;
+        };
+
+        let response = tokens.send("graphql", Some(usage), |client| async move { client._post("/graphql", Some(payload)).await }).await?;
+
+        Value::from_response(octocrab::map_github_error(response).await?).await
+    }
+}
+
+/// GraphQL reports failures such as RATE_LIMITED or NOT_FOUND in the body of a 200 response
+fn query_failure(response: &Value, username: &str) -> SourceError {
+    let messages: Vec<String> = response["errors"].as_array().into_iter().flatten()
+        .map(|error| match error["type"].as_str() {
+            Some(kind) => format!("{}: {}", kind, error["message"].as_str().unwrap_or_default()),
+            None => error["message"].as_str().unwrap_or_default().to_string(),
+        })
+        .collect();
+    if messages.is_empty() {
+        format!("GraphQL returned no data for {}", username).into()
+    } else {
+        format!("GraphQL query for {} failed: {}", username, messages.join("; ")).into()
+    }
+}
+
+/// Calendar from the per-year collections; errors or a missing user fail, since an empty
+/// calendar would read as no contributions and no streak
+fn parse_contribution_calendar(response: &Value, username: &str) -> SourceResult<ContributionCalendar> {
+    let years = match response["data"]["user"].as_object() {
+        Some(years) if response.get("errors").is_none() => years,
+        _ => return Err(query_failure(response, username)),
+    };
+
+    let mut calendar = ContributionCalendar::default();
+    for collection in years.values() {
+        calendar.commit_contributions += collection["totalCommitContributions"].as_u64().unwrap_or(0) as u32;
+
+        let days = collection["contributionCalendar"]["weeks"].as_array().into_iter().flatten()
+            .filter_map(|week| week["contributionDays"].as_array())
+            .flatten();
+        for day in days {
+            let date = day["date"].as_str().and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+            if let Some(date) = date {
+                calendar.add_day(date, day["contributionCount"].as_u64().unwrap_or(0) as u32);
+            }
+        }
+    }
+    Ok(calendar)
+}
+
+/// Review totals from a `REVIEW_QUERY` response; errors or a missing user fail rather than read as no reviews
+fn parse_review_activity(response: &Value, username: &str) -> SourceResult<ReviewActivity> {
+    let user = &response["data"]["user"];
+    if user.is_null() || response.get("errors").is_some() {
+        return Err(query_failure(response, username));
+    }
+
+    let merged_pull_requests: Vec<_> = user["recentMerged"]["nodes"].as_array().into_iter().flatten()
+        .filter_map(|pr| Some((parse_timestamp(&pr["createdAt"])?, parse_timestamp(&pr["mergedAt"])?)))
+        .collect();
+
+    let by_repository = user["contributionsCollection"]["pullRequestReviewContributionsByRepository"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let reviews: Vec<&Value> = by_repository.iter()
+        .filter_map(|repo| repo["contributions"]["nodes"].as_array())
+        .flatten()
+        .collect();
+
+    let merged = count_field(&user["merged"]);
+    let closed = count_field(&user["closed"]);
+
+    Ok(ReviewActivity {
+        pull_requests_authored: count_field(&user["authored"]),
+        pull_requests_merged: merged,
+        pull_requests_closed_unmerged: closed,
+        merge_rate: merge_rate(merged, closed),
+        median_hours_to_merge: median_hours_to_merge(&merged_pull_requests),
+        reviews_given: user["contributionsCollection"]["totalPullRequestReviewContributions"].as_u64().unwrap_or(0) as u32,
+        review_comments: reviews.iter().map(|r| count_field(&r["pullRequestReview"]["comments"])).sum(),
+        repositories_reviewed: by_repository.len() as u32,
+        reviews_of_others: reviews.iter()
+            .filter(|r| r["pullRequest"]["author"]["login"].as_str().is_some_and(|author| !author.eq_ignore_ascii_case(username)))
+            .count() as u32,
+    })
+}
+
+/// Issue totals from an `ISSUE_QUERY` response; errors or a missing user fail rather than read as no activity
+fn parse_issue_activity(response: &Value, username: &str) -> SourceResult<IssueActivity> {
+    let data = &response["data"];
+    let user = &data["user"];
+    if user.is_null() || response.get("errors").is_some() {
+        return Err(query_failure(response, username));
+    }
+    let issue_count = |value: &Value| value["issueCount"].as_u64().unwrap_or(0) as u32;
+
+    Ok(IssueActivity {
+        issues_opened: count_field(&user["issues"]),
+        issue_comments: count_field(&user["issueComments"]),
+        issues_commented: issue_count(&data["commented"]),
+        issues_labeled: 0,
+        discussions_answered: count_field(&user["repositoryDiscussionComments"]),
+        issues_closed_by_pull_requests: issue_count(&data["closedByPullRequests"]),
+    })
+}
+
+fn count_field(value: &Value) -> u32 {
+    value["totalCount"].as_u64().unwrap_or(0) as u32
+}
+
+fn parse_contribution_stats(value: &Value) -> ContributionStats {
+    let field = |name: &str| value[name].as_u64().unwrap_or(0) as u32;
+
+    ContributionStats {
+        total_commit_contributions: field("totalCommitContributions"),
+        total_pull_request_contributions: field("totalPullRequestContributions"),
+        total_pull_request_review_contributions: field("totalPullRequestReviewContributions"),
+        total_issue_contributions: field("totalIssueContributions"),
+        total_repository_contributions: field("totalRepositoryContributions"),
+        restricted_contributions: field("restrictedContributionsCount"),
+    }
+}
+
+fn parse_repository(node: &Value, username: &str) -> RepositoryData {
+    let languages: HashMap<String, u64> = node["languages"]["edges"]
+        .as_array()
+        .map(|edges| {
+            edges.iter()
+                .filter_map(|edge| {
+                    let name = edge["node"]["name"].as_str()?;
+                    Some((name.to_string(), edge["size"].as_u64().unwrap_or(0)))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let topics = node["repositoryTopics"]["nodes"]
+        .as_array()
+        .map(|nodes| {
+            nodes.iter()
+                .filter_map(|n| n["topic"]["name"].as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let default_branch = &node["defaultBranchRef"];
+
+    RepositoryData {
+        name: node["name"].as_str().unwrap_or_default().to_string(),
+        owner: node["owner"]["login"].as_str().unwrap_or(username).to_string(),
+        description: node["description"].as_str().map(|s| s.to_string()),
+        primary_language: node["primaryLanguage"]["name"].as_str().map(|s| s.to_string()),
+        languages: Some(languages),
+        topics,
+        stars: node["stargazerCount"].as_u64().unwrap_or(0) as u32,
+        forks: node["forkCount"].as_u64().unwrap_or(0) as u32,
+        is_fork: node["isFork"].as_bool().unwrap_or(false),
+        homepage: node["homepageUrl"].as_str().filter(|s| !s.is_empty()).map(|s| s.to_string()),
+        size_kb: node["diskUsage"].as_u64().map(|kb| kb as u32),
+        default_branch: default_branch["name"].as_str().map(|s| s.to_string()),
+        commit_count: default_branch["target"]["history"]["totalCount"].as_u64().map(|c| c as u32),
+        created_at: parse_timestamp(&node["createdAt"]),
+        updated_at: parse_timestamp(&node["updatedAt"]),
+        pushed_at: parse_timestamp(&node["pushedAt"]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use warp::Filter;
+
+    /// Repository node as GitHub returns it for `PROFILE_QUERY`
+    const REPOSITORY_NODE: &str = r#"{
+        "name": "hello-world",
+        "owner": {"login": "octocat"},
+        "description": "My first repository",
+        "isFork": false,
+        "stargazerCount": 2500,
+        "forkCount": 2200,
+        "homepageUrl": "",
+        "diskUsage": 1,
+        "createdAt": "2011-01-26T19:01:12Z",
+        "updatedAt": "2024-05-01T10:00:00Z",
+        "pushedAt": "2024-04-30T08:15:00Z",
+        "primaryLanguage": {"name": "Rust"},
+        "languages": {"edges": [{"size": 9000, "node": {"name": "Rust"}}, {"size": 120, "node": {"name": "Shell"}}]},
+        "repositoryTopics": {"nodes": [{"topic": {"name": "cli"}}]},
+        "defaultBranchRef": {"name": "main", "target": {"history": {"totalCount": 42}}}
+    }"#;
+
+    /// GitHub stand-in answering `POST /graphql` with `answer(variables)`, recording every request's variables
+    async fn github(answer: fn(&Value) -> Value) -> (String, Arc<Mutex<Vec<Value>>>) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = seen.clone();
+        let route = warp::post().and(warp::path("graphql")).and(warp::body::json()).map(move |body: Value| {
+            recorded.lock().unwrap().push(body["variables"].clone());
+            warp::reply::json(&answer(&body["variables"]))
+        });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        (format!("http://{}", addr), seen)
+    }
+
+    fn fetcher(api: &str) -> GraphQLFetcher {
+        GraphQLFetcher::new(Octocrab::builder().personal_token("token".to_string()).base_uri(api).unwrap().build().unwrap())
+    }
+
+    #[test]
+    fn repository_nodes_map_every_field() {
+        let node: Value = serde_json::from_str(REPOSITORY_NODE).unwrap();
+        let repo = parse_repository(&node, "fallback");
+        assert_eq!((repo.name.as_str(), repo.owner.as_str()), ("hello-world", "octocat"));
+        assert_eq!(repo.primary_language.as_deref(), Some("Rust"));
+        assert_eq!(repo.languages.unwrap().get("Shell"), Some(&120));
+        assert_eq!(repo.topics, vec!["cli"]);
+        assert_eq!((repo.stars, repo.forks, repo.size_kb, repo.commit_count), (2500, 2200, Some(1), Some(42)));
+        assert_eq!(repo.homepage, None);
+        assert_eq!(repo.default_branch.as_deref(), Some("main"));
+        assert_eq!(repo.pushed_at.unwrap().to_rfc3339(), "2024-04-30T08:15:00+00:00");
+
+        // Missing owner and connections fall back instead of failing
+        let bare = parse_repository(&serde_json::json!({"name": "bare"}), "fallback");
+        assert_eq!(bare.owner, "fallback");
+        assert!(bare.languages.unwrap().is_empty() && bare.topics.is_empty() && bare.commit_count.is_none());
+    }
+
+    #[tokio::test]
+    async fn profile_pages_ask_for_account_totals_once() {
+        let (api, seen) = github(|variables| {
+            let node: Value = serde_json::from_str(REPOSITORY_NODE).unwrap();
+            let first = variables["after"].is_null();
+            serde_json::json!({"data": {"user": {
+                "followers": {"totalCount": 10},
+                "following": {"totalCount": 3},
+                "contributionsCollection": {"totalCommitContributions": 99},
+                "repositories": {
+                    "pageInfo": {"hasNextPage": first, "endCursor": if first { Value::from("page-2") } else { Value::Null }},
+                    "nodes": [node],
+                },
+            }}})
+        }).await;
+
+        let profile = fetcher(&api).fetch_profile("octocat").await.unwrap();
+        assert_eq!((profile.repositories.len(), profile.requests_made), (2, 2));
+        assert_eq!((profile.followers, profile.contribution_stats.total_commit_contributions), (10, 99));
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen[0]["firstPage"], true);
+        assert_eq!((seen[1]["firstPage"].clone(), seen[1]["after"].clone()), (Value::from(false), Value::from("page-2")));
+    }
+
+    #[tokio::test]
+    async fn missing_user_with_errors_is_an_error() {
+        let (api, _) = github(|_| serde_json::json!({
+            "data": {"user": null},
+            "errors": [{"type": "RATE_LIMITED", "message": "API rate limit exceeded"}],
+        })).await;
+        let error = fetcher(&api).fetch_profile("octocat").await.unwrap_err();
+        assert!(error.to_string().contains("RATE_LIMITED"));
+
+        let (api, _) = github(|_| serde_json::json!({"data": {"user": null}})).await;
+        assert!(fetcher(&api).fetch_profile("ghost").await.is_err());
+    }
+
+    #[test]
+    fn review_activity_parses_a_response() {
+        let response = serde_json::json!({"data": {"user": {
+            "authored": {"totalCount": 5},
+            "merged": {"totalCount": 3},
+            "closed": {"totalCount": 1},
+            "recentMerged": {"nodes": [
+                {"createdAt": "2024-03-01T00:00:00Z", "mergedAt": "2024-03-01T10:00:00Z"},
+                {"createdAt": "2024-03-02T00:00:00Z", "mergedAt": "2024-03-03T06:00:00Z"},
+            ]},
+            "contributionsCollection": {
+                "totalPullRequestReviewContributions": 3,
+                "pullRequestReviewContributionsByRepository": [
+                    {"contributions": {"nodes": [
+                        {"pullRequestReview": {"comments": {"totalCount": 4}}, "pullRequest": {"author": {"login": "hubot"}}},
+                        {"pullRequestReview": {"comments": {"totalCount": 0}}, "pullRequest": {"author": {"login": "OctoCat"}}},
+                    ]}},
+                    {"contributions": {"nodes": [
+                        {"pullRequestReview": {"comments": {"totalCount": 1}}, "pullRequest": {"author": null}},
+                    ]}},
+                ],
+            },
+        }}});
+
+        let activity = parse_review_activity(&response, "octocat").unwrap();
+        assert_eq!((activity.pull_requests_authored, activity.pull_requests_merged, activity.pull_requests_closed_unmerged), (5, 3, 1));
+        assert_eq!(activity.merge_rate, Some(0.75));
+        assert_eq!(activity.median_hours_to_merge, Some(20.0));
+        assert_eq!((activity.reviews_given, activity.review_comments, activity.repositories_reviewed), (3, 5, 2));
+        // Own pull requests and ghost authors are not someone else's
+        assert_eq!(activity.reviews_of_others, 1);
+    }
+
+    #[test]
+    fn review_activity_fails_on_errors_or_a_missing_user() {
+        let rate_limited = serde_json::json!({
+            "data": {"user": {"authored": {"totalCount": 5}}},
+            "errors": [{"type": "RATE_LIMITED", "message": "API rate limit exceeded"}],
+        });
+        assert!(parse_review_activity(&rate_limited, "octocat").unwrap_err().to_string().contains("RATE_LIMITED"));
+
+        let not_found = serde_json::json!({
+            "data": {"user": null},
+            "errors": [{"type": "NOT_FOUND", "message": "Could not resolve to a User with the login of 'ghost'."}],
+        });
+        assert!(parse_review_activity(&not_found, "ghost").unwrap_err().to_string().contains("NOT_FOUND"));
+        assert!(parse_review_activity(&serde_json::json!({"data": {"user": null}}), "ghost").is_err());
+    }
+
+    #[test]
+    fn issue_activity_parses_a_response() {
+        let response = serde_json::json!({"data": {
+            "user": {
+                "issues": {"totalCount": 7},
+                "issueComments": {"totalCount": 40},
+                "repositoryDiscussionComments": {"totalCount": 2},
+            },
+            "commented": {"issueCount": 12},
+            "closedByPullRequests": {"issueCount": 3},
+        }});
+        let activity = parse_issue_activity(&response, "octocat").unwrap();
+        assert_eq!((activity.issues_opened, activity.issue_comments, activity.issues_commented), (7, 40, 12));
+        assert_eq!((activity.discussions_answered, activity.issues_closed_by_pull_requests, activity.issues_labeled), (2, 3, 0));
+    }
+
+    #[test]
+    fn issue_activity_fails_on_errors_or_a_missing_user() {
+        let partial = serde_json::json!({
+            "data": {"user": {"issues": {"totalCount": 7}}, "commented": null},
+            "errors": [{"type": "RATE_LIMITED", "message": "API rate limit exceeded"}],
+        });
+        assert!(parse_issue_activity(&partial, "octocat").unwrap_err().to_string().contains("RATE_LIMITED"));
+        assert!(parse_issue_activity(&serde_json::json!({"data": {"user": null}}), "ghost").is_err());
+    }
+
+    #[test]
+    fn cached_issue_activity_keeps_its_comment_count() {
+        let cached: IssueActivity = serde_json::from_str(
+            r#"{"issues_opened":1,"issue_comments":2,"issues_triaged":3,"issues_labeled":4,"discussions_answered":5,"issues_closed_by_pull_requests":6}"#,
+        ).unwrap();
+        assert_eq!(cached.issues_commented, 3);
+    }
+
+    #[test]
+    fn contribution_calendar_merges_overlapping_years() {
+        let response = serde_json::json!({"data": {"user": {
+            "y2024": {"totalCommitContributions": 3, "contributionCalendar": {"weeks": [
+                {"contributionDays": [{"date": "2024-12-31", "contributionCount": 2}, {"date": "2025-01-01", "contributionCount": 1}]},
+            ]}},
+            "y2025": {"totalCommitContributions": 4, "contributionCalendar": {"weeks": [
+                {"contributionDays": [{"date": "2024-12-31", "contributionCount": 0}, {"date": "2025-01-01", "contributionCount": 5}]},
+            ]}},
+        }}});
+        let calendar = parse_contribution_calendar(&response, "octocat").unwrap();
+        assert_eq!(calendar.commit_contributions, 7);
+        assert_eq!(calendar.days.get(&NaiveDate::from_ymd_opt(2024, 12, 31).unwrap()), Some(&2));
+        assert_eq!(calendar.days.get(&NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()), Some(&5));
+    }
+
+    #[test]
+    fn contribution_calendar_fails_on_errors_or_a_missing_user() {
+        let limited = serde_json::json!({
+            "data": {"user": {"y2025": null}},
+            "errors": [{"type": "RATE_LIMITED", "message": "API rate limit exceeded"}],
+        });
+        assert!(parse_contribution_calendar(&limited, "octocat").is_err());
+        assert!(parse_contribution_calendar(&serde_json::json!({"data": {"user": null}}), "ghost").is_err());
+    }
+}
@@ -165,8 +165,8 @@ impl IdenScoreCalculator {
             skill_categories,
             growth_potential,
             verification_hash,
-            calculated_at: Utc::now(),
-            next_evaluation: Utc::now() + chrono::Duration::days(30), // Re-evaluate monthly
+            calculated_at: crate::clock::now(),
+            next_evaluation: crate::clock::now() + chrono::Duration::days(30), // Re-evaluate monthly
             confidence_level,
             skill_trajectory,
         }
@@ -297,6 +297,24 @@ impl IdenScoreCalculator {
             .sum::<f64>() / analysis.repository_analysis.len().max(1) as f64;
         score += avg_testing * 30.0; // Max 30 additional points
 
+        // Sampled source replaces the metadata-only estimate: every input on the same 0-100 scale
+        if let Some(metrics) = &analysis.complexity_metrics {
+            let low_debt = (1.0 - metrics.technical_debt_ratio).clamp(0.0, 1.0) * 100.0;
+            score = analysis.commit_quality_score * 0.2
+                + avg_doc_score.min(100.0) * 0.15
+                + avg_testing.min(100.0) * 0.25
+                + metrics.maintainability_index * 0.25
+                + low_debt * 0.15;
+            evidence_count += metrics.files_sampled;
+
+            if metrics.maintainability_index >= 60.0 {
+                growth_indicators.push("Maintainable, well-factored functions".to_string());
+            }
+            if metrics.technical_debt_ratio < 0.1 {
+                growth_indicators.push("Little complexity debt in sampled code".to_string());
+            }
+        }
+
         evidence_count += analysis.repository_analysis.len() as u32;
 
         if avg_doc_score > 0.7 {
@@ -367,7 +385,31 @@ impl IdenScoreCalculator {
         let total_stars: u32 = analysis.repository_analysis.iter().map(|r| r.stars).sum();
         let total_forks: u32 = analysis.repository_analysis.iter().map(|r| r.forks).sum();
 
-        evidence_count += total_stars + total_forks;
+        // Pull requests, reviews and issues are the direct evidence; stars and forks only stand in without them
+        if let Some(activity) = &analysis.review_activity {
+            evidence_count += activity.evidence_count();
+            if activity.reviews_of_others > 20 {
+                growth_indicators.push("Regularly reviews other people's pull requests".to_string());
+            }
+            if activity.merge_rate.is_some_and(|rate| rate > 0.8) && activity.decided_pull_requests() >= 10 {
+                growth_indicators.push("Pull requests are consistently accepted".to_string());
+            }
+            if activity.median_hours_to_merge.is_some_and(|hours| hours < 24.0) {
+                growth_indicators.push("Pull requests land quickly".to_string());
+            }
+        }
+        if let Some(activity) = &analysis.issue_activity {
+            evidence_count += activity.collaboration_evidence();
+            if activity.issues_commented > 10 {
+                growth_indicators.push("Helps out on issues in other people's projects".to_string());
+            }
+            if activity.issues_closed_by_pull_requests > 5 {
+                growth_indicators.push("Resolves reported issues with linked pull requests".to_string());
+            }
+        }
+        if analysis.review_activity.is_none() && analysis.issue_activity.is_none() {
+            evidence_count += total_stars + total_forks;
+        }
 
         if total_stars > 50 {
             growth_indicators.push("Community recognition through stars".to_string());
@@ -424,11 +466,37 @@ impl IdenScoreCalculator {
             .filter(|r| r.stars > 5 || r.forks > 2)
             .count();
         
-        score += (maintained_projects as f64 / 5.0).min(1.0) * 50.0; // Max 50 points
+        // Reviewing others' work across several repositories is the clearest mentoring signal
+        let review_points = if let Some(activity) = &analysis.review_activity {
+            evidence_count += activity.reviews_of_others + activity.repositories_reviewed;
+            if activity.repositories_reviewed > 3 {
+                growth_indicators.push("Reviews code across several repositories".to_string());
+            }
+            ((activity.reviews_of_others as f64).ln_1p() * 5.0).min(20.0)
+                + (activity.repositories_reviewed as f64 / 5.0).min(1.0) * 10.0
+        } else {
+            0.0
+        };
+        // Ownership and experience make room for the 30 review points when there are any
+        let base_points = if analysis.review_activity.is_some() { 35.0 } else { 50.0 };
+
+        score += (maintained_projects as f64 / 5.0).min(1.0) * base_points;
 
         // Long-term commitment (years active)
-        let experience_score = (analysis.years_active / 10.0).min(1.0) * 50.0; // Max 50 points
-        score += experience_score;
+        let experience_score = (analysis.years_active / 10.0).min(1.0) * base_points;
+        score += experience_score + review_points;
+
+        // Triage and answered discussions show the user unblocking others in public
+        if let Some(activity) = &analysis.issue_activity {
+            evidence_count += activity.leadership_evidence();
+            score += ((activity.leadership_evidence() as f64).ln_1p() * 4.0).min(15.0);
+            if activity.discussions_answered > 5 {
+                growth_indicators.push("Answers community questions in discussions".to_string());
+            }
+            if activity.issues_labeled > 10 {
+                growth_indicators.push("Triages and labels issues for maintainers".to_string());
+            }
+        }
 
         evidence_count += maintained_projects as u32;
 
@@ -461,7 +529,7 @@ impl IdenScoreCalculator {
         let recent_projects = analysis.repository_analysis.iter()
             .filter(|r| {
                 if let Some(updated) = r.updated_at {
-                    let months_since_update = (Utc::now() - updated).num_days() / 30;
+                    let months_since_update = (crate::clock::now() - updated).num_days() / 30;
                     months_since_update < 6 // Updated in last 6 months
                 } else {
                     false
@@ -609,7 +677,7 @@ impl IdenScoreCalculator {
 
         // Recent activity suggests growth mindset
         if analysis.repository_analysis.iter().any(|r| {
-            r.updated_at.map_or(false, |u| (Utc::now() - u).num_days() < 90)
+            r.updated_at.map_or(false, |u| (crate::clock::now() - u).num_days() < 90)
         }) {
             growth_score += 20.0;
         }
@@ -2,6 +2,11 @@ use serde::{Deserialize, Serialize};
 use crate::analyzer::{GitHubAnalyzer, SkillAnalysis};
 use crate::summary_generator::{SummaryGenerator, DeveloperSummary};
 use crate::frontend_adapter::{FrontendAdapter, FrontendProfile};
+use crate::profile_source::ProfileSource;
+use crate::api_usage::ApiUsage;
+use crate::analysis_cache::AnalysisCache;
+use crate::token_pool::TokenPool;
+use std::sync::Arc;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AnalysisRequest {
@@ -36,6 +41,8 @@ pub struct ResponseMetadata {
     pub analysis_duration_ms: u64,
     pub api_calls_made: u32,
     pub rate_limit_remaining: Option<u32>,
+    #[serde(default)]
+    pub rate_limit_reset: Option<String>,
     pub confidence_score: f64,
     pub data_sources: Vec<String>,
 }
@@ -55,12 +62,45 @@ impl ImprovedAnalyzer {
         })
     }
 
+    /// Improved analyzer spreading its GitHub requests over a shared token pool
+    pub fn with_token_pool(tokens: Arc<TokenPool>) -> Self {
+        Self {
+            github_analyzer: GitHubAnalyzer::with_token_pool(tokens),
+            summary_generator: SummaryGenerator::new(),
+            frontend_adapter: FrontendAdapter::new(),
+        }
+    }
+
+    /// Improved analyzer over a non-default `ProfileSource` (e.g. GitLab or Gitea)
+    pub fn with_source(source: Arc<dyn ProfileSource>) -> Self {
+        Self {
+            github_analyzer: GitHubAnalyzer::with_source(source),
+            summary_generator: SummaryGenerator::new(),
+            frontend_adapter: FrontendAdapter::new(),
+        }
+    }
+
+    /// Requests made and rate-limit budget across every analysis run so far
+    pub fn api_usage(&self) -> Option<ApiUsage> {
+        self.github_analyzer.api_usage()
+    }
+
+    pub fn cache(&self) -> &Arc<AnalysisCache> {
+        self.github_analyzer.cache()
+    }
+
     /// Enhanced analysis with multiple output formats
     pub async fn analyze_user(&mut self, request: AnalysisRequest) -> AnalysisResponse {
         let start_time = std::time::Instant::now();
         let analysis_depth = request.analysis_depth.clone().unwrap_or(AnalysisDepth::Standard);
+        let calls_before = self.api_usage().map(|u| u.requests_made).unwrap_or(0);
+
+        let result = self.perform_analysis(&request, &analysis_depth).await;
+        let usage = self.api_usage().unwrap_or_default();
+        let api_calls_made = usage.requests_made.saturating_sub(calls_before);
+        let rate_limit_reset = usage.rate_limit_reset().map(|reset| reset.to_rfc3339());
 
-        match self.perform_analysis(&request, &analysis_depth).await {
+        match result {
             Ok((raw_analysis, ai_summary, frontend_profile)) => {
                 let duration = start_time.elapsed();
                 let confidence = self.calculate_confidence_score(&raw_analysis);
@@ -75,8 +115,9 @@ impl ImprovedAnalyzer {
                     error_message: None,
                     metadata: ResponseMetadata {
                         analysis_duration_ms: duration.as_millis() as u64,
-                        api_calls_made: 15, // Would track actual API calls
-                        rate_limit_remaining: Some(4000), // Would get from GitHub API
+                        api_calls_made,
+                        rate_limit_remaining: usage.rate_limit_remaining(),
+                        rate_limit_reset,
                         confidence_score: confidence,
                         data_sources: vec![
                             "GitHub API".to_string(),
@@ -99,8 +140,9 @@ impl ImprovedAnalyzer {
                     error_message: Some(error.to_string()),
                     metadata: ResponseMetadata {
                         analysis_duration_ms: duration.as_millis() as u64,
-                        api_calls_made: 0,
-                        rate_limit_remaining: None,
+                        api_calls_made,
+                        rate_limit_remaining: usage.rate_limit_remaining(),
+                        rate_limit_reset,
                         confidence_score: 0.0,
                         data_sources: vec![],
                     },
@@ -120,7 +162,9 @@ impl ImprovedAnalyzer {
             github_username: request.username.clone(),
             wallet_address: request.wallet_address.clone(),
         };
-        let raw_analysis = self.github_analyzer.analyze(profile).await?;
+        let max_age = self.github_analyzer.cache_ttls().for_depth(depth);
+        let raw_analysis = self.github_analyzer.analyze_with_max_age(profile, max_age).await
+            .map_err(|e| e as Box<dyn std::error::Error>)?;
 
         // Generate AI summary based on depth
         let ai_summary = match depth {
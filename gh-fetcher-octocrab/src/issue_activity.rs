@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// How the user takes part in issue trackers and discussions
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct IssueActivity {
+    pub issues_opened: u32,
+    pub issue_comments: u32,
+    #[serde(alias = "issues_triaged")]
+    pub issues_commented: u32, // Issues opened by someone else that the user commented on
+    pub issues_labeled: u32, // Labeling, assignment and closing of others' issues in recent public events
+    pub discussions_answered: u32, // Discussion comments marked as the answer
+    pub issues_closed_by_pull_requests: u32, // Merged pull requests linked to an issue
+}
+
+impl IssueActivity {
+    /// Issues and pull requests where the user worked with others in public
+    pub fn collaboration_evidence(&self) -> u32 {
+        self.issues_opened + self.issues_commented + self.issues_closed_by_pull_requests
+    }
+
+    /// Items where the user steered or unblocked someone else's work
+    pub fn leadership_evidence(&self) -> u32 {
+        self.issues_labeled + self.discussions_answered
+    }
+
+    /// Up to 20 points for public communication through issues
+    pub fn communication_score(&self) -> f64 {
+        let items = (self.collaboration_evidence() + self.discussions_answered) as f64;
+        (items.ln_1p() * 4.0).min(20.0)
+    }
+}
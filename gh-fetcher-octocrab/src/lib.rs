@@ -5,6 +5,29 @@ pub mod improved_analyzer;
 pub mod api_server;
 pub mod streamlined_analyzer;
 pub mod iden_score;
+pub mod graphql_fetcher;
+pub mod profile_source;
+pub mod forge_sources;
+pub mod local_source;
+pub mod clock;
+pub mod fixtures;
+pub mod api_usage;
+pub mod analysis_cache;
+pub mod authorship;
+pub mod contribution_calendar;
+pub mod review_activity;
+pub mod issue_activity;
+pub mod readme_analysis;
+pub mod tree_inspection;
+pub mod dependency_manifests;
+pub mod complexity_metrics;
+pub mod advisories;
+pub mod security_hygiene;
+pub mod smart_contracts;
+pub mod onchain_activity;
+pub mod wallet_binding;
+pub mod ownership;
+pub mod token_pool;
 
 pub use analyzer::*;
 pub use summary_generator::*;
@@ -12,4 +35,26 @@ pub use frontend_adapter::*;
 pub use improved_analyzer::*;
 pub use api_server::*;
 pub use streamlined_analyzer::*;
-pub use iden_score::*;
\ No newline at end of file
+pub use iden_score::*;
+pub use graphql_fetcher::*;
+pub use profile_source::*;
+pub use forge_sources::*;
+pub use local_source::*;
+pub use fixtures::*;
+pub use api_usage::*;
+pub use analysis_cache::*;
+pub use authorship::*;
+pub use contribution_calendar::*;
+pub use review_activity::*;
+pub use issue_activity::*;
+pub use readme_analysis::*;
+pub use tree_inspection::*;
+pub use dependency_manifests::*;
+pub use complexity_metrics::*;
+pub use advisories::*;
+pub use security_hygiene::*;
+pub use smart_contracts::*;
+pub use onchain_activity::*;
+pub use wallet_binding::*;
+pub use ownership::*;
+pub use token_pool::*;
\ No newline at end of file
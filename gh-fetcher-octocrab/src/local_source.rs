@@ -0,0 +1,435 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+use crate::authorship::AuthorIdentity;
+use crate::contribution_calendar::{ContributionCalendar, CALENDAR_YEARS};
+use crate::profile_source::{CommitRecord, ProfileSource, RepositoryData, SourceResult, UserStats};
+
+/// Upper bound on commits read per repository
+const MAX_COMMITS_PER_REPO: usize = 5000;
+
+/// Directories that hold vendored or generated code and should not count toward languages
+const VENDORED_DIRS: [&str; 9] = ["node_modules", "vendor", "dist", "build", "target", "third_party", "out", ".venv", "__pycache__"];
+
+/// Offline `ProfileSource` over a directory of cloned or bare git repositories.
+/// Everything is derived from the repositories themselves via the `git` CLI, so no
+/// network access is needed.
+pub struct LocalGitSource {
+    root: PathBuf,
+    author_identities: Vec<String>, // Names or emails that identify the candidate
+}
+
+impl LocalGitSource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            author_identities: Vec::new(),
+        }
+    }
+
+    /// Credit only commits authored or co-authored by any of these names or emails
+    pub fn with_author_identities(mut self, identities: Vec<String>) -> Self {
+        self.author_identities = identities;
+        self
+    }
+
+    /// Every git repository directly under the root (or the root itself)
+    pub fn discover_repositories(&self) -> Vec<PathBuf> {
+        if is_git_repository(&self.root) {
+            return vec![self.root.clone()];
+        }
+
+        let mut repos: Vec<PathBuf> = std::fs::read_dir(&self.root)
+            .map(|entries| {
+                entries.filter_map(|e| e.ok())
+                    .map(|e| e.path())
+                    .filter(|p| p.is_dir() && is_git_repository(p))
+                    .collect()
+            })
+            .unwrap_or_default();
+        repos.sort();
+        repos
+    }
+
+    fn repository_path(&self, repo: &RepositoryData) -> PathBuf {
+        if is_git_repository(&self.root) {
+            return self.root.clone();
+        }
+        let plain = self.root.join(&repo.name);
+        if plain.exists() { plain } else { self.root.join(format!("{}.git", repo.name)) }
+    }
+
+    fn author_args(&self) -> Vec<String> {
+        // Only explicit identities filter: a forge handle means nothing to git, and with no
+        // identities every commit is credited to the candidate
+        let mut args: Vec<String> = self.author_identities.iter()
+            .map(|id| format!("--author={}", id))
+            .collect();
+        if !args.is_empty() {
+            args.push("--fixed-strings".to_string());
+        }
+        args
+    }
+
+    async fn describe_repository(&self, path: &Path, username: &str) -> SourceResult<RepositoryData> {
+        let name = path.file_name()
+            .map(|n| n.to_string_lossy().trim_end_matches(".git").to_string())
+            .unwrap_or_default();
+
+        let languages = language_breakdown(&list_tree(path).await?);
+        let primary_language = languages.iter()
+            .max_by_key(|(_, bytes)| **bytes)
+            .map(|(lang, _)| lang.clone());
+
+        let mut count_args = vec!["rev-list".to_string(), "--count".to_string()];
+        count_args.extend(self.author_args());
+        count_args.push("HEAD".to_string());
+        let commit_count = git(path, &count_args).await.ok().and_then(|out| out.trim().parse().ok());
+
+        let first_commit = git(path, &["log", "--max-parents=0", "--format=%aI", "HEAD"]).await.ok()
+            .and_then(|out| out.lines().filter_map(parse_git_date).min());
+        let last_commit = git(path, &["log", "-1", "--format=%cI", "HEAD"]).await.ok()
+            .and_then(|out| parse_git_date(out.trim()));
+
+        Ok(RepositoryData {
+            name,
+            owner: username.to_string(),
+            description: read_description(path),
+            primary_language,
+            languages: Some(languages),
+            topics: Vec::new(),
+            stars: 0,
+            forks: 0,
+            is_fork: false,
+            homepage: None,
+            size_kb: None,
+            default_branch: git(path, &["symbolic-ref", "--short", "HEAD"]).await.ok().map(|b| b.trim().to_string()),
+            commit_count,
+            created_at: first_commit,
+            updated_at: last_commit,
+            pushed_at: last_commit,
+        })
+    }
+}
+
+#[async_trait]
+impl ProfileSource for LocalGitSource {
+    fn name(&self) -> &str {
+        "Local git"
+    }
+
+    async fn fetch_repositories(&self, username: &str) -> SourceResult<Vec<RepositoryData>> {
+        let mut repos = Vec::new();
+        for path in self.discover_repositories() {
+            match self.describe_repository(&path, username).await {
+                Ok(repo) => repos.push(repo),
+                Err(e) => tracing::warn!("Skipping {}: {}", path.display(), e),
+            }
+        }
+        Ok(repos)
+    }
+
+    async fn fetch_languages(&self, repo: &RepositoryData) -> SourceResult<HashMap<String, u64>> {
+        Ok(language_breakdown(&list_tree(&self.repository_path(repo)).await?))
+    }
+
+    async fn fetch_commits(
+        &self,
+        repo: &RepositoryData,
+        _author: Option<&str>,
+        since: Option<DateTime<Utc>>,
+    ) -> SourceResult<Vec<CommitRecord>> {
+        let mut args = vec![
+            // Without an allowed-signers file git reports SSH-signed commits as unsigned
+            "-c".to_string(),
+            "gpg.ssh.allowedSignersFile=/dev/null".to_string(),
+            "log".to_string(),
+            format!("--max-count={}", MAX_COMMITS_PER_REPO),
+            "--format=%H%x1f%an%x1f%ae%x1f%aI%x1f%G?%x1f%B%x1e".to_string(),
+        ];
+        // Unfiltered: co-authored commits and the repository's total are needed too
+        if let Some(since) = since {
+            args.push(format!("--since={}", since.to_rfc3339()));
+        }
+        args.push("HEAD".to_string());
+
+        let output = git(&self.repository_path(repo), &args).await?;
+
+        Ok(output.split('\x1e')
+            .filter_map(|record| {
+                let fields: Vec<&str> = record.trim_start_matches('\n').splitn(6, '\x1f').collect();
+                if fields.len() < 6 {
+                    return None;
+                }
+                Some(CommitRecord {
+                    sha: fields[0].to_string(),
+                    author_name: Some(fields[1].to_string()),
+                    author_email: Some(fields[2].to_string()),
+                    author_login: None,
+                    date: parse_git_date(fields[3])?,
+                    // Any signature counts; offline there may be no keyring to verify it against
+                    signed: Some(fields[4] != "N"),
+                    message: fields[5].trim().to_string(),
+                })
+            })
+            .collect())
+    }
+
+    async fn fetch_author_identity(&self, _username: &str) -> SourceResult<AuthorIdentity> {
+        if self.author_identities.is_empty() {
+            return Ok(AuthorIdentity::anyone());
+        }
+        let (emails, names): (Vec<&String>, Vec<&String>) = self.author_identities.iter()
+            .partition(|id| id.contains('@'));
+        Ok(AuthorIdentity::default().with_emails(emails).with_names(names))
+    }
+
+    async fn fetch_contribution_calendar(&self, _username: &str) -> SourceResult<Option<ContributionCalendar>> {
+        // Same window as GitHub's calendar: January 1st of the oldest year onwards
+        let first_year = crate::clock::now().year() - CALENDAR_YEARS + 1;
+        let mut args = vec!["log".to_string(), "--format=%aI".to_string(), format!("--since={}-01-01", first_year)];
+        args.extend(self.author_args());
+        args.push("HEAD".to_string());
+
+        let mut calendar = ContributionCalendar::default();
+        for path in self.discover_repositories() {
+            let Ok(output) = git(&path, &args).await else { continue };
+            // Days in the author's own timezone, as GitHub shows them
+            for date in output.lines().filter_map(|line| NaiveDate::parse_from_str(line.get(..10)?, "%Y-%m-%d").ok()) {
+                *calendar.days.entry(date).or_insert(0) += 1;
+                calendar.commit_contributions += 1;
+            }
+        }
+        Ok(Some(calendar))
+    }
+
+    async fn fetch_user_stats(&self, _username: &str) -> SourceResult<UserStats> {
+        // No social graph offline
+        Ok(UserStats::default())
+    }
+
+    async fn fetch_file_tree(&self, repo: &RepositoryData) -> SourceResult<Option<Vec<String>>> {
+        let tree = list_tree(&self.repository_path(repo)).await?;
+        Ok(Some(tree.into_iter().map(|(path, _)| path).collect()))
+    }
+
+    async fn fetch_readme(&self, repo: &RepositoryData) -> SourceResult<Option<String>> {
+        let path = self.repository_path(repo);
+        let tree = list_tree(&path).await?;
+        let Some((readme, _)) = tree.iter().find(|(p, _)| !p.contains('/') && p.to_lowercase().starts_with("readme")) else {
+            return Ok(None);
+        };
+        Ok(Some(git(&path, &["show", &format!("HEAD:{}", readme)]).await?))
+    }
+
+    async fn fetch_file(&self, repo: &RepositoryData, path: &str) -> SourceResult<Option<String>> {
+        // Missing paths make `git show` fail; the tree listing is the source of truth for existence
+        Ok(git(&self.repository_path(repo), &["show", &format!("HEAD:{}", path)]).await.ok())
+    }
+}
+
+fn is_git_repository(path: &Path) -> bool {
+    path.join(".git").exists() || (path.join("HEAD").is_file() && path.join("objects").is_dir())
+}
+
+fn read_description(path: &Path) -> Option<String> {
+    [path.join(".git").join("description"), path.join("description")].iter()
+        .find_map(|p| std::fs::read_to_string(p).ok())
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty() && !d.starts_with("Unnamed repository"))
+}
+
+async fn git<S: AsRef<std::ffi::OsStr>>(repo: &Path, args: &[S]) -> SourceResult<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(format!("git failed in {}: {}", repo.display(), String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// (path, size in bytes) for every blob at HEAD; empty for repositories without commits
+async fn list_tree(repo: &Path) -> SourceResult<Vec<(String, u64)>> {
+    let output = match git(repo, &["ls-tree", "-r", "-l", "HEAD"]).await {
+        Ok(output) => output,
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    Ok(output.lines()
+        .filter_map(|line| {
+            // "<mode> blob <sha> <size>\t<path>"
+            let (meta, path) = line.split_once('\t')?;
+            let size = meta.split_whitespace().nth(3)?.parse().ok()?;
+            Some((path.to_string(), size))
+        })
+        .collect())
+}
+
+fn parse_git_date(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value.trim()).ok().map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Paths inside vendored or generated directories
+pub fn is_vendored_path(path: &str) -> bool {
+    path.split('/').any(|segment| VENDORED_DIRS.contains(&segment))
+}
+
+fn language_breakdown(tree: &[(String, u64)]) -> HashMap<String, u64> {
+    let mut languages = HashMap::new();
+    for (path, size) in tree {
+        if is_vendored_path(path) {
+            continue;
+        }
+        if let Some(language) = language_for_path(path) {
+            *languages.entry(language.to_string()).or_insert(0) += size;
+        }
+    }
+    languages
+}
+
+/// File extension to GitHub (linguist) language name
+pub fn language_for_path(path: &str) -> Option<&'static str> {
+    let extension = Path::new(path).extension()?.to_str()?.to_lowercase();
+    let language = match extension.as_str() {
+        "sol" => "Solidity",
+        "vy" => "Vyper",
+        "move" => "Move",
+        "cairo" => "Cairo",
+        "rs" => "Rust",
+        "go" => "Go",
+        "ts" | "tsx" | "mts" | "cts" => "TypeScript",
+        "js" | "jsx" | "mjs" | "cjs" => "JavaScript",
+        "py" | "pyi" => "Python",
+        "ipynb" => "Jupyter Notebook",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "scala" => "Scala",
+        "c" | "h" => "C",
+        "cc" | "cpp" | "cxx" | "hpp" | "hh" | "hxx" => "C++",
+        "cs" => "C#",
+        "php" => "PHP",
+        "rb" => "Ruby",
+        "swift" => "Swift",
+        "m" | "mm" => "Objective-C",
+        "dart" => "Dart",
+        "r" => "R",
+        "jl" => "Julia",
+        "sh" | "bash" | "zsh" => "Shell",
+        "html" | "htm" => "HTML",
+        "css" => "CSS",
+        "scss" => "SCSS",
+        "vue" => "Vue",
+        "svelte" => "Svelte",
+        "sql" => "SQL",
+        "hs" => "Haskell",
+        "ex" | "exs" => "Elixir",
+        "zig" => "Zig",
+        "lua" => "Lua",
+        "asm" | "s" => "Assembly",
+        "ino" => "Arduino",
+        "tf" => "HCL",
+        _ => return None,
+    };
+    Some(language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Midnight UTC `days` ago, so commits stay inside the calendar window whenever the tests run
+    fn days_ago(days: i64) -> DateTime<Utc> {
+        crate::clock::now().date_naive().and_time(chrono::NaiveTime::MIN).and_utc() - chrono::Duration::days(days)
+    }
+
+    /// A directory holding one repository, `hello`, with a commit by Alice 30 days ago and one by Bob the day after
+    fn workspace(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("idenzero-local-{}-{}", std::process::id(), name));
+        let repo = root.join("hello");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        std::fs::create_dir_all(repo.join("node_modules/left-pad")).unwrap();
+
+        let run = |args: &[&str], author: Option<(&str, &str, &str)>| {
+            let mut command = std::process::Command::new("git");
+            command.arg("-C").arg(&repo).args(["-c", "commit.gpgsign=false", "-c", "init.defaultBranch=main"]).args(args);
+            if let Some((name, email, date)) = author {
+                command.env("GIT_AUTHOR_NAME", name).env("GIT_AUTHOR_EMAIL", email).env("GIT_AUTHOR_DATE", date)
+                    .env("GIT_COMMITTER_NAME", name).env("GIT_COMMITTER_EMAIL", email).env("GIT_COMMITTER_DATE", date);
+            }
+            assert!(command.output().unwrap().status.success(), "git {:?} failed", args);
+        };
+        run(&["init", "-q"], None);
+        std::fs::write(repo.join("src/main.rs"), "fn main() {\n    println!(\"hello\");\n}\n").unwrap();
+        std::fs::write(repo.join("node_modules/left-pad/index.js"), "module.exports = () => {};\n".repeat(50)).unwrap();
+        std::fs::write(repo.join("README.md"), "# Hello\n").unwrap();
+        run(&["add", "-A"], None);
+        run(&["commit", "-q", "-m", "Initial commit"], Some(("Alice", "alice@example.com", &days_ago(30).to_rfc3339())));
+        std::fs::write(repo.join("tool.py"), "print('hi')\n").unwrap();
+        run(&["add", "-A"], None);
+        run(&["commit", "-q", "-m", "Add tool\n\nCo-authored-by: Alice <alice@example.com>"], Some(("Bob", "bob@example.com", &days_ago(29).to_rfc3339())));
+        root
+    }
+
+    #[tokio::test]
+    async fn describes_repositories_from_git_alone() {
+        let root = workspace("describe");
+        let source = LocalGitSource::new(&root).with_author_identities(vec!["alice@example.com".to_string()]);
+        assert_eq!(source.discover_repositories(), [root.join("hello")]);
+
+        let repos = source.fetch_repositories("alice").await.unwrap();
+        let hello = &repos[0];
+        assert_eq!((hello.name.as_str(), hello.owner.as_str()), ("hello", "alice"));
+        assert_eq!(hello.primary_language.as_deref(), Some("Rust"));
+        // Vendored code does not count
+        assert!(!hello.languages.as_ref().unwrap().contains_key("JavaScript"));
+        assert!(hello.languages.as_ref().unwrap().contains_key("Python"));
+        assert_eq!(hello.default_branch.as_deref(), Some("main"));
+        assert_eq!(hello.commit_count, Some(1));
+        assert_eq!(hello.created_at, Some(days_ago(30)));
+        assert_eq!(hello.pushed_at, Some(days_ago(29)));
+
+        assert_eq!(source.fetch_readme(hello).await.unwrap().as_deref(), Some("# Hello\n"));
+        assert_eq!(source.fetch_file(hello, "missing.toml").await.unwrap(), None);
+        assert!(source.fetch_file_tree(hello).await.unwrap().unwrap().contains(&"src/main.rs".to_string()));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn reads_every_commit_and_calendars_only_the_candidates() {
+        let root = workspace("commits");
+        let source = LocalGitSource::new(&root).with_author_identities(vec!["alice@example.com".to_string()]);
+        let hello = RepositoryData { name: "hello".to_string(), ..Default::default() };
+
+        let commits = source.fetch_commits(&hello, None, None).await.unwrap();
+        assert_eq!(commits.len(), 2);
+        assert_eq!((commits[0].author_name.as_deref(), commits[0].signed), (Some("Bob"), Some(false)));
+        assert!(commits[0].message.contains("Co-authored-by: Alice"));
+        assert_eq!(commits[1].message, "Initial commit");
+
+        assert_eq!(source.fetch_commits(&hello, None, Some(days_ago(29))).await.unwrap().len(), 1);
+        assert_eq!(source.fetch_commits(&hello, None, Some(days_ago(30))).await.unwrap().len(), 2);
+
+        let calendar = source.fetch_contribution_calendar("alice").await.unwrap().unwrap();
+        assert_eq!(calendar.commit_contributions, 1);
+        assert_eq!(calendar.days.keys().collect::<Vec<_>>(), [&days_ago(30).date_naive()]);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn maps_extensions_and_skips_vendored_paths() {
+        assert_eq!(language_for_path("contracts/Token.sol"), Some("Solidity"));
+        assert_eq!(language_for_path("web/App.TSX"), Some("TypeScript"));
+        assert_eq!(language_for_path("Makefile"), None);
+        assert!(is_vendored_path("a/node_modules/b.js"));
+        assert!(!is_vendored_path("src/vendors.rs"));
+    }
+}
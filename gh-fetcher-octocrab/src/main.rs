@@ -1,6 +1,9 @@
 use std::env;
 use std::io::{self, Write};
-use idenzero_analyzer::{GitHubAnalyzer, UserProfile, SummaryGenerator, FrontendAdapter, ApiServer, IdenScoreCalculator};
+use std::sync::Arc;
+use idenzero_analyzer::{GitHubAnalyzer, UserProfile, SummaryGenerator, FrontendAdapter, ApiServer, IdenScoreCalculator, LocalGitSource};
+use idenzero_analyzer::{FixtureMode, FixtureServer, GitHubSource, StreamlinedAnalyzer, GITHUB_API_URL};
+use idenzero_analyzer::{TokenCredential, TokenPool};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -13,42 +16,70 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     });
     
+    // Logs go to stderr so stdout stays clean for the JSON printed by record/replay
     tracing_subscriber::fmt()
         .with_env_filter(log_level)
+        .with_writer(std::io::stderr)
         .init();
 
     // Check if server mode is requested
     let args: Vec<String> = env::args().collect();
     let server_mode = args.len() > 1 && args[1] == "server";
+    // Fixture runs print machine-readable JSON only
+    let fixture_mode = args.len() > 1 && (args[1] == "record" || args[1] == "replay");
 
-    if !server_mode {
+    if !server_mode && !fixture_mode {
         println!("🚀 IdenZero Skill Analyzer - AI-Powered GitHub Analysis");
         println!("========================================================");
     }
 
-    // Get GitHub token from environment
-    let github_token = env::var("GITHUB_TOKEN")
-        .or_else(|_| -> Result<String, std::env::VarError> {
-            if server_mode {
-                tracing::error!("GITHUB_TOKEN environment variable is required for server mode");
-                std::process::exit(1);
-            }
-            
+    // Offline mode: analyze a directory of local git repositories, no GitHub API needed
+    if args.len() > 2 && args[1] == "local" {
+        run_local_analysis(&args[2], &args[3..]).await;
+        return Ok(());
+    }
+
+    // Fixture modes: record live GitHub responses to disk, or replay them offline
+    if fixture_mode && args.len() > 3 {
+        let mode = if args[1] == "record" {
+            FixtureMode::Record { upstream: env::var("IDENZERO_FIXTURE_UPSTREAM").unwrap_or_else(|_| GITHUB_API_URL.to_string()) }
+        } else {
+            FixtureMode::Replay
+        };
+        // Replayed requests never leave the machine, so any token will do
+        let token = env::var("GITHUB_TOKEN").unwrap_or_else(|_| "replay".to_string());
+        run_fixture_analysis(mode, &args[2], &args[3], token).await?;
+        return Ok(());
+    }
+
+    // Get GitHub tokens from environment (GITHUB_TOKENS, GITHUB_TOKEN and/or a GitHub App)
+    let tokens = match TokenPool::from_env() {
+        Ok(tokens) => tokens,
+        Err(e) if server_mode => {
+            tracing::error!("{}: GITHUB_TOKEN, GITHUB_TOKENS or a GitHub App is required for server mode", e);
+            std::process::exit(1);
+        }
+        Err(_) => {
             print!("Enter your GitHub Personal Access Token: ");
             io::stdout().flush().unwrap();
             let mut token = String::new();
             io::stdin().read_line(&mut token).unwrap();
-            Ok(token.trim().to_string())
-        })?;
+            let token = token.trim().to_string();
 
-    if github_token.is_empty() {
-        tracing::error!("GitHub token is required");
-        std::process::exit(1);
-    }
+            if token.is_empty() {
+                tracing::error!("GitHub token is required");
+                std::process::exit(1);
+            }
+            TokenPool::new(vec![TokenCredential::PersonalToken(token)])
+                .map_err(|e| e as Box<dyn std::error::Error>)?
+        }
+    };
+    let tokens = Arc::new(tokens);
+    tracing::info!("Using {} GitHub token(s)", tokens.len());
 
     if server_mode {
         tracing::info!("Starting API Server Mode");
-        let server = ApiServer::new(github_token)?;
+        let server = ApiServer::new(tokens)?;
         server.serve().await;
         return Ok(());
     }
@@ -59,7 +90,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!();
 
     // Initialize analyzer and improved components
-    let mut analyzer = GitHubAnalyzer::new(github_token)?;
+    let mut analyzer = GitHubAnalyzer::with_token_pool(tokens);
     let summary_generator = SummaryGenerator::new();
     let frontend_adapter = FrontendAdapter::new();
     let iden_score_calculator = IdenScoreCalculator::new();
@@ -135,6 +166,67 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+async fn run_local_analysis(path: &str, author_identities: &[String]) {
+    println!("💾 Offline Mode - analyzing local repositories in {}", path);
+    if author_identities.is_empty() {
+        println!("⚠️  No author names/emails given - every commit is credited to the candidate");
+    }
+    println!();
+
+    let candidate = author_identities.first().cloned().unwrap_or_else(|| {
+        std::path::Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "local".to_string())
+    });
+
+    let source = LocalGitSource::new(path).with_author_identities(author_identities.to_vec());
+    let mut analyzer = GitHubAnalyzer::with_source(Arc::new(source));
+    let profile = UserProfile {
+        github_username: candidate,
+        wallet_address: None,
+    };
+
+    match analyzer.analyze(profile).await {
+        Ok(analysis) => {
+            display_analysis(&analysis);
+
+            println!("\n🎯 ===== IDENSCORE ANALYSIS =====");
+            let iden_score = IdenScoreCalculator::new().calculate_iden_score(&analysis);
+            display_iden_score(&iden_score);
+
+            println!("\n🤖 AI-Generated Summary:");
+            println!("========================");
+            display_ai_summary(&SummaryGenerator::new().generate_summary(&analysis));
+        }
+        Err(e) => eprintln!("❌ Local analysis failed: {}", e),
+    }
+}
+
+/// Runs the full pipeline against a fixture server and prints the results as JSON,
+/// so two runs over the same fixtures can be diffed byte for byte
+async fn run_fixture_analysis(mode: FixtureMode, dir: &str, username: &str, token: String) -> Result<(), Box<dyn std::error::Error>> {
+    let server = FixtureServer::new(dir, mode)?;
+    let base_url = server.start().await;
+    let source = Arc::new(GitHubSource::with_base_url(token, &base_url)?);
+
+    let profile = UserProfile {
+        github_username: username.to_string(),
+        wallet_address: None,
+    };
+    let analysis = GitHubAnalyzer::with_source(source.clone()).analyze(profile).await
+        .map_err(|e| e as Box<dyn std::error::Error>)?;
+    let iden_score = IdenScoreCalculator::new().calculate_iden_score(&analysis);
+    let streamlined_profile = StreamlinedAnalyzer::with_source(source).get_profile(username.to_string()).await?;
+
+    println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+        "skill_analysis": analysis,
+        "iden_score": iden_score,
+        "streamlined_profile": streamlined_profile,
+    }))?);
+    Ok(())
+}
+
 fn display_analysis(analysis: &idenzero_analyzer::SkillAnalysis) {
     println!("📊 Analysis Results");
     println!("==================");
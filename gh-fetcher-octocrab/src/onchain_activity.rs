@@ -0,0 +1,473 @@
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::analyzer::RepositoryAnalysis;
+use crate::profile_source::SourceResult;
+use crate::smart_contracts::{is_evm_address, SmartContractAnalysis};
+
+/// Nonces checked for contracts the wallet created directly (CREATE, not factories or CREATE2)
+const MAX_CREATE_NONCES: u64 = 64;
+
+/// Most recent transactions located and decoded to find interacted protocols
+const MAX_TRANSACTIONS_SAMPLED: u64 = 10;
+
+/// Well-known Ethereum mainnet contracts
+const KNOWN_PROTOCOLS: [(&str, &str); 14] = [
+    ("0x7a250d5630b4cf539739df2c5dacb4c659f2488d", "Uniswap"), ("0xe592427a0aece92de3edee1f18e0157c05861564", "Uniswap"),
+    ("0x3fc91a3afd70395cd496c647d5a6cc9d4b2b7fad", "Uniswap"), ("0x000000000022d473030f116ddee9f6b43ac78ba3", "Uniswap"),
+    ("0x87870bca3f3fd6335c3f4ce8392d69350b4fa4e2", "Aave"), ("0x7d2768de32b0b80b7a3454c06bdac94a69ddc7a9", "Aave"),
+    ("0xc3d688b66703497daa19211eedff47f25384cdc3", "Compound"), ("0xbebc44782c7db0a1a60cb6fe97d0b483032ff1c7", "Curve"),
+    ("0x1111111254eeb25477b68fb85ed929f73a960582", "1inch"), ("0x00000000000000adc04c56bf30ac9d3c0aaf14dc", "OpenSea"),
+    ("0x253553366da8546fc250f225fe3d25d0c782303b", "ENS"), ("0xae7ab96520de3a18e5e111b5eaab095312d7fe84", "Lido"),
+    ("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2", "WETH"), ("0x6b175474e89094c44da98b954eedeac495271d0f", "MakerDAO"),
+];
+
+/// A contract the wallet deployed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeployedContract {
+    pub address: String,
+    pub verified_sources: Vec<String>, // Source file names published on Sourcify; empty when unverified or unchecked
+    pub matched_repository: Option<String>, // The user's repository it was deployed from or built with
+}
+
+/// Web3 evidence from the wallet's on-chain history
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct OnchainActivity {
+    pub chain_id: u64,
+    pub address: String,
+    pub is_contract_wallet: bool, // Smart-contract account such as a Safe, which has no nonce history of its own
+    pub transaction_count: u64,
+    pub first_transaction_at: Option<DateTime<Utc>>, // None when the node cannot serve historical state
+    pub contracts_deployed: Vec<DeployedContract>,
+    pub interacted_protocols: Vec<String>, // Known protocols among the sampled transactions' targets
+    pub contracts_interacted: u32, // Distinct contracts among the sampled transactions' targets
+    pub transactions_sampled: u32,
+    pub score: f64, // 0-100
+}
+
+impl OnchainActivity {
+    pub fn account_age_days(&self) -> Option<i64> {
+        self.first_transaction_at.map(|first| (crate::clock::now() - first).num_days())
+    }
+
+    fn finish(mut self) -> Self {
+        let activity = ((self.transaction_count as f64).ln_1p() * 5.0).min(25.0);
+        let age = self.account_age_days().map_or(0.0, |days| (days as f64 / 365.0 * 5.0).min(15.0));
+        let deployments = (self.contracts_deployed.len() as f64 * 8.0).min(25.0);
+        let provenance: f64 = self.contracts_deployed.iter()
+            .map(|c| if c.matched_repository.is_some() { 10.0 } else if !c.verified_sources.is_empty() { 5.0 } else { 0.0 })
+            .sum();
+        let protocols = (self.interacted_protocols.len() as f64 * 3.0 + self.contracts_interacted as f64).min(10.0);
+
+        self.score = (activity + age + deployments + provenance.min(25.0) + protocols).min(100.0);
+        self
+    }
+}
+
+/// Minimal Ethereum JSON-RPC client; any node works, including a local anvil
+pub struct EvmRpcClient {
+    http: reqwest::Client,
+    endpoint: String,
+}
+
+impl EvmRpcClient {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self { http: reqwest::Client::new(), endpoint: endpoint.into() }
+    }
+
+    pub async fn call(&self, method: &str, params: Value) -> SourceResult<Value> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = self.http.post(&self.endpoint).json(&body).send().await?.error_for_status()?.json().await?;
+        match response.get("error") {
+            Some(error) => Err(format!("{} failed: {}", method, error["message"].as_str().unwrap_or("unknown error")).into()),
+            None => Ok(response["result"].clone()),
+        }
+    }
+
+    /// One round trip for many calls; results come back in request order, failed calls as null
+    pub async fn call_batch(&self, calls: &[(&str, Value)]) -> SourceResult<Vec<Value>> {
+        if calls.is_empty() {
+            return Ok(Vec::new());
+        }
+        let body: Vec<Value> = calls.iter().enumerate()
+            .map(|(id, (method, params))| json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .collect();
+        let responses: Vec<Value> = self.http.post(&self.endpoint).json(&body).send().await?.error_for_status()?.json().await?;
+
+        let mut results = vec![Value::Null; calls.len()];
+        for response in responses {
+            if let Some(id) = response["id"].as_u64().filter(|id| (*id as usize) < calls.len()) {
+                results[id as usize] = response["result"].clone();
+            }
+        }
+        Ok(results)
+    }
+
+    async fn quantity(&self, method: &str, params: Value) -> SourceResult<u64> {
+        let result = self.call(method, params).await?;
+        parse_quantity(&result).ok_or_else(|| format!("{} returned {}", method, result).into())
+    }
+
+    async fn nonce_at(&self, address: &str, block: u64) -> SourceResult<u64> {
+        self.quantity("eth_getTransactionCount", json!([address, format!("{:#x}", block)])).await
+    }
+}
+
+/// Analyzes a wallet's deployments, history and protocol usage over JSON-RPC
+pub struct OnchainAnalyzer {
+    rpc: EvmRpcClient,
+    http: reqwest::Client,
+    sourcify_url: Option<String>,
+}
+
+impl OnchainAnalyzer {
+    pub fn new(rpc_url: impl Into<String>) -> Self {
+        Self { rpc: EvmRpcClient::new(rpc_url), http: reqwest::Client::new(), sourcify_url: None }
+    }
+
+    /// Configured by `IDENZERO_EVM_RPC_URL` and optionally `IDENZERO_SOURCIFY_URL`; None without an RPC endpoint
+    pub fn from_env() -> Option<Self> {
+        let rpc_url = std::env::var("IDENZERO_EVM_RPC_URL").ok().filter(|url| !url.is_empty())?;
+        let analyzer = Self::new(rpc_url);
+        Some(match std::env::var("IDENZERO_SOURCIFY_URL") {
+            Ok(url) if !url.is_empty() => analyzer.with_sourcify(url),
+            _ => analyzer,
+        })
+    }
+
+    /// Look deployed contracts up on a Sourcify server, e.g. "https://sourcify.dev/server"
+    pub fn with_sourcify(mut self, url: impl Into<String>) -> Self {
+        self.sourcify_url = Some(url.into().trim_end_matches('/').to_string());
+        self
+    }
+
+    /// On-chain evidence for `wallet`, with deployments matched against the user's repositories
+    pub async fn analyze(&self, wallet: &str, repos: &[RepositoryAnalysis]) -> SourceResult<OnchainActivity> {
+        let address = wallet.trim().to_lowercase();
+        if !is_evm_address(&address) {
+            return Err(format!("{} is not an EVM address", wallet).into());
+        }
+
+        let overview = self.rpc.call_batch(&[
+            ("eth_chainId", json!([])),
+            ("eth_blockNumber", json!([])),
+            ("eth_getTransactionCount", json!([address, "latest"])),
+            ("eth_getCode", json!([address, "latest"])),
+        ]).await?;
+        let (Some(chain_id), Some(latest_block), Some(nonce)) =
+            (parse_quantity(&overview[0]), parse_quantity(&overview[1]), parse_quantity(&overview[2]))
+        else {
+            return Err("EVM node did not report chain id, block number or nonce".into());
+        };
+
+        let mut activity = OnchainActivity {
+            chain_id,
+            address: address.clone(),
+            is_contract_wallet: has_code(&overview[3]),
+            transaction_count: nonce,
+            ..OnchainActivity::default()
+        };
+        activity.contracts_deployed = self.find_deployments(&address, nonce, chain_id, repos).await?;
+
+        if nonce > 0 {
+            // Historical nonces need an archive node (anvil keeps every state); without one only the totals are known
+            if let Err(e) = self.sample_transactions(&address, nonce, latest_block, &mut activity).await {
+                tracing::debug!("Could not trace transaction history of {}: {}", address, e);
+            }
+        }
+        Ok(activity.finish())
+    }
+
+    /// Contracts at the CREATE addresses of the wallet's first nonces
+    async fn find_deployments(&self, address: &str, nonce: u64, chain_id: u64, repos: &[RepositoryAnalysis]) -> SourceResult<Vec<DeployedContract>> {
+        let candidates: Vec<String> = (0..nonce.min(MAX_CREATE_NONCES)).map(|n| create_address(address, n)).collect();
+        let calls: Vec<(&str, Value)> = candidates.iter().map(|c| ("eth_getCode", json!([c, "latest"]))).collect();
+        let codes = self.rpc.call_batch(&calls).await?;
+
+        let mut deployed = Vec::new();
+        for (candidate, code) in candidates.into_iter().zip(codes) {
+            if !has_code(&code) {
+                continue;
+            }
+            let verified_sources = self.verified_sources(chain_id, &candidate).await;
+            let matched_repository = match_repository(&candidate, &verified_sources, repos);
+            deployed.push(DeployedContract { address: candidate, verified_sources, matched_repository });
+        }
+        Ok(deployed)
+    }
+
+    /// File names of the verified sources on Sourcify, if configured and published
+    async fn verified_sources(&self, chain_id: u64, address: &str) -> Vec<String> {
+        let Some(sourcify) = &self.sourcify_url else { return Vec::new() };
+        let url = format!("{}/files/any/{}/{}", sourcify, chain_id, address);
+        let response = match self.http.get(&url).send().await.and_then(|r| r.error_for_status()) {
+            Ok(response) => response.json::<Value>().await.unwrap_or_default(),
+            Err(e) => {
+                tracing::debug!("No verified source for {} on chain {}: {}", address, chain_id, e);
+                return Vec::new();
+            }
+        };
+        let mut names: Vec<String> = response["files"].as_array().into_iter().flatten()
+            .filter_map(|file| file["name"].as_str())
+            .filter(|name| name.ends_with(".sol") || name.ends_with(".vy"))
+            .map(|name| name.to_string())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Locates the wallet's first and most recent transactions and decodes where they went
+    async fn sample_transactions(&self, address: &str, nonce: u64, latest_block: u64, activity: &mut OnchainActivity) -> SourceResult<()> {
+        let first_block = self.block_of_nonce(address, 0, 0, latest_block).await?;
+        activity.first_transaction_at = self.block_timestamp(first_block).await?;
+
+        let mut targets = BTreeSet::new();
+        let mut blocks: HashMap<u64, Value> = HashMap::new();
+        let mut upper = latest_block;
+        for k in (nonce.saturating_sub(MAX_TRANSACTIONS_SAMPLED)..nonce).rev() {
+            let block_number = self.block_of_nonce(address, k, first_block, upper).await?;
+            upper = block_number;
+            let block = match blocks.entry(block_number) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(self.rpc.call("eth_getBlockByNumber", json!([format!("{:#x}", block_number), true])).await?),
+            };
+
+            let transaction = block["transactions"].as_array().into_iter().flatten()
+                .find(|tx| tx["from"].as_str().is_some_and(|from| from.eq_ignore_ascii_case(address)) && parse_quantity(&tx["nonce"]) == Some(k));
+            activity.transactions_sampled += 1;
+            // Contract creations have no target and are counted as deployments
+            if let Some(to) = transaction.and_then(|tx| tx["to"].as_str()) {
+                targets.insert(to.to_lowercase());
+            }
+        }
+
+        let targets: Vec<String> = targets.into_iter().collect();
+        let calls: Vec<(&str, Value)> = targets.iter().map(|t| ("eth_getCode", json!([t, "latest"]))).collect();
+        let codes = self.rpc.call_batch(&calls).await?;
+        let mut protocols = BTreeSet::new();
+        for (target, code) in targets.iter().zip(codes) {
+            if !has_code(&code) {
+                continue; // Plain transfers to other accounts
+            }
+            activity.contracts_interacted += 1;
+            if activity.chain_id == 1 {
+                if let Some((_, protocol)) = KNOWN_PROTOCOLS.iter().find(|(known, _)| known == target) {
+                    protocols.insert(protocol.to_string());
+                }
+            }
+        }
+        activity.interacted_protocols = protocols.into_iter().collect();
+        Ok(())
+    }
+
+    /// The block holding the transaction with nonce `k`: the first whose state has a nonce above `k`
+    async fn block_of_nonce(&self, address: &str, k: u64, mut low: u64, mut high: u64) -> SourceResult<u64> {
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if self.rpc.nonce_at(address, middle).await? > k {
+                high = middle;
+            } else {
+                low = middle + 1;
+            }
+        }
+        Ok(low)
+    }
+
+    async fn block_timestamp(&self, block_number: u64) -> SourceResult<Option<DateTime<Utc>>> {
+        let block = self.rpc.call("eth_getBlockByNumber", json!([format!("{:#x}", block_number), false])).await?;
+        Ok(parse_quantity(&block["timestamp"]).and_then(|secs| Utc.timestamp_opt(secs as i64, 0).single()))
+    }
+}
+
+/// Repository that recorded this deployment, or whose contracts the verified sources are named after
+fn match_repository(address: &str, verified_sources: &[String], repos: &[RepositoryAnalysis]) -> Option<String> {
+    let contract_repos: Vec<(&String, &SmartContractAnalysis)> = repos.iter()
+        .filter_map(|repo| Some((&repo.name, repo.smart_contracts.as_ref()?)))
+        .collect();
+    let stems: Vec<&str> = verified_sources.iter().map(|name| name.split('.').next().unwrap_or(name)).collect();
+
+    contract_repos.iter()
+        .find(|(_, contracts)| contracts.deployed_addresses.iter().any(|a| a == address))
+        .or_else(|| contract_repos.iter().find(|(_, contracts)| contracts.contract_names.iter().any(|n| stems.contains(&n.as_str()))))
+        .map(|(name, _)| name.to_string())
+}
+
+/// Address of the contract `deployer` creates with `nonce`: keccak256(rlp([deployer, nonce]))[12..]
+pub fn create_address(deployer: &str, nonce: u64) -> String {
+    let deployer = hex::decode(deployer.trim_start_matches("0x")).unwrap_or_default();
+    let nonce_bytes: Vec<u8> = nonce.to_be_bytes().into_iter().skip_while(|b| *b == 0).collect();
+
+    let mut payload = vec![0x80 + deployer.len() as u8];
+    payload.extend(&deployer);
+    match nonce_bytes.as_slice() {
+        [] => payload.push(0x80),
+        [single] if *single < 0x80 => payload.push(*single),
+        bytes => {
+            payload.push(0x80 + bytes.len() as u8);
+            payload.extend(bytes);
+        }
+    }
+    let mut encoded = vec![0xc0 + payload.len() as u8];
+    encoded.extend(payload);
+
+    format!("0x{}", hex::encode(&Keccak256::digest(&encoded)[12..]))
+}
+
+fn parse_quantity(value: &Value) -> Option<u64> {
+    u64::from_str_radix(value.as_str()?.trim_start_matches("0x"), 16).ok()
+}
+
+fn has_code(value: &Value) -> bool {
+    value.as_str().is_some_and(|code| code.len() > 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use warp::Filter;
+
+    const WALLET: &str = "0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0";
+    const WETH: &str = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2";
+    const FRIEND: &str = "0x00000000000000000000000000000000000000aa";
+
+    /// A mainnet node where the wallet deploys a contract in block 5, then calls WETH and pays a friend in block 8
+    fn node(request: &Value, archive: bool) -> Value {
+        let params = &request["params"];
+        let result = match request["method"].as_str().unwrap_or_default() {
+            "eth_chainId" => json!("0x1"),
+            "eth_blockNumber" => json!("0xa"),
+            "eth_getTransactionCount" => match params[1].as_str() {
+                Some("latest") => json!("0x3"),
+                _ if !archive => return json!({ "jsonrpc": "2.0", "id": request["id"], "error": { "code": -32000, "message": "missing trie node" } }),
+                block => match parse_quantity(&json!(block)).unwrap_or_default() {
+                    8.. => json!("0x3"),
+                    5.. => json!("0x1"),
+                    _ => json!("0x0"),
+                },
+            },
+            "eth_getCode" => {
+                let target = params[0].as_str().unwrap_or_default();
+                json!(if target == WETH || target == create_address(WALLET, 0) { "0x6080" } else { "0x" })
+            }
+            "eth_getBlockByNumber" => {
+                let number = parse_quantity(&params[0]).unwrap_or_default();
+                let transactions = match number {
+                    5 => json!([{ "from": WALLET, "nonce": "0x0", "to": null }]),
+                    8 => json!([
+                        { "from": FRIEND, "nonce": "0x1", "to": WETH },
+                        { "from": WALLET, "nonce": "0x1", "to": WETH },
+                        { "from": WALLET, "nonce": "0x2", "to": FRIEND },
+                    ]),
+                    _ => json!([]),
+                };
+                json!({ "number": format!("{:#x}", number), "timestamp": format!("{:#x}", 1_600_000_000 + number * 12), "transactions": transactions })
+            }
+            _ => return json!({ "jsonrpc": "2.0", "id": request["id"], "error": { "code": -32601, "message": "method not found" } }),
+        };
+        json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })
+    }
+
+    async fn serve(archive: bool) -> String {
+        let route = warp::post().and(warp::body::json()).map(move |body: Value| {
+            warp::reply::json(&match body.as_array() {
+                Some(batch) => Value::Array(batch.iter().map(|request| node(request, archive)).collect()),
+                None => node(&body, archive),
+            })
+        });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        format!("http://{}", addr)
+    }
+
+    fn repo(name: &str, contracts: SmartContractAnalysis) -> RepositoryAnalysis {
+        let mut repo: RepositoryAnalysis = serde_json::from_value(json!({
+            "name": name, "languages": {}, "stars": 0, "forks": 0,
+            "architecture_score": 0.0, "documentation_score": 0.0, "testing_coverage": 0.0, "is_web3_project": true,
+        })).unwrap();
+        repo.smart_contracts = Some(contracts);
+        repo
+    }
+
+    #[tokio::test]
+    async fn reads_deployments_and_history_from_the_node() {
+        let deployed = create_address(WALLET, 0);
+        let repos = [repo("vault", SmartContractAnalysis { deployed_addresses: vec![deployed.clone()], ..Default::default() })];
+        let activity = OnchainAnalyzer::new(serve(true).await).analyze(&WALLET.to_uppercase(), &repos).await.unwrap();
+
+        assert_eq!(activity.chain_id, 1);
+        assert_eq!(activity.address, WALLET);
+        assert!(!activity.is_contract_wallet);
+        assert_eq!(activity.transaction_count, 3);
+        assert_eq!(activity.contracts_deployed.len(), 1);
+        assert_eq!(activity.contracts_deployed[0].address, deployed);
+        assert_eq!(activity.contracts_deployed[0].matched_repository.as_deref(), Some("vault"));
+        assert_eq!(activity.first_transaction_at, Utc.timestamp_opt(1_600_000_060, 0).single());
+        assert_eq!(activity.transactions_sampled, 3);
+        assert_eq!(activity.contracts_interacted, 1);
+        assert_eq!(activity.interacted_protocols, vec!["WETH"]);
+        assert!(activity.score > 0.0);
+    }
+
+    #[tokio::test]
+    async fn keeps_the_totals_without_an_archive_node() {
+        let activity = OnchainAnalyzer::new(serve(false).await).analyze(WALLET, &[]).await.unwrap();
+
+        assert_eq!(activity.transaction_count, 3);
+        assert_eq!(activity.contracts_deployed.len(), 1);
+        assert_eq!(activity.contracts_deployed[0].matched_repository, None);
+        assert_eq!(activity.first_transaction_at, None);
+        assert_eq!(activity.transactions_sampled, 0);
+        assert!(activity.interacted_protocols.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rpc_errors_surface_singly_and_null_in_batches() {
+        let rpc = EvmRpcClient::new(serve(true).await);
+
+        let error = rpc.call("eth_mine", json!([])).await.unwrap_err();
+        assert!(error.to_string().contains("method not found"));
+        let results = rpc.call_batch(&[("eth_mine", json!([])), ("eth_chainId", json!([]))]).await.unwrap();
+        assert_eq!(results, vec![Value::Null, json!("0x1")]);
+
+        assert!(OnchainAnalyzer::new("http://127.0.0.1:9").analyze("not-a-wallet", &[]).await.is_err());
+    }
+
+    #[test]
+    fn create_addresses_follow_the_rlp_encoding() {
+        assert_eq!(create_address(WALLET, 0), "0xcd234a471b72ba2f1ccf0a70fcaba648a5eecd8d");
+        assert_eq!(create_address(WALLET, 1), "0x343c43a37d37dff08ae8c4a11544c718abb4fcf8");
+        assert_eq!(create_address(WALLET, 2), "0xf778b86fa74e846c4f0a1fbd1335fe81c00a0c91");
+        assert_eq!(create_address(WALLET, 3), "0xfffd933a0bc612844eaf0c6fe3e5b8e9b6c1d19c");
+    }
+
+    #[test]
+    fn quantities_and_code_are_hex_strings() {
+        assert_eq!(parse_quantity(&json!("0x0")), Some(0));
+        assert_eq!(parse_quantity(&json!("0x1b4")), Some(436));
+        assert_eq!(parse_quantity(&json!(436)), None);
+        assert_eq!(parse_quantity(&json!("0xzz")), None);
+
+        assert!(has_code(&json!("0x6080")));
+        assert!(!has_code(&json!("0x")));
+        assert!(!has_code(&Value::Null));
+    }
+
+    #[test]
+    fn repositories_match_by_recorded_address_before_contract_name() {
+        let address = create_address(WALLET, 0);
+        let repos = [
+            repo("token", SmartContractAnalysis { contract_names: vec!["Vault".into()], ..Default::default() }),
+            repo("vault", SmartContractAnalysis { deployed_addresses: vec![address.clone()], ..Default::default() }),
+        ];
+        let sources = vec!["Vault.sol".to_string()];
+
+        assert_eq!(match_repository(&address, &sources, &repos).as_deref(), Some("vault"));
+        assert_eq!(match_repository(FRIEND, &sources, &repos).as_deref(), Some("token"));
+        assert_eq!(match_repository(FRIEND, &[], &repos), None);
+    }
+}
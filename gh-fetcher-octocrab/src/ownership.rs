@@ -0,0 +1,340 @@
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::analysis_cache::AnalysisCache;
+use crate::profile_source::SourceResult;
+
+/// How long the owner has to put the challenge in their bio or a gist
+const OWNERSHIP_CHALLENGE_TTL_HOURS: i64 = 24;
+/// Pending tokens kept per account; the oldest is dropped when another is issued
+const MAX_PENDING_CHALLENGES_PER_ACCOUNT: usize = 5;
+/// Pending tokens kept overall, whatever the account
+const MAX_PENDING_CHALLENGES: usize = 10_000;
+
+/// A token the account owner must publish on the account
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OwnershipChallenge {
+    pub username: String,
+    pub token: String,
+    pub instructions: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// How ownership of a GitHub account was proven
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OwnershipVerification {
+    pub username: String,
+    pub method: String, // "bio" or "gist"
+    pub proof_url: String,
+    pub verified_at: DateTime<Utc>,
+}
+
+/// Reads public proofs (bio, gists) straight from the GitHub API, bypassing the response cache
+#[derive(Clone)]
+pub struct GitHubProofClient {
+    http: reqwest::Client,
+    api: String,
+    token: Option<String>,
+}
+
+impl Default for GitHubProofClient {
+    fn default() -> Self {
+        Self {
+            http: reqwest::Client::builder().user_agent("idenzero-analyzer").build().unwrap_or_default(),
+            api: "https://api.github.com".to_string(),
+            token: None,
+        }
+    }
+}
+
+impl GitHubProofClient {
+    /// GitHub Enterprise API instead of api.github.com
+    pub fn with_api(mut self, url: impl Into<String>) -> Self {
+        self.api = url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Authenticated lookups, which otherwise share the unauthenticated rate limit
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// The first of `needles` found in the account's bio, with the profile URL
+    pub async fn bio_containing<'a>(&self, username: &str, needles: &'a [String]) -> SourceResult<Option<(&'a str, String)>> {
+        let user = self.get(&format!("/users/{}", username)).await?;
+        let bio = user["bio"].as_str().unwrap_or_default();
+        Ok(needles.iter()
+            .find(|needle| bio.contains(needle.as_str()))
+            .map(|needle| (needle.as_str(), user["html_url"].as_str().unwrap_or_default().to_string())))
+    }
+
+    /// Errors unless the gist belongs to `username` and one of its files contains `needle`
+    pub async fn check_gist(&self, gist_url: &str, username: &str, needle: &str) -> SourceResult<()> {
+        self.gist_containing(gist_url, username, &[needle.to_string()]).await.map(|_| ())
+    }
+
+    /// The first of `needles` found in a gist owned by `username`; errors if none is there
+    pub async fn gist_containing<'a>(&self, gist_url: &str, username: &str, needles: &'a [String]) -> SourceResult<&'a str> {
+        let gist_id = gist_url.split(['#', '?']).next().unwrap_or(gist_url)
+            .trim_end_matches('/')
+            .rsplit('/').next()
+            .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric()))
+            .ok_or_else(|| format!("{} is not a gist URL", gist_url))?;
+        let gist = self.get(&format!("/gists/{}", gist_id)).await?;
+
+        if !gist["owner"]["login"].as_str().is_some_and(|owner| owner.eq_ignore_ascii_case(username)) {
+            return Err(format!("gist {} is not owned by {}", gist_id, username).into());
+        }
+        let contents: Vec<String> = gist["files"].as_object().into_iter().flatten()
+            .filter_map(|(_, file)| file["content"].as_str().map(|content| content.to_lowercase()))
+            .collect();
+        needles.iter()
+            .find(|needle| {
+                let needle = needle.trim().to_lowercase();
+                contents.iter().any(|content| content.contains(&needle))
+            })
+            .map(|needle| needle.as_str())
+            .ok_or_else(|| format!("gist {} does not contain the expected proof", gist_id).into())
+    }
+
+    async fn get(&self, route: &str) -> SourceResult<Value> {
+        let mut request = self.http.get(format!("{}{}", self.api, route));
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        Ok(request.send().await?.error_for_status()?.json().await?)
+    }
+}
+
+/// Proves a requester controls a GitHub account before profiles are published as theirs
+pub struct OwnershipVerifier {
+    cache: Arc<AnalysisCache>,
+    // By token. Unexpired tokens stay valid up to the per-account cap, so requesting a few challenges
+    // for someone else's account does not invalidate the one its owner is publishing
+    challenges: Mutex<HashMap<String, OwnershipChallenge>>,
+    proofs: GitHubProofClient,
+}
+
+impl OwnershipVerifier {
+    pub fn new(cache: Arc<AnalysisCache>) -> Self {
+        Self { cache, challenges: Mutex::new(HashMap::new()), proofs: GitHubProofClient::default() }
+    }
+
+    pub fn with_proof_client(mut self, proofs: GitHubProofClient) -> Self {
+        self.proofs = proofs;
+        self
+    }
+
+    /// A token to add to the account's bio or a public gist; the latest few pending tokens stay valid
+    pub fn issue_challenge(&self, username: &str) -> SourceResult<OwnershipChallenge> {
+        let username = username.trim();
+        if username.is_empty() || !username.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(format!("{} is not a GitHub username", username).into());
+        }
+
+        let mut token = [0u8; 8];
+        rand::thread_rng().fill_bytes(&mut token);
+        let token = format!("idenzero-verify-{}", hex::encode(token));
+        let now = crate::clock::now();
+        let challenge = OwnershipChallenge {
+            username: username.to_string(),
+            instructions: format!(
+                "Add {} to the bio of github.com/{} or to a public gist owned by it, then submit. It can be removed once verified.",
+                token, username
+            ),
+            token,
+            expires_at: now + Duration::hours(OWNERSHIP_CHALLENGE_TTL_HOURS),
+        };
+
+        let mut challenges = self.challenges.lock().map_err(|_| "challenge store unavailable")?;
+        challenges.retain(|_, c| c.expires_at > now);
+        let pending = challenges.values().filter(|c| c.username.eq_ignore_ascii_case(username)).count();
+        if pending >= MAX_PENDING_CHALLENGES_PER_ACCOUNT {
+            evict_oldest(&mut challenges, Some(username));
+        }
+        if challenges.len() >= MAX_PENDING_CHALLENGES {
+            evict_oldest(&mut challenges, None);
+        }
+        challenges.insert(challenge.token.clone(), challenge.clone());
+        Ok(challenge)
+    }
+
+    /// Looks for any pending token of the account in the bio, or in `gist_url` when given, and records the owner
+    pub async fn verify(&self, username: &str, gist_url: Option<&str>) -> SourceResult<OwnershipVerification> {
+        let username = username.trim();
+        let now = crate::clock::now();
+        let tokens: Vec<String> = self.challenges.lock().map_err(|_| "challenge store unavailable")?
+            .values()
+            .filter(|c| c.username.eq_ignore_ascii_case(username) && c.expires_at > now)
+            .map(|c| c.token.clone())
+            .collect();
+        if tokens.is_empty() {
+            return Err("no pending ownership challenge for this account".into());
+        }
+
+        let (method, proof_url) = match gist_url {
+            Some(gist_url) => {
+                self.proofs.gist_containing(gist_url, username, &tokens).await?;
+                ("gist", gist_url.to_string())
+            }
+            None => match self.proofs.bio_containing(username, &tokens).await? {
+                Some((_, profile_url)) => ("bio", profile_url),
+                None => return Err(format!("the bio of {} does not contain a pending verification token", username).into()),
+            },
+        };
+
+        // Tokens stay pending after a failed check so the owner can retry once the bio or gist is updated
+        if let Ok(mut challenges) = self.challenges.lock() {
+            challenges.retain(|_, c| !c.username.eq_ignore_ascii_case(username));
+        }
+        let verification = OwnershipVerification {
+            username: username.to_string(),
+            method: method.to_string(),
+            proof_url,
+            verified_at: crate::clock::now(),
+        };
+        self.cache.put_owner_verification(&verification);
+        tracing::info!("Verified ownership of {} via {}", verification.username, verification.method);
+        Ok(verification)
+    }
+
+    pub fn verification(&self, username: &str) -> Option<OwnershipVerification> {
+        self.cache.owner_verification(username)
+    }
+
+    pub fn is_verified(&self, username: &str) -> bool {
+        self.verification(username).is_some()
+    }
+}
+
+/// Drops the challenge closest to expiry, of `username` when given
+fn evict_oldest(challenges: &mut HashMap<String, OwnershipChallenge>, username: Option<&str>) {
+    let oldest = challenges.values()
+        .filter(|c| username.is_none_or(|username| c.username.eq_ignore_ascii_case(username)))
+        .min_by_key(|c| c.expires_at)
+        .map(|c| c.token.clone());
+    if let Some(token) = oldest {
+        challenges.remove(&token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use warp::Filter;
+
+    /// Serves `/users/octocat` with whatever `bio` holds, and gist `abc123` owned by octocat with the same text
+    async fn github(bio: Arc<Mutex<String>>) -> String {
+        let users_bio = bio.clone();
+        let user = warp::path!("users" / String).map(move |login: String| {
+            warp::reply::json(&serde_json::json!({
+                "login": login,
+                "bio": users_bio.lock().unwrap().clone(),
+                "html_url": format!("https://github.com/{}", login)
+            }))
+        });
+        let gist = warp::path!("gists" / String).map(move |_id: String| {
+            warp::reply::json(&serde_json::json!({
+                "owner": { "login": "octocat" },
+                "files": { "proof.md": { "content": bio.lock().unwrap().clone() } }
+            }))
+        });
+        let (addr, server) = warp::serve(user.or(gist)).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        format!("http://{}", addr)
+    }
+
+    fn verifier(api: &str) -> OwnershipVerifier {
+        OwnershipVerifier::new(Arc::new(AnalysisCache::in_memory()))
+            .with_proof_client(GitHubProofClient::default().with_api(api))
+    }
+
+    #[tokio::test]
+    async fn later_challenges_do_not_invalidate_the_published_token() {
+        let bio = Arc::new(Mutex::new(String::new()));
+        let verifier = verifier(&github(bio.clone()).await);
+
+        let owners = verifier.issue_challenge("octocat").unwrap();
+        *bio.lock().unwrap() = format!("Rustacean. {}", owners.token);
+        // Someone else keeps asking for challenges on the same account
+        for _ in 0..3 {
+            assert_ne!(verifier.issue_challenge("OctoCat").unwrap().token, owners.token);
+        }
+
+        let verification = verifier.verify("octocat", None).await.unwrap();
+        assert_eq!(verification.method, "bio");
+        assert_eq!(verification.proof_url, "https://github.com/octocat");
+        assert!(verifier.is_verified("octocat"));
+        // Every pending token of the account is spent once it is verified
+        assert!(verifier.verify("octocat", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_and_expired_tokens() {
+        let bio = Arc::new(Mutex::new("no token here".to_string()));
+        let verifier = verifier(&github(bio.clone()).await);
+
+        assert!(verifier.verify("octocat", None).await.unwrap_err().to_string().contains("no pending"));
+        let challenge = verifier.issue_challenge("octocat").unwrap();
+        assert!(verifier.verify("octocat", None).await.unwrap_err().to_string().contains("does not contain"));
+
+        *bio.lock().unwrap() = challenge.token.clone();
+        verifier.challenges.lock().unwrap().get_mut(&challenge.token).unwrap().expires_at = crate::clock::now() - Duration::seconds(1);
+        assert!(verifier.verify("octocat", None).await.is_err());
+        assert!(!verifier.is_verified("octocat"));
+    }
+
+    #[tokio::test]
+    async fn verifies_through_a_gist() {
+        let bio = Arc::new(Mutex::new(String::new()));
+        let verifier = verifier(&github(bio.clone()).await);
+
+        let challenge = verifier.issue_challenge("octocat").unwrap();
+        *bio.lock().unwrap() = challenge.token.to_uppercase();
+        let verification = verifier.verify("octocat", Some("https://gist.github.com/octocat/abc123")).await.unwrap();
+        assert_eq!(verification.method, "gist");
+        assert!(verifier.issue_challenge("not a user").is_err());
+    }
+
+    #[test]
+    fn pending_tokens_are_capped_per_account() {
+        let verifier = verifier("http://127.0.0.1:9");
+        let tokens: Vec<String> = (0..MAX_PENDING_CHALLENGES_PER_ACCOUNT + 2).map(|_| {
+            // Age the earlier tokens so issue order decides which is oldest
+            for challenge in verifier.challenges.lock().unwrap().values_mut() {
+                challenge.expires_at -= Duration::seconds(1);
+            }
+            verifier.issue_challenge("octocat").unwrap().token
+        }).collect();
+        verifier.issue_challenge("hubot").unwrap();
+
+        let challenges = verifier.challenges.lock().unwrap();
+        assert_eq!(challenges.len(), MAX_PENDING_CHALLENGES_PER_ACCOUNT + 1);
+        // The two oldest tokens of the account went first
+        assert!(!challenges.contains_key(&tokens[0]) && !challenges.contains_key(&tokens[1]));
+        assert!(tokens[2..].iter().all(|token| challenges.contains_key(token)));
+    }
+
+    #[test]
+    fn oldest_token_goes_when_the_store_is_full() {
+        let mut challenges = HashMap::new();
+        for (i, username) in ["octocat", "hubot", "monalisa"].into_iter().enumerate() {
+            let token = format!("token-{}", i);
+            challenges.insert(token.clone(), OwnershipChallenge {
+                username: username.to_string(),
+                token,
+                instructions: String::new(),
+                expires_at: Utc::now() + Duration::hours(i as i64 + 1),
+            });
+        }
+        evict_oldest(&mut challenges, None);
+        assert!(!challenges.contains_key("token-0"));
+        evict_oldest(&mut challenges, Some("MonaLisa"));
+        assert_eq!(challenges.keys().collect::<Vec<_>>(), ["token-1"]);
+    }
+}
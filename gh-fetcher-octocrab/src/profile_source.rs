@@ -0,0 +1,850 @@
+use async_trait::async_trait;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use octocrab::{models::Repository, Octocrab};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::time::{sleep, Duration};
+use warp::http::header::{HeaderValue, ETAG, IF_NONE_MATCH};
+use warp::http::{HeaderMap, StatusCode};
+
+use crate::analysis_cache::AnalysisCache;
+use crate::api_usage::{ApiUsage, ApiUsageTracker, MAX_RATE_LIMIT_WAIT_SECS};
+use crate::authorship::AuthorIdentity;
+use crate::contribution_calendar::ContributionCalendar;
+use crate::issue_activity::IssueActivity;
+use crate::review_activity::ReviewActivity;
+use crate::graphql_fetcher::{ContributionStats, GraphQLFetcher};
+use crate::token_pool::TokenPool;
+
+/// Upper bound on commits pulled per repository by the forge sources
+pub const MAX_COMMITS_PER_REPO: usize = 500;
+
+/// Search result pages read for merged pull requests (search caps at 1000 results)
+const MAX_PULL_REQUEST_SEARCH_PAGES: u32 = 3;
+
+/// External repositories enriched with metadata, most-contributed first
+const MAX_EXTERNAL_REPOSITORIES: usize = 20;
+
+pub type SourceError = Box<dyn std::error::Error + Send + Sync>;
+pub type SourceResult<T> = std::result::Result<T, SourceError>;
+
+/// Source-neutral repository record produced by every `ProfileSource`
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RepositoryData {
+    pub name: String,
+    pub owner: String,
+    pub description: Option<String>,
+    pub primary_language: Option<String>,
+    pub languages: Option<HashMap<String, u64>>, // None when languages still need a per-repo fetch
+    pub topics: Vec<String>,
+    pub stars: u32,
+    pub forks: u32,
+    pub is_fork: bool,
+    pub homepage: Option<String>,
+    pub size_kb: Option<u32>,
+    pub default_branch: Option<String>,
+    pub commit_count: Option<u32>, // Commits on the default branch
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub pushed_at: Option<DateTime<Utc>>,
+}
+
+impl RepositoryData {
+    pub fn from_rest(repo: &Repository, fallback_owner: &str) -> Self {
+        Self {
+            name: repo.name.clone(),
+            owner: repo.owner.as_ref()
+                .map(|o| o.login.clone())
+                .unwrap_or_else(|| fallback_owner.to_string()),
+            description: repo.description.clone(),
+            primary_language: repo.language.as_ref()
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            languages: None,
+            topics: repo.topics.clone().unwrap_or_default(),
+            stars: repo.stargazers_count.unwrap_or(0),
+            forks: repo.forks_count.unwrap_or(0),
+            is_fork: repo.fork.unwrap_or(false),
+            homepage: repo.homepage.clone().filter(|h| !h.is_empty()),
+            size_kb: repo.size,
+            default_branch: repo.default_branch.clone(),
+            commit_count: None,
+            created_at: repo.created_at,
+            updated_at: repo.updated_at,
+            pushed_at: repo.pushed_at,
+        }
+    }
+}
+
+/// A single commit as reported by a source
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommitRecord {
+    pub sha: String,
+    pub message: String,
+    pub author_name: Option<String>,
+    pub author_email: Option<String>,
+    pub author_login: Option<String>,
+    pub date: DateTime<Utc>,
+    #[serde(default)]
+    pub signed: Option<bool>, // None when the source does not report signatures
+}
+
+/// Work the user landed in a repository they do not own
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ExternalContribution {
+    pub owner: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub primary_language: Option<String>,
+    pub stars: u32,
+    pub merged_pull_requests: u32,
+    pub authored_commits: u32,
+    pub last_contributed_at: Option<DateTime<Utc>>,
+}
+
+impl ExternalContribution {
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.name)
+    }
+}
+
+/// Account-level numbers that are not derivable from repositories
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct UserStats {
+    pub followers: u32,
+    pub following: u32,
+    pub public_repos: u32,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Where profile data comes from. GitHub is the default implementation; other
+/// forges plug in so candidates hosting elsewhere can still be analyzed.
+#[async_trait]
+pub trait ProfileSource: Send + Sync {
+    /// Human readable source name, e.g. "GitHub"
+    fn name(&self) -> &str;
+
+    /// All repositories owned by the user (forks included, the analyzer filters them)
+    async fn fetch_repositories(&self, username: &str) -> SourceResult<Vec<RepositoryData>>;
+
+    /// Language byte counts for a repository
+    async fn fetch_languages(&self, repo: &RepositoryData) -> SourceResult<HashMap<String, u64>>;
+
+    /// Commits in a repository, optionally restricted to an author (the account login) and a start date
+    async fn fetch_commits(
+        &self,
+        repo: &RepositoryData,
+        author: Option<&str>,
+        since: Option<DateTime<Utc>>,
+    ) -> SourceResult<Vec<CommitRecord>>;
+
+    async fn fetch_user_stats(&self, username: &str) -> SourceResult<UserStats>;
+
+    /// Every file path in the repository's default branch, when the source can list it
+    async fn fetch_file_tree(&self, _repo: &RepositoryData) -> SourceResult<Option<Vec<String>>> {
+        Ok(None)
+    }
+
+    /// README of the default branch; None when there is none or the source cannot serve it
+    async fn fetch_readme(&self, _repo: &RepositoryData) -> SourceResult<Option<String>> {
+        Ok(None)
+    }
+
+    /// Contents of one file on the default branch; None when it is missing or the source cannot serve it
+    async fn fetch_file(&self, _repo: &RepositoryData, _path: &str) -> SourceResult<Option<String>> {
+        Ok(None)
+    }
+
+    /// Aggregate contribution totals, when the source offers them
+    async fn fetch_contribution_stats(&self, _username: &str) -> SourceResult<Option<ContributionStats>> {
+        Ok(None)
+    }
+
+    /// Logins, emails and names under which the user's commits appear
+    async fn fetch_author_identity(&self, username: &str) -> SourceResult<AuthorIdentity> {
+        Ok(AuthorIdentity::for_login(username))
+    }
+
+    /// Day-by-day contribution counts, when the source keeps a calendar
+    async fn fetch_contribution_calendar(&self, _username: &str) -> SourceResult<Option<ContributionCalendar>> {
+        Ok(None)
+    }
+
+    /// Pull requests authored and reviews given, for sources with code review
+    async fn fetch_review_activity(&self, _username: &str) -> SourceResult<Option<ReviewActivity>> {
+        Ok(None)
+    }
+
+    /// Issues opened, commented on, triaged and answered, for sources with an issue tracker
+    async fn fetch_issue_activity(&self, _username: &str) -> SourceResult<Option<IssueActivity>> {
+        Ok(None)
+    }
+
+    /// Merged pull requests and authored commits in repositories owned by someone else
+    async fn fetch_external_contributions(&self, _username: &str) -> SourceResult<Vec<ExternalContribution>> {
+        Ok(Vec::new())
+    }
+
+    /// Requests made and rate-limit budget, for sources that meter their API
+    fn api_usage(&self) -> Option<ApiUsage> {
+        None
+    }
+}
+
+/// How `GitHubSource` pulls profile data from GitHub
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FetchStrategy {
+    #[default]
+    Rest,    // Paged REST listing plus one languages call per repository
+    GraphQL, // Batched GraphQL v4 queries - a handful of requests per profile
+}
+
+impl FetchStrategy {
+    /// Reads `IDENZERO_FETCH_STRATEGY` ("rest" or "graphql"), defaulting to REST
+    pub fn from_env() -> Self {
+        match std::env::var("IDENZERO_FETCH_STRATEGY").map(|v| v.to_lowercase()) {
+            Ok(v) if v == "graphql" => FetchStrategy::GraphQL,
+            _ => FetchStrategy::Rest,
+        }
+    }
+}
+
+/// GitHub implementation of `ProfileSource` backed by Octocrab
+pub struct GitHubSource {
+    pub client: Octocrab, // First token's client, for work that spends no budget
+    tokens: Arc<TokenPool>,
+    fetch_strategy: FetchStrategy,
+    // Contribution totals captured as a by-product of GraphQL repository fetches
+    contribution_stats: Mutex<HashMap<String, ContributionStats>>,
+    // Requests this source made; the pool's totals include every source sharing it
+    usage: Arc<ApiUsageTracker>,
+    // ETag'd bodies for conditional requests; 304 answers do not count against the rate limit
+    response_cache: Option<Arc<AnalysisCache>>,
+}
+
+impl GitHubSource {
+    pub fn new(token: String) -> octocrab::Result<Self> {
+        let client = Octocrab::builder()
+            .personal_token(token)
+            .build()?;
+
+        Ok(Self::from_client(client))
+    }
+
+    /// GitHub source talking to a different API root (GitHub Enterprise or a fixture server)
+    pub fn with_base_url(token: String, base_url: &str) -> octocrab::Result<Self> {
+        let client = Octocrab::builder()
+            .personal_token(token)
+            .base_uri(base_url)?
+            .build()?;
+
+        Ok(Self::from_client(client))
+    }
+
+    pub fn from_client(client: Octocrab) -> Self {
+        Self::with_token_pool(Arc::new(TokenPool::from_client(client)))
+    }
+
+    /// GitHub source spreading its requests over several tokens; the pool can be shared between sources
+    pub fn with_token_pool(tokens: Arc<TokenPool>) -> Self {
+        Self {
+            client: tokens.primary_client(),
+            tokens,
+            fetch_strategy: FetchStrategy::from_env(),
+            contribution_stats: Mutex::new(HashMap::new()),
+            usage: Arc::new(ApiUsageTracker::new()),
+            response_cache: None,
+        }
+    }
+
+    pub fn token_pool(&self) -> &Arc<TokenPool> {
+        &self.tokens
+    }
+
+    /// Revalidate GET requests against bodies stored in `cache` using ETags
+    pub fn with_response_cache(mut self, cache: Arc<AnalysisCache>) -> Self {
+        self.response_cache = Some(cache);
+        self
+    }
+
+    pub fn with_fetch_strategy(mut self, fetch_strategy: FetchStrategy) -> Self {
+        self.fetch_strategy = fetch_strategy;
+        self
+    }
+
+    pub fn fetch_strategy(&self) -> FetchStrategy {
+        self.fetch_strategy
+    }
+
+    async fn fetch_rest_repositories(&self, username: &str) -> SourceResult<Vec<RepositoryData>> {
+        let mut all_repos = Vec::new();
+        let mut page = 1u32;
+        let per_page = 100usize;
+
+        loop {
+            let route = format!("/users/{}/repos?per_page={}&page={}", username, per_page, page);
+            // A partial listing would read as a smaller account, so any failed page fails the fetch
+            let repos: Vec<Repository> = self.get_json(&route).await.map_err(|e| {
+                tracing::error!("Failed to fetch repositories for {}: {}", username, e);
+                e
+            })?;
+
+            if repos.is_empty() {
+                break;
+            }
+
+            let items_len = repos.len();
+            all_repos.extend(repos.iter().map(|repo| RepositoryData::from_rest(repo, username)));
+
+            if items_len < per_page {
+                break; // Last page
+            }
+
+            page += 1;
+            sleep(Duration::from_millis(200)).await; // Rate limiting
+        }
+
+        Ok(all_repos)
+    }
+
+    /// GET a JSON route, waiting out a rate limit once before giving up
+    async fn get_json<R: serde::de::DeserializeOwned>(&self, route: &str) -> SourceResult<R> {
+        let mut attempt = 0;
+        loop {
+            match self.send_get(route).await {
+                Ok(body) => return Ok(serde_json::from_str(&body)?),
+                Err(e) if attempt == 0 && is_rate_limit_error(&e.source) => {
+                    tracing::warn!("Rate limited on {}", route);
+                    self.wait_for_rate_limit_reset().await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Decoded body of a contents-style route (readme, contents/{path}); None on 404
+    async fn get_file_contents(&self, route: &str) -> SourceResult<Option<String>> {
+        let response: serde_json::Value = match self.get_json(route).await {
+            Ok(response) => response,
+            Err(e) if has_status(e.as_ref(), StatusCode::NOT_FOUND) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        // Base64 with line breaks every 60 characters
+        let encoded: String = response["content"].as_str().unwrap_or_default().split_whitespace().collect();
+        let decoded = base64::engine::general_purpose::STANDARD.decode(encoded)?;
+        Ok(Some(String::from_utf8_lossy(&decoded).into_owned()))
+    }
+
+    /// Single metered GET through the token pool, which picks the token, throttles on a low budget,
+    /// records the rate-limit headers and fails over; cached bodies are revalidated by ETag
+    async fn send_get(&self, route: &str) -> Result<String, GitHubRequestError> {
+        // Search has its own, much smaller, rate-limit bucket
+        let resource = if route.starts_with("/search/") { "search" } else { "core" };
+
+        let cached = self.response_cache.as_ref().and_then(|cache| cache.get_response(route));
+        let mut headers = HeaderMap::new();
+        if let Some(value) = cached.as_ref().and_then(|c| HeaderValue::from_str(&c.etag).ok()) {
+            headers.insert(IF_NONE_MATCH, value);
+        }
+
+        let response = self.tokens.send(resource, Some(&self.usage), |client| {
+            let headers = headers.clone();
+            async move { client._get_with_headers(route, Some(headers)).await }
+        }).await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let (Some(cache), Some(cached)) = (&self.response_cache, cached) {
+                cache.touch_response(route);
+                return Ok(cached.body);
+            }
+        }
+
+        let etag = response.headers().get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+        let status = response.status();
+        let response = octocrab::map_github_error(response).await
+            .map_err(|source| GitHubRequestError { status: Some(status), source })?;
+        let body = self.client.body_to_string(response).await?;
+
+        if let (Some(cache), Some(etag)) = (&self.response_cache, etag) {
+            cache.put_response(route, &etag, &body);
+        }
+        Ok(body)
+    }
+
+    /// Waits until a rate-limited token can be used again; returns at once while another one is usable
+    async fn wait_for_rate_limit_reset(&self) {
+        // Secondary limits bench a token for `retry-after` or a minute, primary ones until the window resets
+        let Some(available_at) = self.tokens.next_available() else {
+            return;
+        };
+
+        let wait_secs = (available_at.timestamp() - Utc::now().timestamp()).clamp(1, MAX_RATE_LIMIT_WAIT_SECS);
+        tracing::info!("Waiting {}s for GitHub rate limit reset", wait_secs);
+        sleep(Duration::from_secs(wait_secs as u64)).await;
+    }
+}
+
+#[async_trait]
+impl ProfileSource for GitHubSource {
+    fn name(&self) -> &str {
+        "GitHub"
+    }
+
+    async fn fetch_repositories(&self, username: &str) -> SourceResult<Vec<RepositoryData>> {
+        match self.fetch_strategy {
+            FetchStrategy::Rest => self.fetch_rest_repositories(username).await,
+            FetchStrategy::GraphQL => {
+                let profile = GraphQLFetcher::new(self.client.clone())
+                    .with_token_pool(self.tokens.clone(), self.usage.clone())
+                    .fetch_profile(username)
+                    .await?;
+                if let Ok(mut stats) = self.contribution_stats.lock() {
+                    stats.insert(username.to_string(), profile.contribution_stats);
+                }
+                Ok(profile.repositories)
+            }
+        }
+    }
+
+    async fn fetch_languages(&self, repo: &RepositoryData) -> SourceResult<HashMap<String, u64>> {
+        self.get_json(&format!("/repos/{}/{}/languages", repo.owner, repo.name)).await
+    }
+
+    async fn fetch_commits(
+        &self,
+        repo: &RepositoryData,
+        author: Option<&str>,
+        since: Option<DateTime<Utc>>,
+    ) -> SourceResult<Vec<CommitRecord>> {
+        let mut commits = Vec::new();
+        let mut page = 1u32;
+
+        loop {
+            let mut route = format!("/repos/{}/{}/commits?per_page=100&page={}", repo.owner, repo.name, page);
+            if let Some(author) = author {
+                route.push_str(&format!("&author={}", author));
+            }
+            if let Some(since) = since {
+                route.push_str(&format!("&since={}", since.format("%Y-%m-%dT%H:%M:%SZ")));
+            }
+
+            let items: Vec<serde_json::Value> = match self.get_json(&route).await {
+                Ok(items) => items,
+                // Empty repositories answer 409 Conflict
+                Err(e) if has_status(e.as_ref(), StatusCode::CONFLICT) => break,
+                Err(e) => return Err(e),
+            };
+            let items_len = items.len();
+            commits.extend(items.iter().filter_map(parse_github_commit));
+
+            if items_len < 100 || commits.len() >= MAX_COMMITS_PER_REPO {
+                break;
+            }
+            page += 1;
+        }
+
+        commits.truncate(MAX_COMMITS_PER_REPO);
+        Ok(commits)
+    }
+
+    async fn fetch_file_tree(&self, repo: &RepositoryData) -> SourceResult<Option<Vec<String>>> {
+        let reference = repo.default_branch.as_deref().unwrap_or("HEAD");
+        let route = format!("/repos/{}/{}/git/trees/{}?recursive=1", repo.owner, repo.name, reference);
+        let response: serde_json::Value = match self.get_json(&route).await {
+            Ok(response) => response,
+            // Empty repositories answer 409 Conflict
+            Err(e) if has_status(e.as_ref(), StatusCode::CONFLICT) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        if response["truncated"].as_bool() == Some(true) {
+            tracing::debug!("File tree of {}/{} is truncated, inspecting the partial listing", repo.owner, repo.name);
+        }
+        Ok(Some(response["tree"].as_array().into_iter().flatten()
+            .filter(|entry| entry["type"].as_str() == Some("blob"))
+            .filter_map(|entry| entry["path"].as_str().map(|p| p.to_string()))
+            .collect()))
+    }
+
+    async fn fetch_readme(&self, repo: &RepositoryData) -> SourceResult<Option<String>> {
+        self.get_file_contents(&format!("/repos/{}/{}/readme", repo.owner, repo.name)).await
+    }
+
+    async fn fetch_file(&self, repo: &RepositoryData, path: &str) -> SourceResult<Option<String>> {
+        let reference = repo.default_branch.as_deref().unwrap_or("HEAD");
+        self.get_file_contents(&format!("/repos/{}/{}/contents/{}?ref={}", repo.owner, repo.name, path, reference)).await
+    }
+
+    async fn fetch_user_stats(&self, username: &str) -> SourceResult<UserStats> {
+        let response: serde_json::Value = self.get_json(&format!("/users/{}", username)).await?;
+
+        Ok(UserStats {
+            followers: response["followers"].as_u64().unwrap_or(0) as u32,
+            following: response["following"].as_u64().unwrap_or(0) as u32,
+            public_repos: response["public_repos"].as_u64().unwrap_or(0) as u32,
+            created_at: parse_timestamp(&response["created_at"]),
+        })
+    }
+
+    async fn fetch_contribution_stats(&self, username: &str) -> SourceResult<Option<ContributionStats>> {
+        Ok(self.contribution_stats.lock()
+            .ok()
+            .and_then(|stats| stats.get(username).cloned()))
+    }
+
+    async fn fetch_author_identity(&self, username: &str) -> SourceResult<AuthorIdentity> {
+        let user: serde_json::Value = self.get_json(&format!("/users/{}", username)).await?;
+        let mut identity = AuthorIdentity::for_login(username)
+            .with_emails(user["email"].as_str());
+
+        // Emails on GPG keys are only listed as verified once GitHub confirmed them
+        match self.get_json::<Vec<serde_json::Value>>(&format!("/users/{}/gpg_keys", username)).await {
+            Ok(keys) => {
+                let verified = keys.iter()
+                    .filter_map(|key| key["emails"].as_array())
+                    .flatten()
+                    .filter(|e| e["verified"].as_bool() == Some(true))
+                    .filter_map(|e| e["email"].as_str());
+                identity = identity.with_emails(verified);
+            }
+            Err(e) => tracing::debug!("No GPG key emails for {}: {}", username, e),
+        }
+
+        Ok(identity)
+    }
+
+    async fn fetch_contribution_calendar(&self, username: &str) -> SourceResult<Option<ContributionCalendar>> {
+        let fetcher = GraphQLFetcher::new(self.client.clone()).with_token_pool(self.tokens.clone(), self.usage.clone());
+        Ok(Some(fetcher.fetch_contribution_calendar(username).await?))
+    }
+
+    async fn fetch_review_activity(&self, username: &str) -> SourceResult<Option<ReviewActivity>> {
+        let fetcher = GraphQLFetcher::new(self.client.clone()).with_token_pool(self.tokens.clone(), self.usage.clone());
+        Ok(Some(fetcher.fetch_review_activity(username).await?))
+    }
+
+    async fn fetch_issue_activity(&self, username: &str) -> SourceResult<Option<IssueActivity>> {
+        let fetcher = GraphQLFetcher::new(self.client.clone()).with_token_pool(self.tokens.clone(), self.usage.clone());
+        let mut activity = fetcher.fetch_issue_activity(username).await?;
+
+        // Triage actions only show up in the public events feed (last 90 days, 300 events)
+        for page in 1..=3 {
+            let events: Vec<serde_json::Value> = match self.get_json(&format!("/users/{}/events/public?per_page=100&page={}", username, page)).await {
+                Ok(events) => events,
+                Err(e) => {
+                    tracing::debug!("No public events for {}: {}", username, e);
+                    break;
+                }
+            };
+            activity.issues_labeled += events.iter().filter(|event| is_triage_event(event, username)).count() as u32;
+            if events.len() < 100 {
+                break;
+            }
+        }
+
+        Ok(Some(activity))
+    }
+
+    async fn fetch_external_contributions(&self, username: &str) -> SourceResult<Vec<ExternalContribution>> {
+        let mut contributions: HashMap<String, ExternalContribution> = HashMap::new();
+
+        // Merged pull requests outside the user's namespace
+        for page in 1..=MAX_PULL_REQUEST_SEARCH_PAGES {
+            let route = format!(
+                "/search/issues?q=author:{}+type:pr+is:merged+-user:{}&sort=updated&per_page=100&page={}",
+                username, username, page
+            );
+            let response: serde_json::Value = self.get_json(&route).await?;
+            let items = response["items"].as_array().cloned().unwrap_or_default();
+
+            for item in &items {
+                let Some((owner, name)) = item["repository_url"].as_str().and_then(repository_from_api_url) else {
+                    continue;
+                };
+                let merged_at = parse_timestamp(&item["pull_request"]["merged_at"])
+                    .or_else(|| parse_timestamp(&item["closed_at"]));
+                let entry = external_entry(&mut contributions, owner, name);
+                entry.merged_pull_requests += 1;
+                entry.last_contributed_at = entry.last_contributed_at.max(merged_at);
+            }
+
+            if items.len() < 100 {
+                break;
+            }
+        }
+
+        // Commits pushed directly (maintainer access, or landed without a PR)
+        let route = format!("/search/commits?q=author:{}+-user:{}&sort=author-date&per_page=100", username, username);
+        let response: serde_json::Value = self.get_json(&route).await?;
+        for item in response["items"].as_array().into_iter().flatten() {
+            let repository = &item["repository"];
+            if repository["fork"].as_bool().unwrap_or(false) {
+                continue; // Someone else's fork, not the upstream project
+            }
+            let (Some(owner), Some(name)) = (repository["owner"]["login"].as_str(), repository["name"].as_str()) else {
+                continue;
+            };
+            let date = parse_timestamp(&item["commit"]["author"]["date"]);
+            let entry = external_entry(&mut contributions, owner, name);
+            entry.authored_commits += 1;
+            entry.last_contributed_at = entry.last_contributed_at.max(date);
+        }
+
+        let mut contributions: Vec<ExternalContribution> = contributions.into_values().collect();
+        contributions.sort_by(|a, b| {
+            (b.merged_pull_requests, b.authored_commits, a.full_name())
+                .cmp(&(a.merged_pull_requests, a.authored_commits, b.full_name()))
+        });
+        contributions.truncate(MAX_EXTERNAL_REPOSITORIES);
+
+        for contribution in &mut contributions {
+            let route = format!("/repos/{}/{}", contribution.owner, contribution.name);
+            match self.get_json::<serde_json::Value>(&route).await {
+                Ok(repo) => {
+                    contribution.description = repo["description"].as_str().map(|s| s.to_string());
+                    contribution.primary_language = repo["language"].as_str().map(|s| s.to_string());
+                    contribution.stars = repo["stargazers_count"].as_u64().unwrap_or(0) as u32;
+                }
+                Err(e) => tracing::debug!("No metadata for {}: {}", contribution.full_name(), e),
+            }
+        }
+
+        Ok(contributions)
+    }
+
+    /// This source's own requests against the budget left across the whole pool
+    fn api_usage(&self) -> Option<ApiUsage> {
+        Some(ApiUsage {
+            requests_made: self.usage.snapshot().requests_made,
+            rate_limits: self.tokens.usage().rate_limits,
+        })
+    }
+}
+
+fn external_entry<'a>(
+    contributions: &'a mut HashMap<String, ExternalContribution>,
+    owner: &str,
+    name: &str,
+) -> &'a mut ExternalContribution {
+    contributions.entry(format!("{}/{}", owner, name).to_lowercase())
+        .or_insert_with(|| ExternalContribution {
+            owner: owner.to_string(),
+            name: name.to_string(),
+            ..Default::default()
+        })
+}
+
+/// "https://api.github.com/repos/{owner}/{name}" -> (owner, name)
+fn repository_from_api_url(url: &str) -> Option<(&str, &str)> {
+    let (_, path) = url.split_once("/repos/")?;
+    let mut parts = path.split('/');
+    Some((parts.next()?, parts.next()?))
+}
+
+/// Labeling, assigning or closing an issue someone else opened
+fn is_triage_event(event: &serde_json::Value, username: &str) -> bool {
+    let payload = &event["payload"];
+    event["type"].as_str() == Some("IssuesEvent")
+        && matches!(payload["action"].as_str(), Some("labeled" | "assigned" | "closed"))
+        && payload["issue"]["user"]["login"].as_str().is_some_and(|author| !author.eq_ignore_ascii_case(username))
+}
+
+fn parse_github_commit(item: &serde_json::Value) -> Option<CommitRecord> {
+    let commit = &item["commit"];
+    let date = parse_timestamp(&commit["author"]["date"])
+        .or_else(|| parse_timestamp(&commit["committer"]["date"]))?;
+
+    Some(CommitRecord {
+        sha: item["sha"].as_str()?.to_string(),
+        message: commit["message"].as_str().unwrap_or_default().to_string(),
+        author_name: commit["author"]["name"].as_str().map(|s| s.to_string()),
+        author_email: commit["author"]["email"].as_str().map(|s| s.to_string()),
+        author_login: item["author"]["login"].as_str().map(|s| s.to_string()),
+        date,
+        signed: commit["verification"]["verified"].as_bool(),
+    })
+}
+
+pub(crate) fn parse_timestamp(value: &serde_json::Value) -> Option<DateTime<Utc>> {
+    value.as_str()
+        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Failed GitHub request, keeping the HTTP status that octocrab's error drops
+#[derive(Debug)]
+pub struct GitHubRequestError {
+    pub status: Option<StatusCode>,
+    pub source: octocrab::Error,
+}
+
+impl From<octocrab::Error> for GitHubRequestError {
+    fn from(source: octocrab::Error) -> Self {
+        Self { status: None, source }
+    }
+}
+
+impl std::fmt::Display for GitHubRequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+impl std::error::Error for GitHubRequestError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Whether a request failed with the given HTTP status
+pub(crate) fn has_status(error: &(dyn std::error::Error + Send + Sync + 'static), status: StatusCode) -> bool {
+    error.downcast_ref::<GitHubRequestError>().is_some_and(|e| e.status == Some(status))
+}
+
+pub(crate) fn is_rate_limit_error(error: &octocrab::Error) -> bool {
+    match error {
+        octocrab::Error::GitHub { source, .. } => source.message.to_lowercase().contains("rate limit"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use warp::Filter;
+
+    /// GitHub stand-in: `empty` is an empty repository, `broken` fails with a message mentioning "empty",
+    /// `hello` has a language breakdown;
+    /// `newcomer` owns no repositories while listing those of `octocat` fails
+    async fn github() -> String {
+        let listing = warp::path!("users" / String / "repos").map(|user: String| {
+            let (status, body) = match user.as_str() {
+                "newcomer" => (200, "[]"),
+                _ => (502, r#"{"message":"Server Error","documentation_url":""}"#),
+            };
+            warp::http::Response::builder()
+                .status(status)
+                .header("content-type", "application/json")
+                .body(body)
+                .unwrap()
+        });
+        let route = warp::path!("repos" / "octocat" / String / ..).map(|repo: String| {
+            let (status, body) = match repo.as_str() {
+                "empty" => (409, r#"{"message":"Git Repository is empty.","documentation_url":""}"#),
+                "missing" => (404, r#"{"message":"Not Found","documentation_url":""}"#),
+                "hello" => (200, r#"{"Rust":48213,"Shell":1024}"#),
+                _ => (500, r#"{"message":"Not Found: cache empty","documentation_url":""}"#),
+            };
+            warp::http::Response::builder()
+                .status(status)
+                .header("content-type", "application/json")
+                .body(body)
+                .unwrap()
+        });
+        let (addr, server) = warp::serve(listing.or(route)).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        format!("http://{}", addr)
+    }
+
+    fn repo(name: &str) -> RepositoryData {
+        RepositoryData { name: name.to_string(), owner: "octocat".to_string(), ..Default::default() }
+    }
+
+    #[tokio::test]
+    async fn empty_repository_conflict_is_not_an_error() {
+        let source = GitHubSource::with_base_url("token".to_string(), &github().await).unwrap();
+        assert_eq!(source.fetch_file_tree(&repo("empty")).await.unwrap(), None);
+        assert!(source.fetch_commits(&repo("empty"), None, None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn missing_file_is_none() {
+        let source = GitHubSource::with_base_url("token".to_string(), &github().await).unwrap();
+        assert_eq!(source.fetch_readme(&repo("missing")).await.unwrap(), None);
+        assert_eq!(source.fetch_file(&repo("missing"), "package.json").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn other_failures_are_errors_whatever_the_message() {
+        let source = GitHubSource::with_base_url("token".to_string(), &github().await).unwrap();
+        let error = source.fetch_file_tree(&repo("broken")).await.unwrap_err();
+        assert!(!has_status(error.as_ref(), StatusCode::CONFLICT));
+        assert!(has_status(error.as_ref(), StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(source.fetch_readme(&repo("broken")).await.is_err());
+        assert!(source.fetch_commits(&repo("broken"), None, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn languages_are_byte_counts() {
+        let source = GitHubSource::with_base_url("token".to_string(), &github().await).unwrap();
+        let languages = source.fetch_languages(&repo("hello")).await.unwrap();
+        assert_eq!(languages, HashMap::from([("Rust".to_string(), 48213), ("Shell".to_string(), 1024)]));
+        assert!(source.fetch_languages(&repo("broken")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn failed_repository_listing_is_an_error() {
+        let source = GitHubSource::with_base_url("token".to_string(), &github().await).unwrap();
+        assert!(source.fetch_repositories("newcomer").await.unwrap().is_empty());
+        assert!(source.fetch_repositories("octocat").await.is_err());
+    }
+
+    /// Search and repository endpoints for `dev`, who landed pull requests in rust-lang/rust and
+    /// tokio-rs/tokio, pushed a commit to tokio and one to someone's fork
+    async fn contributions_api() -> String {
+        let pulls = warp::path!("search" / "issues").map(|| warp::reply::json(&serde_json::json!({ "items": [
+            { "repository_url": "https://api.github.com/repos/rust-lang/rust", "pull_request": { "merged_at": "2024-01-05T00:00:00Z" } },
+            { "repository_url": "https://api.github.com/repos/Rust-Lang/rust", "pull_request": { "merged_at": "2024-03-05T00:00:00Z" } },
+            { "repository_url": "https://api.github.com/repos/rust-lang/rust", "pull_request": {}, "closed_at": "2024-02-05T00:00:00Z" },
+            { "repository_url": "https://api.github.com/repos/tokio-rs/tokio", "pull_request": { "merged_at": "2023-06-01T00:00:00Z" } },
+            { "repository_url": "not a repository" },
+        ] })));
+        let commits = warp::path!("search" / "commits").map(|| warp::reply::json(&serde_json::json!({ "items": [
+            { "repository": { "owner": { "login": "tokio-rs" }, "name": "tokio", "fork": false },
+              "commit": { "author": { "date": "2024-04-01T00:00:00Z" } } },
+            { "repository": { "owner": { "login": "someone" }, "name": "tokio", "fork": true },
+              "commit": { "author": { "date": "2024-05-01T00:00:00Z" } } },
+        ] })));
+        let repo = warp::path!("repos" / String / String).map(|owner: String, name: String| {
+            let (status, body) = match (owner.as_str(), name.as_str()) {
+                ("rust-lang", "rust") => (200, r#"{"description":"The Rust language","language":"Rust","stargazers_count":90000}"#),
+                _ => (404, r#"{"message":"Not Found","documentation_url":""}"#),
+            };
+            warp::http::Response::builder().status(status).header("content-type", "application/json").body(body).unwrap()
+        });
+        let (addr, server) = warp::serve(pulls.or(commits).or(repo)).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn external_contributions_are_grouped_per_repository() {
+        let source = GitHubSource::with_base_url("token".to_string(), &contributions_api().await).unwrap();
+        let contributions = source.fetch_external_contributions("dev").await.unwrap();
+
+        assert_eq!(contributions.iter().map(|c| c.full_name()).collect::<Vec<_>>(), ["rust-lang/rust", "tokio-rs/tokio"]);
+        let rust = &contributions[0];
+        assert_eq!((rust.merged_pull_requests, rust.authored_commits), (3, 0));
+        assert_eq!(rust.last_contributed_at, parse_timestamp(&serde_json::json!("2024-03-05T00:00:00Z")));
+        assert_eq!((rust.primary_language.as_deref(), rust.stars), (Some("Rust"), 90000));
+
+        // Commits to forks are not upstream work; missing metadata leaves the counts alone
+        let tokio = &contributions[1];
+        assert_eq!((tokio.merged_pull_requests, tokio.authored_commits), (1, 1));
+        assert_eq!(tokio.last_contributed_at, parse_timestamp(&serde_json::json!("2024-04-01T00:00:00Z")));
+        assert_eq!(tokio.primary_language, None);
+    }
+
+    #[test]
+    fn repository_names_come_from_api_urls() {
+        assert_eq!(repository_from_api_url("https://api.github.com/repos/rust-lang/rust"), Some(("rust-lang", "rust")));
+        assert_eq!(repository_from_api_url("https://ghe.example.com/api/v3/repos/a/b/issues"), Some(("a", "b")));
+        assert_eq!(repository_from_api_url("https://api.github.com/users/a"), None);
+    }
+}
@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+
+/// Headings kept per README; enough to show its structure
+const MAX_SECTIONS: usize = 20;
+
+/// What a repository's README and docs directory cover
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ReadmeAnalysis {
+    pub word_count: u32,
+    pub section_count: u32,
+    pub sections: Vec<String>, // The first MAX_SECTIONS headings
+    pub has_installation: bool,
+    pub has_usage: bool,
+    pub badge_count: u32,
+    pub code_examples: u32,
+    pub link_count: u32,
+    pub has_docs_directory: bool,
+    pub score: f64, // 0-95, the repository description supplies the rest of documentation_score
+}
+
+impl ReadmeAnalysis {
+    /// Parses a Markdown README; `has_docs_directory` comes from the file tree when known
+    pub fn from_markdown(content: &str, has_docs_directory: bool) -> Self {
+        let mut sections = Vec::new();
+        let mut code_examples = 0;
+        let mut in_code = false;
+        let mut prose = String::new();
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                if !in_code {
+                    code_examples += 1;
+                }
+                in_code = !in_code;
+                continue;
+            }
+            if in_code {
+                continue;
+            }
+            if trimmed.starts_with('#') {
+                let heading = trimmed.trim_start_matches('#').trim();
+                if !heading.is_empty() {
+                    sections.push(heading.to_string());
+                }
+            }
+            prose.push_str(trimmed);
+            prose.push('\n');
+        }
+
+        let lower_sections: Vec<String> = sections.iter().map(|s| s.to_lowercase()).collect();
+        let has_section = |keywords: &[&str]| lower_sections.iter().any(|s| keywords.iter().any(|k| s.contains(k)));
+        let section_count = sections.len() as u32;
+        sections.truncate(MAX_SECTIONS);
+
+        let mut analysis = Self {
+            word_count: prose.split_whitespace().count() as u32,
+            section_count,
+            has_installation: has_section(&["install", "getting started", "setup", "set up", "quick start", "quickstart", "build"]),
+            has_usage: has_section(&["usage", "example", "how to", "tutorial", "running", "api"]),
+            sections,
+            badge_count: (prose.matches("[![").count() + prose.matches("<img src=\"https://img.shields.io").count()) as u32,
+            code_examples,
+            link_count: (prose.matches("](http").count() + prose.matches("href=\"http").count()) as u32,
+            has_docs_directory,
+            score: 0.0,
+        };
+        analysis.score = analysis.calculate_score();
+        analysis
+    }
+
+    fn calculate_score(&self) -> f64 {
+        let length: f64 = match self.word_count {
+            500.. => 20.0,
+            200..=499 => 15.0,
+            50..=199 => 8.0,
+            1..=49 => 2.0,
+            0 => 0.0,
+        };
+        let structure = (self.section_count.min(6) as f64 / 6.0) * 15.0;
+        let installation = if self.has_installation { 15.0 } else { 0.0 };
+        let usage = if self.has_usage { 15.0 } else { 0.0 };
+        let examples = (self.code_examples.min(3) as f64 / 3.0) * 10.0;
+        let badges = if self.badge_count > 0 { 5.0 } else { 0.0 };
+        let links = (self.link_count.min(5) as f64 / 5.0) * 5.0;
+        let docs = if self.has_docs_directory { 10.0 } else { 0.0 };
+
+        length + structure + installation + usage + examples + badges + links + docs
+    }
+
+    /// One-line summary for profile cards
+    pub fn insights(&self) -> String {
+        let mut parts = vec![format!("{} in {}", counted(self.word_count, "word"), counted(self.section_count, "section"))];
+        match (self.has_installation, self.has_usage) {
+            (true, true) => parts.push("installation and usage guides".to_string()),
+            (true, false) => parts.push("installation guide".to_string()),
+            (false, true) => parts.push("usage guide".to_string()),
+            (false, false) => {}
+        }
+        if self.code_examples > 0 {
+            parts.push(counted(self.code_examples, "code example"));
+        }
+        if self.badge_count > 0 {
+            parts.push(counted(self.badge_count, "badge"));
+        }
+        if self.has_docs_directory {
+            parts.push("dedicated docs directory".to_string());
+        }
+        format!("README: {}", parts.join(", "))
+    }
+}
+
+/// A top-level `docs/`, `doc/` or `documentation/` directory
+pub fn has_docs_directory(paths: &[String]) -> bool {
+    paths.iter().any(|path| {
+        path.split_once('/')
+            .is_some_and(|(top, _)| matches!(top.to_lowercase().as_str(), "docs" | "doc" | "documentation"))
+    })
+}
+
+fn counted(count: u32, noun: &str) -> String {
+    format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" })
+}
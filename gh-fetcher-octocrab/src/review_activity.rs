@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Pull requests the user authored and reviews they gave, across every repository
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ReviewActivity {
+    pub pull_requests_authored: u32,
+    pub pull_requests_merged: u32,
+    pub pull_requests_closed_unmerged: u32,
+    pub merge_rate: Option<f64>, // Merged share of decided (merged or closed) pull requests
+    pub median_hours_to_merge: Option<f64>, // Over the most recent merged pull requests
+    pub reviews_given: u32, // Last year
+    pub review_comments: u32,
+    pub repositories_reviewed: u32,
+    pub reviews_of_others: u32, // Reviews on pull requests someone else opened
+}
+
+impl ReviewActivity {
+    pub fn decided_pull_requests(&self) -> u32 {
+        self.pull_requests_merged + self.pull_requests_closed_unmerged
+    }
+
+    /// Data points behind the collaboration signal
+    pub fn evidence_count(&self) -> u32 {
+        self.pull_requests_authored + self.reviews_given + self.review_comments
+    }
+}
+
+pub fn merge_rate(merged: u32, closed_unmerged: u32) -> Option<f64> {
+    let decided = merged + closed_unmerged;
+    (decided > 0).then(|| merged as f64 / decided as f64)
+}
+
+/// Median hours between opening and merging
+pub fn median_hours_to_merge(merged: &[(DateTime<Utc>, DateTime<Utc>)]) -> Option<f64> {
+    let mut hours: Vec<f64> = merged.iter()
+        .map(|(created, merged)| (*merged - *created).num_minutes() as f64 / 60.0)
+        .filter(|h| *h >= 0.0)
+        .collect();
+    if hours.is_empty() {
+        return None;
+    }
+    hours.sort_by(|a, b| a.total_cmp(b));
+
+    let mid = hours.len() / 2;
+    Some(if hours.len().is_multiple_of(2) { (hours[mid - 1] + hours[mid]) / 2.0 } else { hours[mid] })
+}
@@ -0,0 +1,377 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::local_source::is_vendored_path;
+use crate::tree_inspection::is_test_path;
+
+/// Contract sources and test files read per repository, shallowest first
+pub const MAX_CONTRACTS_PER_REPO: usize = 6;
+pub const MAX_CONTRACT_TESTS_PER_REPO: usize = 4;
+pub const MAX_DEPLOYMENT_RECORDS_PER_REPO: usize = 4;
+
+/// Framework configs, matched on the file name
+const CONTRACT_FRAMEWORKS: [(&str, &str); 8] = [
+    ("foundry.toml", "Foundry"), ("hardhat.config.js", "Hardhat"), ("hardhat.config.ts", "Hardhat"), ("hardhat.config.cjs", "Hardhat"),
+    ("truffle-config.js", "Truffle"), ("brownie-config.yaml", "Brownie"), ("ape-config.yaml", "Ape"), ("Anchor.toml", "Anchor"),
+];
+
+/// Fuzzing harness configs, matched on the file name
+const FUZZER_CONFIGS: [&str; 4] = ["echidna.yaml", "echidna.config.yml", "medusa.json", "halmos.toml"];
+
+/// Starter contracts shipped by Hardhat, Foundry, Truffle and Remix templates
+const TEMPLATE_CONTRACTS: [&str; 9] = [
+    "Lock.sol", "Greeter.sol", "Counter.sol", "SimpleStorage.sol", "HelloWorld.sol", "Storage.sol", "Owner.sol", "Ballot.sol", "Migrations.sol",
+];
+
+/// Repository names and descriptions of courses and walkthroughs
+const TUTORIAL_KEYWORDS: [&str; 9] = [
+    "tutorial", "course", "bootcamp", "workshop", "learning", "speedrun", "scaffold-eth", "cryptozombies", "hello-world",
+];
+
+/// Upgradeability markers in contract sources
+const PROXY_PATTERNS: [(&str, &str); 8] = [
+    ("UUPSUpgradeable", "UUPS"), ("TransparentUpgradeableProxy", "Transparent proxy"), ("UpgradeableBeacon", "Beacon proxy"),
+    ("BeaconProxy", "Beacon proxy"), ("IDiamondCut", "Diamond"), ("ERC1967", "ERC-1967"), ("Initializable", "Initializable"),
+    ("openzeppelin-contracts-upgradeable", "Initializable"),
+];
+
+/// How seriously a repository engineers its smart contracts, from its layout and a few sampled sources
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SmartContractAnalysis {
+    pub frameworks: Vec<String>, // "Foundry", "Hardhat", ...
+    pub contract_files: u32, // Solidity, Vyper or Anchor program sources outside tests, scripts and `lib/`
+    #[serde(default)]
+    pub contract_names: Vec<String>, // File stems of those sources, e.g. "Vault"
+    pub test_files: u32,
+    pub test_suites: Vec<String>, // Frameworks whose tests are present
+    pub fuzz_tests: u32, // Parameterized Foundry tests and Echidna properties in the sampled tests
+    pub invariant_tests: u32,
+    pub fuzzer_configs: Vec<String>,
+    pub uses_openzeppelin: bool,
+    pub upgradeable_patterns: Vec<String>,
+    pub deployment_scripts: u32,
+    #[serde(default)]
+    pub deployed_addresses: Vec<String>, // Lowercased, from committed Foundry broadcasts and hardhat-deploy records
+    pub audits: Vec<String>, // Audit directories or reports
+    pub tutorial: bool, // Only starter-template contracts, or a course/tutorial repository
+    pub score: f64, // 0-100
+}
+
+impl SmartContractAnalysis {
+    /// None when the repository has no contracts and no contract framework config
+    pub fn from_tree(paths: &[String]) -> Option<Self> {
+        let mut analysis = Self::default();
+        let mut frameworks = BTreeSet::new();
+        let mut audits = BTreeSet::new();
+        let mut contract_names = BTreeSet::new();
+        let mut contract_stems = BTreeSet::new();
+        let mut has_foundry_tests = false;
+        let mut has_script_tests = false;
+
+        // Vendored copies and Foundry submodules
+        analysis.uses_openzeppelin = paths.iter().any(|p| p.to_lowercase().contains("openzeppelin"));
+
+        for path in paths.iter().filter(|p| !is_vendored_path(p) && !is_library_path(p)) {
+            let file_name = path.rsplit('/').next().unwrap_or(path);
+            if let Some((_, framework)) = CONTRACT_FRAMEWORKS.iter().find(|(config, _)| *config == file_name) {
+                frameworks.insert(framework.to_string());
+            }
+            if FUZZER_CONFIGS.contains(&file_name) {
+                analysis.fuzzer_configs.push(path.clone());
+            }
+            if let Some(audit) = audit_path(path) {
+                audits.insert(audit);
+            }
+
+            if is_deployment_script(path) {
+                analysis.deployment_scripts += 1;
+            } else if is_contract_test(path) {
+                analysis.test_files += 1;
+                if file_name.ends_with(".t.sol") {
+                    has_foundry_tests = true;
+                } else if !file_name.ends_with(".sol") {
+                    has_script_tests = true;
+                }
+            } else if is_contract_source(path) {
+                analysis.contract_files += 1;
+                contract_stems.insert(file_name.split('.').next().unwrap_or(file_name).to_string());
+                // Remix numbers its starters ("1_Storage.sol")
+                contract_names.insert(file_name.trim_start_matches(|c: char| c.is_ascii_digit() || c == '_').to_string());
+            }
+        }
+
+        if analysis.contract_files == 0 && frameworks.is_empty() {
+            return None;
+        }
+
+        // JS/TS/Python tests only belong to a contract suite when a framework runs them
+        if has_foundry_tests {
+            analysis.test_suites.push("Foundry".to_string());
+        }
+        if has_script_tests {
+            analysis.test_suites.extend(frameworks.iter().filter(|f| *f != "Foundry").cloned());
+        }
+        if analysis.test_suites.is_empty() {
+            analysis.test_files = 0;
+        }
+
+        analysis.tutorial = !contract_names.is_empty()
+            && contract_names.iter().all(|name| TEMPLATE_CONTRACTS.contains(&name.as_str()));
+        analysis.frameworks = frameworks.into_iter().collect();
+        analysis.contract_names = contract_stems.into_iter().collect();
+        analysis.audits = audits.into_iter().collect();
+        Some(analysis)
+    }
+
+    /// Flags course and walkthrough repositories by name or description
+    pub fn note_repository(&mut self, name: &str, description: Option<&str>) {
+        let text = format!("{} {}", name, description.unwrap_or("")).to_lowercase();
+        if TUTORIAL_KEYWORDS.iter().any(|keyword| text.contains(keyword)) {
+            self.tutorial = true;
+        }
+    }
+
+    /// OpenZeppelin imports and upgradeability patterns in a contract source
+    pub fn add_contract(&mut self, content: &str) {
+        if content.contains("@openzeppelin/") || content.contains("openzeppelin-contracts") {
+            self.uses_openzeppelin = true;
+        }
+        for (marker, pattern) in PROXY_PATTERNS {
+            if content.contains(marker) && !self.upgradeable_patterns.iter().any(|p| p == pattern) {
+                self.upgradeable_patterns.push(pattern.to_string());
+            }
+        }
+        // Hand-rolled proxies forward calls to an implementation slot
+        if content.contains("delegatecall") && content.contains("implementation") && self.upgradeable_patterns.is_empty() {
+            self.upgradeable_patterns.push("Custom proxy".to_string());
+        }
+    }
+
+    /// Counts fuzz and invariant test functions in a test source
+    pub fn add_test(&mut self, content: &str) {
+        let test_function = Regex::new(r"function\s+(test\w*|invariant\w*|statefulFuzz\w*|echidna_\w*)\s*\(([^)]*)\)").expect("valid regex");
+        for captures in test_function.captures_iter(content) {
+            let (name, params) = (&captures[1], captures[2].trim());
+            if name.starts_with("invariant") || name.starts_with("statefulFuzz") {
+                self.invariant_tests += 1;
+            } else if name.starts_with("echidna_") || name.starts_with("testFuzz") || !params.is_empty() {
+                self.fuzz_tests += 1;
+            }
+        }
+    }
+
+    /// OpenZeppelin in Foundry's `remappings.txt` or `.gitmodules`
+    pub fn add_dependency_config(&mut self, content: &str) {
+        if content.to_lowercase().contains("openzeppelin") {
+            self.uses_openzeppelin = true;
+        }
+    }
+
+    /// Contract addresses in a Foundry `run-latest.json` or a hardhat-deploy record
+    pub fn add_deployment_record(&mut self, content: &str) {
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(content) else { return };
+        let foundry = ["transactions", "receipts"].into_iter()
+            .flat_map(|key| json[key].as_array().into_iter().flatten())
+            .filter_map(|entry| entry["contractAddress"].as_str());
+        for address in json["address"].as_str().into_iter().chain(foundry) {
+            let address = address.to_lowercase();
+            if is_evm_address(&address) && !self.deployed_addresses.contains(&address) {
+                self.deployed_addresses.push(address);
+            }
+        }
+    }
+
+    pub fn finish(mut self) -> Self {
+        let mut score = (self.contract_files.min(10) * 2) as f64;
+        if self.test_files > 0 {
+            score += 10.0 + self.test_files.min(10) as f64;
+        }
+        if self.fuzz_tests > 0 || !self.fuzzer_configs.is_empty() {
+            score += 10.0;
+        }
+        if self.invariant_tests > 0 {
+            score += 10.0;
+        }
+        if self.uses_openzeppelin {
+            score += 5.0;
+        }
+        if !self.upgradeable_patterns.is_empty() {
+            score += 10.0;
+        }
+        if self.deployment_scripts > 0 {
+            score += 10.0;
+        }
+        if !self.audits.is_empty() {
+            score += 15.0;
+        }
+        if self.tutorial {
+            score *= 0.3;
+        }
+
+        self.score = score.min(100.0);
+        self
+    }
+}
+
+/// Foundry installs dependencies (forge-std, OpenZeppelin) under a top-level `lib/`
+fn is_library_path(path: &str) -> bool {
+    path.starts_with("lib/")
+}
+
+pub fn is_contract_source(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let solidity = (file_name.ends_with(".sol") && !file_name.ends_with(".t.sol") && !file_name.ends_with(".s.sol"))
+        || file_name.ends_with(".vy");
+    // Anchor programs are Rust crates under `programs/`
+    let anchor = path.starts_with("programs/") && file_name.ends_with(".rs");
+    (solidity || anchor) && !is_test_path(path) && !is_vendored_path(path) && !is_library_path(path)
+}
+
+pub fn is_contract_test(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let language = [".sol", ".js", ".ts", ".py", ".vy"].iter().any(|ext| file_name.ends_with(ext));
+    language && is_test_path(path) && !is_vendored_path(path) && !is_library_path(path)
+}
+
+/// Foundry broadcast logs and hardhat-deploy deployment records, which name deployed addresses
+pub fn is_deployment_record(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    (path.starts_with("broadcast/") && file_name == "run-latest.json")
+        || (path.starts_with("deployments/") && file_name.ends_with(".json") && !path.contains("/solcInputs/") && !file_name.starts_with('.'))
+}
+
+/// "0x" followed by 40 hex digits
+pub fn is_evm_address(value: &str) -> bool {
+    value.len() == 42 && value.starts_with("0x") && value[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Foundry scripts, hardhat-deploy and Ignition modules, Truffle migrations and `scripts/deploy*`
+fn is_deployment_script(path: &str) -> bool {
+    let segments: Vec<&str> = path.split('/').collect();
+    let file_name = segments.last().copied().unwrap_or(path);
+    let directories = &segments[..segments.len() - 1];
+    let script = [".js", ".ts", ".py", ".sol"].iter().any(|ext| file_name.ends_with(ext));
+
+    file_name.ends_with(".s.sol")
+        || (script && directories.iter().any(|d| matches!(*d, "deploy" | "migrations" | "ignition")))
+        || (script && directories.iter().any(|d| matches!(*d, "scripts" | "script")) && file_name.to_lowercase().starts_with("deploy"))
+}
+
+/// The audits directory a path sits in, or the path itself for a standalone audit report
+fn audit_path(path: &str) -> Option<String> {
+    let lower = path.to_lowercase();
+    let segments: Vec<&str> = lower.split('/').collect();
+    if let Some(index) = segments.iter().position(|s| matches!(*s, "audit" | "audits" | "security-reviews")) {
+        return Some(path.split('/').take(index + 1).collect::<Vec<_>>().join("/"));
+    }
+    let file_name = segments.last().copied().unwrap_or(&lower);
+    (file_name.contains("audit") && (file_name.ends_with(".pdf") || file_name.ends_with(".md"))).then(|| path.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn reads_a_foundry_project_layout() {
+        let paths = tree(&[
+            "foundry.toml", "src/Vault.sol", "src/interfaces/IVault.sol", "test/Vault.t.sol", "script/Deploy.s.sol",
+            "lib/openzeppelin-contracts/contracts/token/ERC20/ERC20.sol", "lib/forge-std/src/Test.sol",
+            "echidna.yaml", "audits/2024-01-spearbit.pdf", "broadcast/Deploy.s.sol/1/run-latest.json",
+        ]);
+        let analysis = SmartContractAnalysis::from_tree(&paths).unwrap();
+        assert_eq!(analysis.frameworks, ["Foundry"]);
+        assert_eq!((analysis.contract_files, analysis.test_files, analysis.deployment_scripts), (2, 1, 1));
+        assert_eq!(analysis.contract_names, ["IVault", "Vault"]);
+        assert_eq!(analysis.test_suites, ["Foundry"]);
+        assert_eq!(analysis.fuzzer_configs, ["echidna.yaml"]);
+        assert_eq!(analysis.audits, ["audits"]);
+        assert!(analysis.uses_openzeppelin && !analysis.tutorial);
+        assert!(is_deployment_record("broadcast/Deploy.s.sol/1/run-latest.json"));
+    }
+
+    #[test]
+    fn starter_templates_are_tutorials() {
+        let hardhat = SmartContractAnalysis::from_tree(&tree(&["hardhat.config.ts", "contracts/Lock.sol", "test/Lock.ts"])).unwrap();
+        assert!(hardhat.tutorial);
+        assert_eq!(hardhat.test_suites, ["Hardhat"]);
+
+        let remix = SmartContractAnalysis::from_tree(&tree(&["contracts/1_Storage.sol", "contracts/3_Ballot.sol"])).unwrap();
+        assert!(remix.tutorial);
+
+        let mut course = SmartContractAnalysis::from_tree(&tree(&["contracts/Market.sol"])).unwrap();
+        assert!(!course.tutorial);
+        course.note_repository("solidity-bootcamp", None);
+        assert!(course.tutorial);
+    }
+
+    #[test]
+    fn repositories_without_contracts_are_skipped() {
+        assert!(SmartContractAnalysis::from_tree(&tree(&["src/main.rs", "lib/x/Token.sol", "node_modules/a/B.sol"])).is_none());
+        // Test scripts without a contract framework are not a contract test suite
+        let bare = SmartContractAnalysis::from_tree(&tree(&["contracts/Token.sol", "test/token.js"])).unwrap();
+        assert_eq!((bare.test_files, bare.test_suites.len()), (0, 0));
+    }
+
+    #[test]
+    fn finds_proxies_and_openzeppelin_in_sources() {
+        let mut analysis = SmartContractAnalysis::default();
+        analysis.add_contract(r#"
+            import "@openzeppelin/contracts-upgradeable/proxy/utils/UUPSUpgradeable.sol";
+            import "@openzeppelin/contracts-upgradeable/proxy/utils/Initializable.sol";
+            contract Vault is Initializable, UUPSUpgradeable {}
+        "#);
+        assert!(analysis.uses_openzeppelin);
+        assert_eq!(analysis.upgradeable_patterns, ["UUPS", "Initializable"]);
+
+        let mut custom = SmartContractAnalysis::default();
+        custom.add_contract("fallback() external { address implementation = _impl; (bool ok,) = implementation.delegatecall(msg.data); }");
+        assert_eq!(custom.upgradeable_patterns, ["Custom proxy"]);
+    }
+
+    #[test]
+    fn counts_fuzz_and_invariant_tests() {
+        let mut analysis = SmartContractAnalysis::default();
+        analysis.add_test(r#"
+            function setUp() public {}
+            function test_deposit() public {}
+            function testFuzz_withdraw(uint256 amount) public {}
+            function test_transfer(address to, uint96 amount) public {}
+            function invariant_solvent() public {}
+            function statefulFuzz_supply() public {}
+            function echidna_balance_under_cap() public returns (bool) {}
+        "#);
+        assert_eq!((analysis.fuzz_tests, analysis.invariant_tests), (3, 2));
+    }
+
+    #[test]
+    fn collects_deployed_addresses_from_records() {
+        let mut analysis = SmartContractAnalysis::default();
+        analysis.add_deployment_record(r#"{"transactions":[{"contractAddress":"0x5FbDB2315678afecb367f032d93F642f64180aa3"},{"contractAddress":null}],
+            "receipts":[{"contractAddress":"0x5fbdb2315678afecb367f032d93f642f64180aa3"}]}"#);
+        analysis.add_deployment_record(r#"{"address":"0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512","abi":[]}"#);
+        analysis.add_deployment_record("not json");
+        assert_eq!(analysis.deployed_addresses, [
+            "0x5fbdb2315678afecb367f032d93f642f64180aa3",
+            "0xe7f1725e7734ce288f8367e1bb143e90bb3f0512",
+        ]);
+        assert!(!is_evm_address("0x1234"));
+    }
+
+    #[test]
+    fn tutorials_score_lower() {
+        let engineered = SmartContractAnalysis {
+            contract_files: 5, test_files: 4, fuzz_tests: 2, invariant_tests: 1, uses_openzeppelin: true,
+            deployment_scripts: 1, audits: vec!["audits".to_string()], ..Default::default()
+        }.finish();
+        assert_eq!(engineered.score, 10.0 + 14.0 + 10.0 + 10.0 + 5.0 + 10.0 + 15.0);
+
+        let tutorial = SmartContractAnalysis { contract_files: 1, tutorial: true, ..Default::default() }.finish();
+        assert!((tutorial.score - 0.6).abs() < 1e-9);
+    }
+}
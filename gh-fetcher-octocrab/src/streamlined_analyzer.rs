@@ -1,7 +1,13 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
-use crate::analyzer::{GitHubAnalyzer, SkillAnalysis, UserProfile};
+use crate::analyzer::{GitHubAnalyzer, RepositoryAnalysis, SkillAnalysis, UserProfile};
 use crate::iden_score::{IdenScoreCalculator, IdenScore};
+use crate::profile_source::ProfileSource;
+use crate::api_usage::ApiUsage;
+use crate::analysis_cache::AnalysisCache;
+use crate::token_pool::TokenPool;
+use crate::contribution_calendar::ContributionActivity;
+use std::sync::Arc;
 
 /// Streamlined developer profile with essential information only
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -16,6 +22,8 @@ pub struct StreamlinedProfile {
     pub github_stats: GitHubStats,
     pub badges: Vec<Badge>, // Achievement badges
     pub iden_score: IdenScoreSummary, // Complete IdenScore summary
+    #[serde(default)]
+    pub verified_owner: bool, // Whoever publishes it proved they control the GitHub account
     pub last_updated: DateTime<Utc>,
 }
 
@@ -111,6 +119,10 @@ pub struct ContributionInfo {
     pub stars: u32,
     pub primary_language: Option<String>,
     pub readme_insights: Option<String>, // Key insights from README
+    #[serde(default)]
+    pub external: bool, // Contribution to someone else's repository
+    #[serde(default)]
+    pub merged_pull_requests: u32,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -119,10 +131,12 @@ pub struct GitHubStats {
     pub followers: u32,
     pub following: u32,
     pub years_active: f64,
-    pub total_commits: u32, // Estimated
+    pub total_commits: u32, // From the contribution calendar, estimated without one
     pub total_stars: u32,   // Total stars across all repos
     pub total_forks: u32,   // Total forks across all repos
-    pub contribution_streak: u32, // Days of recent activity
+    pub contribution_streak: u32, // Current run of consecutive active days
+    #[serde(default)]
+    pub contribution_activity: Option<ContributionActivity>,
 }
 
 pub struct StreamlinedAnalyzer {
@@ -140,6 +154,39 @@ impl StreamlinedAnalyzer {
         })
     }
 
+    /// Streamlined analyzer spreading its GitHub requests over a shared token pool
+    pub fn with_token_pool(tokens: Arc<TokenPool>) -> Self {
+        Self {
+            github_analyzer: GitHubAnalyzer::with_token_pool(tokens),
+            iden_score_calculator: IdenScoreCalculator::new(),
+        }
+    }
+
+    /// Over a shared token pool and a cache shared with other analyzers, so ownership
+    /// verifications and wallet bindings recorded through them show up here too
+    pub fn with_token_pool_and_cache(tokens: Arc<TokenPool>, cache: Arc<AnalysisCache>) -> Self {
+        Self {
+            github_analyzer: GitHubAnalyzer::with_token_pool_and_cache(tokens, cache),
+            iden_score_calculator: IdenScoreCalculator::new(),
+        }
+    }
+
+    /// Streamlined analyzer over a non-default `ProfileSource` (e.g. GitLab or Gitea)
+    pub fn with_source(source: Arc<dyn ProfileSource>) -> Self {
+        Self {
+            github_analyzer: GitHubAnalyzer::with_source(source),
+            iden_score_calculator: IdenScoreCalculator::new(),
+        }
+    }
+
+    pub fn api_usage(&self) -> Option<ApiUsage> {
+        self.github_analyzer.api_usage()
+    }
+
+    pub fn cache(&self) -> &Arc<AnalysisCache> {
+        self.github_analyzer.cache()
+    }
+
     /// Get streamlined profile for a user
     pub async fn get_profile(&mut self, username: String) -> Result<StreamlinedProfile, Box<dyn std::error::Error>> {
         let profile = UserProfile {
@@ -148,7 +195,8 @@ impl StreamlinedAnalyzer {
         };
 
         // Get full analysis
-        let analysis = self.github_analyzer.analyze(profile).await?;
+        let analysis = self.github_analyzer.analyze(profile).await
+            .map_err(|e| e as Box<dyn std::error::Error>)?;
         
         // Calculate IdenScore
         let iden_score = self.iden_score_calculator.calculate_iden_score(&analysis);
@@ -181,23 +229,17 @@ impl StreamlinedAnalyzer {
             github_stats,
             badges,
             iden_score: iden_score_summary,
-            last_updated: chrono::Utc::now(),
+            verified_owner: analysis.verified_owner,
+            last_updated: crate::clock::now(),
         };
 
         Ok(streamlined)
     }
 
     async fn fetch_github_user_stats(&self, username: &str) -> Result<(u32, u32), Box<dyn std::error::Error>> {
-        use serde_json::Value;
-        
-        let response: Value = self.github_analyzer.client
-            .get(&format!("/users/{}", username), None::<&()>)
-            .await?;
-            
-        let followers = response["followers"].as_u64().unwrap_or(0) as u32;
-        let following = response["following"].as_u64().unwrap_or(0) as u32;
-        
-        Ok((followers, following))
+        let stats = self.github_analyzer.source().fetch_user_stats(username).await
+            .map_err(|e| e as Box<dyn std::error::Error>)?;
+        Ok((stats.followers, stats.following))
     }
 
     fn generate_summary(&self, analysis: &SkillAnalysis) -> String {
@@ -288,7 +330,7 @@ impl StreamlinedAnalyzer {
     }
 
     fn analyze_contributions_from_analysis(&self, analysis: &SkillAnalysis) -> Vec<ContributionInfo> {
-        analysis.repository_analysis
+        let owned = analysis.repository_analysis
             .iter()
             .take(5)
             .map(|repo| ContributionInfo {
@@ -296,12 +338,32 @@ impl StreamlinedAnalyzer {
                 description: repo.description.clone().unwrap_or("No description available".to_string()),
                 stars: repo.stars,
                 primary_language: repo.primary_language.clone(),
-                readme_insights: None, // Simplified
-            })
-            .collect()
+                readme_insights: repo.readme.as_ref().map(|readme| readme.insights()),
+                external: false,
+                merged_pull_requests: 0,
+            });
+
+        // Upstream work, already ordered by merged PRs then commits
+        let external = analysis.external_contributions
+            .iter()
+            .take(3)
+            .map(|contribution| ContributionInfo {
+                repository: contribution.full_name(),
+                description: contribution.description.clone().unwrap_or("No description available".to_string()),
+                stars: contribution.stars,
+                primary_language: contribution.primary_language.clone(),
+                readme_insights: None,
+                external: true,
+                merged_pull_requests: contribution.merged_pull_requests,
+            });
+
+        owned.chain(external).collect()
     }
 
-    fn estimate_total_commits(&self, analysis: &SkillAnalysis) -> u32 {
+    fn total_commits(&self, analysis: &SkillAnalysis) -> u32 {
+        if let Some(activity) = &analysis.contribution_activity {
+            return activity.commit_contributions;
+        }
         // Rough estimation based on repositories and activity
         (analysis.total_repositories as f64 * analysis.years_active * 50.0) as u32
     }
@@ -453,7 +515,7 @@ impl StreamlinedAnalyzer {
             
             // Framework detection (very strong indicators)
             let framework_matches = ai_ml_frameworks.iter().filter(|fw| {
-                desc.contains(**fw) || name.contains(**fw)
+                desc.contains(**fw) || name.contains(**fw) || declares_technology(repo, fw)
             }).count();
             
             if framework_matches > 0 {
@@ -618,10 +680,30 @@ impl StreamlinedAnalyzer {
             }
         }
         
+        // Hygiene the user practices in their own repositories is direct evidence
+        let inspected: Vec<_> = analysis.repository_analysis.iter().filter_map(|r| r.security_hygiene.as_ref()).collect();
+        if !inspected.is_empty() {
+            let avg_hygiene = inspected.iter().map(|h| h.score).sum::<f64>() / inspected.len() as f64;
+            score += avg_hygiene * 0.3; // Max 30 points
+
+            let practices = [
+                ("Security policy", inspected.iter().any(|h| h.security_policy.is_some())),
+                ("Automated dependency updates", inspected.iter().any(|h| h.dependency_update_bot.is_some())),
+                ("Pinned CI actions", inspected.iter().any(|h| h.ci_actions > 0 && h.pinned_ci_actions == h.ci_actions)),
+                ("Signed commits", inspected.iter().any(|h| h.commits_checked > 0 && h.signed_commits * 2 >= h.commits_checked)),
+            ];
+            technologies.extend(practices.iter().filter(|(_, present)| *present).map(|(practice, _)| practice.to_string()));
+
+            // Leaked credentials undercut any security claim
+            let leaking = inspected.iter().filter(|h| !h.secret_findings.is_empty()).count();
+            score -= leaking as f64 * 10.0;
+        }
+
         // Credibility check - security requires depth
         if projects < 2 && total_security_lines < 2000 && score > 40.0 {
             score *= 0.5; // Heavy penalty for shallow security claims
         }
+        let score: f64 = score.max(0.0);
 
         DomainScore {
             score: score.min(100.0),
@@ -666,7 +748,7 @@ impl StreamlinedAnalyzer {
             
             // Strong Web3 indicators
             let strong_matches = web3_keywords.iter().filter(|kw| desc.contains(**kw) || name.contains(**kw)).count();
-            let framework_matches = web3_frameworks.iter().filter(|fw| desc.contains(**fw) || name.contains(**fw)).count();
+            let framework_matches = web3_frameworks.iter().filter(|fw| desc.contains(**fw) || name.contains(**fw) || declares_technology(repo, fw)).count();
             
             if repo.is_web3_project {
                 repo_web3_score += 25.0; // High score for confirmed Web3 projects
@@ -702,6 +784,36 @@ impl StreamlinedAnalyzer {
                     repo_web3_score += 10.0;
                 }
             }
+
+            // Contract engineering depth separates protocol work from tutorial forks
+            if let Some(contracts) = &repo.smart_contracts {
+                repo_web3_score += contracts.score * 0.3;
+                if contracts.tutorial {
+                    repo_web3_score *= 0.5;
+                }
+
+                let mut practices = contracts.frameworks.clone();
+                if contracts.fuzz_tests > 0 || !contracts.fuzzer_configs.is_empty() {
+                    practices.push("Fuzz testing".to_string());
+                }
+                if contracts.invariant_tests > 0 {
+                    practices.push("Invariant testing".to_string());
+                }
+                if contracts.uses_openzeppelin {
+                    practices.push("OpenZeppelin".to_string());
+                }
+                if !contracts.upgradeable_patterns.is_empty() {
+                    practices.push("Upgradeable contracts".to_string());
+                }
+                if !contracts.audits.is_empty() {
+                    practices.push("Audited contracts".to_string());
+                }
+                for practice in practices {
+                    if !technologies.contains(&practice) {
+                        technologies.push(practice);
+                    }
+                }
+            }
             
             if repo_web3_score > 5.0 { // Only count if meaningful Web3 score
                 score += repo_web3_score;
@@ -764,7 +876,7 @@ impl StreamlinedAnalyzer {
             
             // Data frameworks (strong indicators)
             let framework_matches = data_frameworks.iter().filter(|fw| {
-                desc.contains(**fw) || name.contains(**fw)
+                desc.contains(**fw) || name.contains(**fw) || declares_technology(repo, fw)
             }).count();
             
             if framework_matches > 0 {
@@ -1065,7 +1177,7 @@ impl StreamlinedAnalyzer {
 
     fn generate_badges(&self, analysis: &SkillAnalysis, iden_score: &IdenScore, followers: u32) -> Vec<Badge> {
         let mut badges = Vec::new();
-        let now = Utc::now();
+        let now = crate::clock::now();
 
         // Repository count badges
         if analysis.total_repositories >= 100 {
@@ -1243,23 +1355,45 @@ impl StreamlinedAnalyzer {
     fn calculate_enhanced_github_stats(&self, analysis: &SkillAnalysis, followers: u32, following: u32) -> GitHubStats {
         let total_stars: u32 = analysis.repository_analysis.iter().map(|r| r.stars).sum();
         let total_forks: u32 = analysis.repository_analysis.iter().map(|r| r.forks).sum();
-        
-        // Calculate contribution streak (simplified)
-        let contribution_streak = if analysis.years_active > 0.0 {
-            (analysis.years_active * 365.0 / 4.0) as u32 // Rough estimate
-        } else {
-            0
-        };
+        let contribution_streak = analysis.contribution_activity.as_ref().map(|a| a.current_streak).unwrap_or(0);
 
         GitHubStats {
             public_repos: analysis.total_repositories,
             followers,
             following,
             years_active: analysis.years_active,
-            total_commits: self.estimate_total_commits(analysis),
+            total_commits: self.total_commits(analysis),
             total_stars,
             total_forks,
             contribution_streak,
+            contribution_activity: analysis.contribution_activity.clone(),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Whether the repository's dependency manifests declare a technology, matched case-insensitively
+fn declares_technology(repo: &RepositoryAnalysis, technology: &str) -> bool {
+    repo.technologies.keys().any(|t| t.eq_ignore_ascii_case(technology))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ownership::OwnershipVerification;
+
+    #[tokio::test]
+    async fn shares_the_cache_it_is_given() {
+        let tokens = Arc::new(TokenPool::from_client(octocrab::Octocrab::default()));
+        let cache = Arc::new(AnalysisCache::in_memory());
+        let analyzer = StreamlinedAnalyzer::with_token_pool_and_cache(tokens, cache.clone());
+
+        cache.put_owner_verification(&OwnershipVerification {
+            username: "octocat".to_string(),
+            method: "bio".to_string(),
+            proof_url: "https://github.com/octocat".to_string(),
+            verified_at: Utc::now(),
+        });
+        assert!(Arc::ptr_eq(analyzer.cache(), &cache));
+        assert!(analyzer.cache().owner_verification("OctoCat").is_some());
+    }
+}
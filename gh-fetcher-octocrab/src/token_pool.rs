@@ -0,0 +1,414 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use futures::Future;
+use octocrab::models::{AppId, InstallationId};
+use octocrab::Octocrab;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use warp::http::{HeaderMap, Response, StatusCode};
+use warp::hyper::Body;
+
+use crate::api_usage::{ApiUsage, ApiUsageTracker, RateLimitWindow};
+use crate::profile_source::{is_rate_limit_error, SourceResult};
+
+/// How long a token sits out a secondary rate limit that came without `retry-after`
+const SECONDARY_LIMIT_BACKOFF_SECS: i64 = 60;
+
+/// How a pooled client authenticates
+#[derive(Clone)]
+pub enum TokenCredential {
+    PersonalToken(String),
+    // Octocrab mints and refreshes the installation token from the app's private key
+    AppInstallation { app_id: u64, private_key_pem: String, installation_id: u64 },
+}
+
+/// Whether a token is picked for requests
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum TokenHealth {
+    Healthy,
+    Throttled { until: DateTime<Utc> }, // Rate limited; used again once the window resets
+    Revoked { since: DateTime<Utc> },   // 401 Bad credentials; only used when nothing else is left
+}
+
+/// Health and budget of one pooled token, without the secret
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TokenStatus {
+    pub label: String,
+    pub health: TokenHealth,
+    pub usage: ApiUsage,
+}
+
+/// One authenticated client with its own rate-limit tracking
+pub struct PooledToken {
+    label: String,
+    client: Octocrab,
+    personal_token: Option<String>,
+    usage: Arc<ApiUsageTracker>,
+    health: Mutex<TokenHealth>,
+}
+
+impl PooledToken {
+    fn new(label: String, client: Octocrab, personal_token: Option<String>) -> Self {
+        Self {
+            label,
+            client,
+            personal_token,
+            usage: Arc::new(ApiUsageTracker::new()),
+            health: Mutex::new(TokenHealth::Healthy),
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn health(&self) -> TokenHealth {
+        self.health.lock().map(|h| h.clone()).unwrap_or(TokenHealth::Healthy)
+    }
+
+    fn set_health(&self, health: TokenHealth) {
+        if let Ok(mut current) = self.health.lock() {
+            *current = health;
+        }
+    }
+
+    fn is_usable(&self, now: DateTime<Utc>) -> bool {
+        match self.health() {
+            TokenHealth::Healthy => true,
+            TokenHealth::Throttled { until } => until <= now,
+            TokenHealth::Revoked { .. } => false,
+        }
+    }
+
+    /// Requests left in `resource`; a bucket never seen or already reset counts as untouched
+    fn budget(&self, resource: &str, now: DateTime<Utc>) -> u32 {
+        match self.usage.snapshot().rate_limits.get(resource) {
+            Some(window) if window.reset_at > now => window.remaining,
+            Some(window) => window.limit,
+            None => u32::MAX,
+        }
+    }
+
+    /// Usable tokens first, by budget; then throttled ones by how soon they recover; revoked last
+    fn rank(&self, resource: &str, now: DateTime<Utc>) -> (u8, i64) {
+        if self.is_usable(now) {
+            return (2, self.budget(resource, now) as i64);
+        }
+        match self.health() {
+            TokenHealth::Throttled { until } => (1, -until.timestamp()),
+            _ => (0, 0),
+        }
+    }
+}
+
+/// Several GitHub tokens (PATs and/or App installations) behind one client. Each request goes to the
+/// usable token with the most budget left for its bucket; revoked and throttled tokens are failed over.
+pub struct TokenPool {
+    tokens: Vec<PooledToken>,
+}
+
+impl TokenPool {
+    pub fn new(credentials: Vec<TokenCredential>) -> SourceResult<Self> {
+        Self::build(credentials, None)
+    }
+
+    /// Pool talking to a different API root (GitHub Enterprise or a fixture server)
+    pub fn with_base_url(credentials: Vec<TokenCredential>, base_url: &str) -> SourceResult<Self> {
+        Self::build(credentials, Some(base_url))
+    }
+
+    /// Single-token pool around an already configured client
+    pub fn from_client(client: Octocrab) -> Self {
+        Self { tokens: vec![PooledToken::new("token-1".to_string(), client, None)] }
+    }
+
+    /// `GITHUB_TOKENS` (comma or whitespace separated) and `GITHUB_TOKEN`, plus one installation per id in
+    /// `IDENZERO_GITHUB_APP_INSTALLATIONS` when `IDENZERO_GITHUB_APP_ID` and `IDENZERO_GITHUB_APP_KEY_PATH` are set
+    pub fn from_env() -> SourceResult<Self> {
+        let mut credentials: Vec<TokenCredential> = Vec::new();
+        let listed = std::env::var("GITHUB_TOKENS").unwrap_or_default();
+        let single = std::env::var("GITHUB_TOKEN").unwrap_or_default();
+        for token in listed.split(|c: char| c == ',' || c.is_whitespace()).chain([single.trim()]) {
+            let duplicate = credentials.iter().any(|c| matches!(c, TokenCredential::PersonalToken(t) if t == token));
+            if !token.is_empty() && !duplicate {
+                credentials.push(TokenCredential::PersonalToken(token.to_string()));
+            }
+        }
+
+        if let (Ok(app_id), Ok(key_path)) = (std::env::var("IDENZERO_GITHUB_APP_ID"), std::env::var("IDENZERO_GITHUB_APP_KEY_PATH")) {
+            let app_id: u64 = app_id.trim().parse().map_err(|_| format!("IDENZERO_GITHUB_APP_ID {} is not a number", app_id))?;
+            let private_key_pem = std::fs::read_to_string(&key_path)
+                .map_err(|e| format!("cannot read GitHub App key {}: {}", key_path, e))?;
+            let installations = std::env::var("IDENZERO_GITHUB_APP_INSTALLATIONS").unwrap_or_default();
+            for id in installations.split(',').map(str::trim).filter(|id| !id.is_empty()) {
+                let installation_id = id.parse().map_err(|_| format!("installation id {} is not a number", id))?;
+                credentials.push(TokenCredential::AppInstallation { app_id, private_key_pem: private_key_pem.clone(), installation_id });
+            }
+        }
+
+        Self::new(credentials)
+    }
+
+    fn build(credentials: Vec<TokenCredential>, base_url: Option<&str>) -> SourceResult<Self> {
+        if credentials.is_empty() {
+            return Err("no GitHub tokens configured".into());
+        }
+
+        let mut tokens = Vec::new();
+        for (index, credential) in credentials.into_iter().enumerate() {
+            let mut builder = Octocrab::builder();
+            if let Some(base_url) = base_url {
+                builder = builder.base_uri(base_url)?;
+            }
+            let token = match credential {
+                TokenCredential::PersonalToken(token) => {
+                    // Labels are public on /api/rate-limit, so they carry no part of the secret
+                    let client = builder.personal_token(token.clone()).build()?;
+                    PooledToken::new(format!("token-{}", index + 1), client, Some(token))
+                }
+                TokenCredential::AppInstallation { app_id, private_key_pem, installation_id } => {
+                    let key = jsonwebtoken::EncodingKey::from_rsa_pem(private_key_pem.as_bytes())?;
+                    let client = builder.app(AppId(app_id), key).build()?.installation(InstallationId(installation_id));
+                    PooledToken::new(format!("app-{}/installation-{}", app_id, installation_id), client, None)
+                }
+            };
+            tokens.push(token);
+        }
+        Ok(Self { tokens })
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Client of the first token, for work that spends no budget (reading bodies, unmetered fallbacks)
+    pub fn primary_client(&self) -> Octocrab {
+        self.tokens[0].client.clone()
+    }
+
+    /// First personal access token that has not been revoked, for plain HTTP clients outside the pool
+    pub fn personal_token(&self) -> Option<String> {
+        self.tokens.iter()
+            .filter(|t| !matches!(t.health(), TokenHealth::Revoked { .. }))
+            .find_map(|t| t.personal_token.clone())
+    }
+
+    /// The token the next `resource` request should go to. When every token is throttled or revoked
+    /// the one recovering soonest is returned, so the caller still gets GitHub's own error.
+    pub fn select(&self, resource: &str) -> &PooledToken {
+        let now = Utc::now();
+        // Reversed so ties go to the earlier token
+        self.tokens.iter().rev()
+            .max_by_key(|t| t.rank(resource, now))
+            .expect("token pool is never empty")
+    }
+
+    /// Sends a request through the best token for `resource`, paced and metered by that token's tracker.
+    /// `meter` also counts every attempt, for callers that report their own share of a shared pool.
+    /// A 401 marks the token revoked and a rate-limit answer marks it throttled; either is retried on
+    /// the next usable token, and returned as an error once none is left.
+    pub async fn send<F, Fut>(&self, resource: &str, meter: Option<&ApiUsageTracker>, request: F) -> octocrab::Result<Response<Body>>
+    where
+        F: Fn(Octocrab) -> Fut,
+        Fut: Future<Output = octocrab::Result<Response<Body>>>,
+    {
+        let mut tried = 0;
+        loop {
+            let token = self.select(resource);
+            token.usage.throttle(resource).await;
+            let response = request(token.client.clone()).await?;
+            token.usage.record_request();
+            token.usage.record_headers(response.headers());
+            if let Some(meter) = meter {
+                meter.record_request();
+            }
+            tried += 1;
+
+            let status = response.status();
+            if !matches!(status, StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS) {
+                token.set_health(TokenHealth::Healthy);
+                return Ok(response);
+            }
+
+            let headers = response.headers().clone();
+            let error = match octocrab::map_github_error(response).await {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+            let now = Utc::now();
+            if status == StatusCode::UNAUTHORIZED {
+                tracing::warn!("GitHub token {} was rejected, taking it out of rotation: {}", token.label, error);
+                token.set_health(TokenHealth::Revoked { since: now });
+            } else if status == StatusCode::TOO_MANY_REQUESTS || is_rate_limit_error(&error) {
+                let until = throttled_until(&headers, now);
+                tracing::warn!("GitHub token {} is rate limited until {}", token.label, until.to_rfc3339());
+                token.set_health(TokenHealth::Throttled { until });
+            } else {
+                // Missing scope or SSO enforcement: another token would not be treated differently
+                return Err(error);
+            }
+
+            let another_usable = self.tokens.iter().any(|t| t.is_usable(now));
+            if tried >= self.tokens.len() || !another_usable {
+                return Err(error);
+            }
+        }
+    }
+
+    /// When the first benched token becomes usable again; None while one is usable now (or all are revoked)
+    pub fn next_available(&self) -> Option<DateTime<Utc>> {
+        let now = Utc::now();
+        if self.tokens.iter().any(|t| t.is_usable(now)) {
+            return None;
+        }
+        self.tokens.iter()
+            .filter_map(|t| match t.health() {
+                TokenHealth::Throttled { until } => Some(until),
+                _ => None,
+            })
+            .min()
+    }
+
+    /// Requests made by every token, with each bucket's budget summed over the tokens not revoked
+    pub fn usage(&self) -> ApiUsage {
+        let mut requests_made = 0;
+        let mut rate_limits: BTreeMap<String, RateLimitWindow> = BTreeMap::new();
+        for token in &self.tokens {
+            let usage = token.usage.snapshot();
+            requests_made += usage.requests_made;
+            if matches!(token.health(), TokenHealth::Revoked { .. }) {
+                continue;
+            }
+            for (resource, window) in usage.rate_limits {
+                rate_limits.entry(resource)
+                    .and_modify(|total| {
+                        total.limit += window.limit;
+                        total.remaining += window.remaining;
+                        total.used += window.used;
+                        total.reset_at = total.reset_at.min(window.reset_at);
+                        total.observed_at = total.observed_at.max(window.observed_at);
+                    })
+                    .or_insert(window);
+            }
+        }
+        ApiUsage { requests_made, rate_limits }
+    }
+
+    pub fn status(&self) -> Vec<TokenStatus> {
+        self.tokens.iter()
+            .map(|t| TokenStatus { label: t.label.clone(), health: t.health(), usage: t.usage.snapshot() })
+            .collect()
+    }
+}
+
+/// `retry-after` for secondary limits, the window reset for an exhausted primary limit
+fn throttled_until(headers: &HeaderMap, now: DateTime<Utc>) -> DateTime<Utc> {
+    let header = |name: &str| -> Option<i64> { headers.get(name)?.to_str().ok()?.trim().parse().ok() };
+
+    if let Some(secs) = header("retry-after") {
+        return now + Duration::seconds(secs.max(1));
+    }
+    if header("x-ratelimit-remaining") == Some(0) {
+        if let Some(reset_at) = header("x-ratelimit-reset").and_then(|secs| Utc.timestamp_opt(secs, 0).single()) {
+            return reset_at.max(now + Duration::seconds(1));
+        }
+    }
+    now + Duration::seconds(SECONDARY_LIMIT_BACKOFF_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile_source::{GitHubSource, ProfileSource};
+    use warp::Filter;
+
+    /// GitHub stand-in whose answer depends on the bearer token
+    async fn github() -> String {
+        let route = warp::header::<String>("authorization").map(|auth: String| {
+            let reset = (Utc::now().timestamp() + 3600).to_string();
+            let (status, body, remaining) = match auth.trim_start_matches("Bearer ") {
+                "revoked" => (401, r#"{"message":"Bad credentials","documentation_url":""}"#, "0"),
+                "limited" => (403, r#"{"message":"API rate limit exceeded for user ID 1.","documentation_url":""}"#, "0"),
+                "no-scope" => (403, r#"{"message":"Resource not accessible by personal access token","documentation_url":""}"#, "900"),
+                "low" => (200, "[]", "100"),
+                _ => (200, "[]", "4000"),
+            };
+            warp::http::Response::builder()
+                .status(status)
+                .header("content-type", "application/json")
+                .header("x-ratelimit-limit", "5000")
+                .header("x-ratelimit-remaining", remaining)
+                .header("x-ratelimit-reset", reset)
+                .body(body)
+                .unwrap()
+        });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        format!("http://{}", addr)
+    }
+
+    fn pool(api: &str, tokens: &[&str]) -> Arc<TokenPool> {
+        let credentials = tokens.iter().map(|t| TokenCredential::PersonalToken(t.to_string())).collect();
+        Arc::new(TokenPool::with_base_url(credentials, api).unwrap())
+    }
+
+    async fn get(pool: &TokenPool) -> octocrab::Result<Response<Body>> {
+        pool.send("core", None, |client| async move { client._get("/users/octocat").await }).await
+    }
+
+    #[tokio::test]
+    async fn fails_over_revoked_and_throttled_tokens() {
+        let pool = pool(&github().await, &["revoked", "limited", "low", "plenty"]);
+
+        assert_eq!(get(&pool).await.unwrap().status(), StatusCode::OK);
+        let status = pool.status();
+        assert!(matches!(status[0].health, TokenHealth::Revoked { .. }));
+        assert!(matches!(status[1].health, TokenHealth::Throttled { .. }));
+        assert_eq!(status[2].usage.requests_made, 1);
+        assert_eq!(status.iter().map(|s| s.label.as_str()).collect::<Vec<_>>(), ["token-1", "token-2", "token-3", "token-4"]);
+
+        // Untried beats known-low, then the larger remaining budget wins
+        get(&pool).await.unwrap();
+        get(&pool).await.unwrap();
+        assert_eq!(pool.status()[2].usage.requests_made, 1);
+        assert_eq!(pool.status()[3].usage.requests_made, 2);
+        assert!(pool.next_available().is_none());
+        assert_eq!(pool.usage().rate_limit_remaining(), Some(4100));
+        assert_eq!(pool.personal_token().as_deref(), Some("limited"));
+    }
+
+    #[tokio::test]
+    async fn returns_githubs_error_once_no_token_is_left() {
+        let api = github().await;
+        let exhausted = pool(&api, &["revoked", "limited"]);
+        assert!(is_rate_limit_error(&get(&exhausted).await.unwrap_err()));
+        assert!(exhausted.next_available().is_some_and(|at| at > Utc::now()));
+
+        // A missing scope is not the token's health; it is returned without trying others
+        let no_scope = pool(&api, &["no-scope", "plenty"]);
+        assert!(get(&no_scope).await.is_err());
+        assert_eq!(no_scope.status()[1].usage.requests_made, 0);
+        assert_eq!(no_scope.status()[0].health, TokenHealth::Healthy);
+    }
+
+    #[tokio::test]
+    async fn sources_sharing_a_pool_count_their_own_requests() {
+        let pool = pool(&github().await, &["revoked", "plenty"]);
+        let first = GitHubSource::with_token_pool(pool.clone());
+        let second = GitHubSource::with_token_pool(pool.clone());
+
+        first.fetch_repositories("octocat").await.unwrap();
+        for _ in 0..3 {
+            second.fetch_repositories("octocat").await.unwrap();
+        }
+        // The first source's failed attempt on the revoked token counts as its call
+        assert_eq!(first.api_usage().unwrap().requests_made, 2);
+        assert_eq!(second.api_usage().unwrap().requests_made, 3);
+        assert_eq!(pool.usage().requests_made, 5);
+    }
+}
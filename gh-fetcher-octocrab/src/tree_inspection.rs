@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::local_source::{is_vendored_path, language_for_path};
+
+/// Lint and formatter configs, matched on the file name
+const LINT_CONFIGS: [&str; 22] = [
+    ".eslintrc", ".eslintrc.js", ".eslintrc.cjs", ".eslintrc.json", ".eslintrc.yml", "eslint.config.js", "eslint.config.mjs",
+    ".prettierrc", ".prettierrc.json", "biome.json", "tslint.json", "rustfmt.toml", ".rustfmt.toml", "clippy.toml",
+    ".flake8", ".pylintrc", "ruff.toml", ".golangci.yml", ".golangci.yaml", ".solhint.json", ".rubocop.yml", ".pre-commit-config.yaml",
+];
+
+/// Coverage tooling configs, matched on the file name
+const COVERAGE_CONFIGS: [&str; 11] = [
+    ".coveragerc", "codecov.yml", ".codecov.yml", ".coveralls.yml", ".nycrc", ".nycrc.json", ".c8rc.json",
+    "tarpaulin.toml", ".tarpaulin.toml", ".solcover.js", "sonar-project.properties",
+];
+
+/// Evidence about tests, CI and project layout read from a repository's file listing
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TreeInspection {
+    pub source_files: u32,
+    pub test_files: u32,
+    pub test_directories: Vec<String>,
+    pub ci_files: Vec<String>,
+    pub coverage_configs: Vec<String>,
+    pub lint_configs: Vec<String>,
+    pub has_containerization: bool, // Dockerfile, docker-compose or Kubernetes manifests
+    pub source_directories: u32, // Distinct directories holding source files
+}
+
+impl TreeInspection {
+    pub fn from_paths(paths: &[String]) -> Self {
+        let mut inspection = Self::default();
+        let mut test_directories = BTreeSet::new();
+        let mut source_directories = BTreeSet::new();
+
+        for path in paths.iter().filter(|p| !is_vendored_path(p)) {
+            let file_name = path.rsplit('/').next().unwrap_or(path);
+            let lower_name = file_name.to_lowercase();
+
+            if is_test_path(path) {
+                inspection.test_files += 1;
+                if let Some(dir) = test_directory(path) {
+                    test_directories.insert(dir);
+                }
+            } else if language_for_path(path).is_some() {
+                inspection.source_files += 1;
+                source_directories.insert(path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("").to_string());
+            }
+
+            if is_ci_path(path) {
+                inspection.ci_files.push(path.clone());
+            }
+            if COVERAGE_CONFIGS.contains(&lower_name.as_str()) {
+                inspection.coverage_configs.push(path.clone());
+            }
+            if LINT_CONFIGS.contains(&lower_name.as_str()) {
+                inspection.lint_configs.push(path.clone());
+            }
+            if lower_name == "dockerfile" || lower_name.starts_with("docker-compose") || path.starts_with("k8s/") || path.starts_with("helm/") {
+                inspection.has_containerization = true;
+            }
+        }
+
+        inspection.test_directories = test_directories.into_iter().collect();
+        inspection.source_directories = source_directories.len() as u32;
+        inspection
+    }
+
+    /// Test-to-source ratio backed by CI, coverage tooling and dedicated test directories
+    pub fn testing_score(&self) -> f64 {
+        if self.source_files == 0 {
+            return 0.0;
+        }
+
+        // Ratio of test files to source files, saturating at one test per three sources
+        let ratio_score = (self.test_files as f64 / self.source_files as f64 * 3.0).min(1.0) * 60.0;
+        let ci = if self.ci_files.is_empty() { 0.0 } else { 20.0 };
+        let coverage = if self.coverage_configs.is_empty() { 0.0 } else { 10.0 };
+        let test_dirs = if self.test_directories.is_empty() { 0.0 } else { 10.0 };
+
+        (ratio_score + ci + coverage + test_dirs).min(100.0)
+    }
+
+    /// Up to 90 points of layout and tooling evidence; repository maturity supplies the rest
+    pub fn architecture_score(&self) -> f64 {
+        if self.source_files == 0 {
+            return 0.0;
+        }
+
+        let modularity = (self.source_directories.min(10) as f64 / 10.0) * 30.0;
+        let lint = if self.lint_configs.is_empty() { 0.0 } else { 15.0 };
+        let ci = if self.ci_files.is_empty() { 0.0 } else { 15.0 };
+        let tests = if self.test_files > 0 { 15.0 } else { 0.0 };
+        let containers = if self.has_containerization { 15.0 } else { 0.0 };
+
+        modularity + lint + ci + tests + containers
+    }
+}
+
+pub fn is_test_path(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    let file_name = lower.rsplit('/').next().unwrap_or(&lower);
+
+    lower.split('/').any(|segment| matches!(segment, "test" | "tests" | "__tests__" | "spec" | "specs"))
+        || file_name.starts_with("test_")
+        || file_name.contains("_test.")
+        || file_name.contains(".test.")
+        || file_name.contains(".spec.")
+        || file_name.ends_with(".t.sol")
+}
+
+pub fn is_ci_path(path: &str) -> bool {
+    path.starts_with(".github/workflows/")
+        || path == ".gitlab-ci.yml"
+        || path == ".travis.yml"
+        || path == "Jenkinsfile"
+        || path == "azure-pipelines.yml"
+        || path.starts_with(".circleci/")
+        || path.starts_with(".woodpecker")
+}
+
+/// The directory a test lives under, e.g. "tests" or "src/__tests__"
+fn test_directory(path: &str) -> Option<String> {
+    let segments: Vec<&str> = path.split('/').collect();
+    let index = segments.iter()
+        .position(|segment| matches!(segment.to_lowercase().as_str(), "test" | "tests" | "__tests__" | "spec" | "specs"))?;
+    Some(segments[..=index].join("/"))
+}
@@ -0,0 +1,407 @@
+use chrono::{DateTime, Duration, Utc};
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::analysis_cache::AnalysisCache;
+use crate::ownership::GitHubProofClient;
+use crate::profile_source::SourceResult;
+use crate::smart_contracts::is_evm_address;
+
+/// How long an issued challenge can be signed
+const CHALLENGE_TTL_MINUTES: i64 = 10;
+/// Unanswered challenges kept per account and overall; the oldest goes first
+const MAX_PENDING_CHALLENGES_PER_ACCOUNT: usize = 5;
+const MAX_PENDING_CHALLENGES: usize = 10_000;
+
+/// A message the wallet owner must sign to bind it to a GitHub account
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WalletChallenge {
+    pub nonce: String,
+    pub username: String,
+    pub wallet_address: String, // Lowercased
+    pub message: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Proof that the holder of `wallet_address` vouched for the GitHub account
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WalletBinding {
+    pub username: String,
+    pub wallet_address: String, // Lowercased
+    pub message: String,
+    pub signature: String,
+    pub gist_url: String, // Gist owned by the account that carries the signature
+    pub bound_at: DateTime<Utc>,
+}
+
+/// Issues signing challenges and stores verified wallet bindings in the analysis cache
+pub struct WalletBinder {
+    cache: Arc<AnalysisCache>,
+    challenges: Mutex<HashMap<String, WalletChallenge>>, // By nonce
+    proofs: GitHubProofClient,
+}
+
+impl WalletBinder {
+    pub fn new(cache: Arc<AnalysisCache>) -> Self {
+        Self {
+            cache,
+            challenges: Mutex::new(HashMap::new()),
+            proofs: GitHubProofClient::default(),
+        }
+    }
+
+    pub fn with_proof_client(mut self, proofs: GitHubProofClient) -> Self {
+        self.proofs = proofs;
+        self
+    }
+
+    /// A fresh EIP-191 message naming the GitHub account and the wallet
+    pub fn issue_challenge(&self, username: &str, wallet_address: &str) -> SourceResult<WalletChallenge> {
+        let username = username.trim();
+        let wallet_address = wallet_address.trim().to_lowercase();
+        if username.is_empty() || !username.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(format!("{} is not a GitHub username", username).into());
+        }
+        if !is_evm_address(&wallet_address) {
+            return Err(format!("{} is not an EVM address", wallet_address).into());
+        }
+
+        let mut nonce = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let nonce = hex::encode(nonce);
+        let now = crate::clock::now();
+        let challenge = WalletChallenge {
+            message: format!(
+                "IdenZero wallet binding\n\nI control {} and GitHub account {}.\n\nNonce: {}\nIssued at: {}",
+                wallet_address, username, nonce, now.to_rfc3339()
+            ),
+            nonce: nonce.clone(),
+            username: username.to_string(),
+            wallet_address,
+            expires_at: now + Duration::minutes(CHALLENGE_TTL_MINUTES),
+        };
+
+        let mut challenges = self.challenges.lock().map_err(|_| "challenge store unavailable")?;
+        challenges.retain(|_, c| c.expires_at > now);
+        let pending = challenges.values().filter(|c| c.username.eq_ignore_ascii_case(username)).count();
+        if pending >= MAX_PENDING_CHALLENGES_PER_ACCOUNT {
+            evict_oldest(&mut challenges, Some(username));
+        }
+        if challenges.len() >= MAX_PENDING_CHALLENGES {
+            evict_oldest(&mut challenges, None);
+        }
+        challenges.insert(nonce, challenge.clone());
+        Ok(challenge)
+    }
+
+    /// Checks the `personal_sign` signature and records the binding. The signer must also control
+    /// the GitHub account, shown by publishing this very signature in a gist the account owns; an
+    /// earlier ownership verification is not enough, as it does not say who is binding the wallet.
+    /// The gist is mandatory: the signature alone proves the wallet, and any wallet can name any account.
+    /// Replacing a bound wallet also needs `previous_signature`, the same message signed by that wallet.
+    /// A challenge can be answered once; a failed attempt needs a new one.
+    pub async fn verify(
+        &self,
+        nonce: &str,
+        signature: &str,
+        gist_url: &str,
+        previous_signature: Option<&str>,
+    ) -> SourceResult<WalletBinding> {
+        let challenge = self.challenges.lock().map_err(|_| "challenge store unavailable")?
+            .remove(nonce)
+            .filter(|c| c.expires_at > crate::clock::now())
+            .ok_or("unknown or expired challenge")?;
+
+        let signer = recover_signer(&challenge.message, signature)?;
+        if signer != challenge.wallet_address {
+            return Err(format!("signature is from {}, not {}", signer, challenge.wallet_address).into());
+        }
+        // Checked before the gist so a rebind without the old wallet fails without a GitHub call
+        let previous = self.cache.wallet_binding(&challenge.username);
+        if let Some(previous) = previous.as_ref().filter(|b| b.wallet_address != challenge.wallet_address) {
+            let previous_signature = previous_signature
+                .ok_or_else(|| format!("{} is bound to {}; sign the challenge with that wallet too to replace it", challenge.username, previous.wallet_address))?;
+            if recover_signer(&challenge.message, previous_signature)? != previous.wallet_address {
+                return Err(format!("previous_signature is not from {}", previous.wallet_address).into());
+            }
+        }
+        self.proofs.check_gist(gist_url, &challenge.username, signature).await?;
+
+        let binding = WalletBinding {
+            username: challenge.username,
+            wallet_address: challenge.wallet_address,
+            message: challenge.message,
+            signature: signature.trim().to_string(),
+            gist_url: gist_url.to_string(),
+            bound_at: crate::clock::now(),
+        };
+        match &previous {
+            Some(previous) => self.cache.replace_wallet_binding(&binding, &previous.wallet_address)?,
+            None => self.cache.insert_wallet_binding(&binding)?,
+        }
+        tracing::info!("Bound wallet {} to {}", binding.wallet_address, binding.username);
+        Ok(binding)
+    }
+
+    pub fn binding(&self, username: &str) -> Option<WalletBinding> {
+        self.cache.wallet_binding(username)
+    }
+
+    /// Removes the binding without any signature, for owners who lost the bound wallet's key.
+    /// Callers must restrict this to administrators
+    pub fn unbind(&self, username: &str) -> SourceResult<Option<WalletBinding>> {
+        let removed = self.cache.remove_wallet_binding(username.trim())?;
+        if let Some(binding) = &removed {
+            tracing::info!("Unbound wallet {} from {}", binding.wallet_address, binding.username);
+        }
+        Ok(removed)
+    }
+}
+
+/// Drops the challenge closest to expiry, of `username` when given
+fn evict_oldest(challenges: &mut HashMap<String, WalletChallenge>, username: Option<&str>) {
+    let oldest = challenges.values()
+        .filter(|c| username.is_none_or(|username| c.username.eq_ignore_ascii_case(username)))
+        .min_by_key(|c| c.expires_at)
+        .map(|c| c.nonce.clone());
+    if let Some(nonce) = oldest {
+        challenges.remove(&nonce);
+    }
+}
+
+/// keccak256("\x19Ethereum Signed Message:\n" + len(message) + message), as signed by `personal_sign`
+pub fn eip191_hash(message: &str) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(format!("\x19Ethereum Signed Message:\n{}", message.len()));
+    hasher.update(message);
+    hasher.finalize().into()
+}
+
+/// Lowercased address whose key produced a 65-byte r||s||v `signature` over `message`
+pub fn recover_signer(message: &str, signature: &str) -> SourceResult<String> {
+    let bytes = hex::decode(signature.trim().trim_start_matches("0x"))?;
+    if bytes.len() != 65 {
+        return Err(format!("signature is {} bytes, expected 65", bytes.len()).into());
+    }
+
+    // Wallets send v as 27/28; some libraries as 0/1
+    let v = match bytes[64] {
+        27 | 28 => bytes[64] - 27,
+        0 | 1 => bytes[64],
+        other => return Err(format!("invalid recovery id {}", other).into()),
+    };
+    let mut signature = Signature::from_slice(&bytes[..64])?;
+    let mut recovery_id = RecoveryId::from_byte(v).ok_or("invalid recovery id")?;
+    // High-s signatures recover with the flipped parity once normalized
+    if let Some(normalized) = signature.normalize_s() {
+        signature = normalized;
+        recovery_id = RecoveryId::new(!recovery_id.is_y_odd(), recovery_id.is_x_reduced());
+    }
+
+    let key = VerifyingKey::recover_from_prehash(&eip191_hash(message), &signature, recovery_id)?;
+    Ok(address_of(&key))
+}
+
+/// Last 20 bytes of keccak256 over the uncompressed public key
+pub fn address_of(key: &VerifyingKey) -> String {
+    let point = key.to_encoded_point(false);
+    format!("0x{}", hex::encode(&Keccak256::digest(&point.as_bytes()[1..])[12..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::ecdsa::SigningKey;
+    use warp::Filter;
+
+    // Anvil/Hardhat account 0
+    const ANVIL_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const ANVIL_ADDRESS: &str = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+    // personal_sign("Hello World") by ANVIL_KEY, as produced by ethers' `signMessage`
+    const HELLO_SIGNATURE: &str = "0x65e72b1cf8e189569963750e10ccb88fe89389daeeb8b735277d59cd6885ee823eb5a6982b540f185703492dab77b863a88ce01f27e21ade8b2879c10fc9e6531c";
+
+    fn sign(key_hex: &str, message: &str) -> String {
+        let key = SigningKey::from_slice(&hex::decode(key_hex).unwrap()).unwrap();
+        let (signature, recovery_id) = key.sign_prehash_recoverable(&eip191_hash(message)).unwrap();
+        format!("0x{}{:02x}", hex::encode(signature.to_bytes()), 27 + recovery_id.to_byte())
+    }
+
+    /// Serves `/gists/{id}` owned by `owner` with whatever `content` holds at request time
+    async fn gist_server(owner: &'static str, content: Arc<Mutex<String>>) -> String {
+        let route = warp::path!("gists" / String).map(move |_id: String| {
+            warp::reply::json(&serde_json::json!({
+                "owner": { "login": owner },
+                "files": { "proof.txt": { "content": content.lock().unwrap().clone() } }
+            }))
+        });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        format!("http://{}", addr)
+    }
+
+    fn binder(api: &str) -> WalletBinder {
+        WalletBinder::new(Arc::new(AnalysisCache::in_memory()))
+            .with_proof_client(GitHubProofClient::default().with_api(api))
+    }
+
+    #[test]
+    fn hashes_with_the_personal_sign_prefix() {
+        assert_eq!(hex::encode(eip191_hash("Hello World")), "a1de988600a42c4b4ab089b619297c17d53cffae5d5120d82d8a92d0bb3b78f2");
+    }
+
+    #[test]
+    fn recovers_a_known_personal_sign_signature() {
+        assert_eq!(recover_signer("Hello World", HELLO_SIGNATURE).unwrap(), ANVIL_ADDRESS);
+        assert_eq!(sign(ANVIL_KEY, "Hello World"), HELLO_SIGNATURE);
+        // Another key, to rule out a hard-wired address
+        let other = sign("0123456789012345678901234567890123456789012345678901234567890123", "Hello World");
+        assert_eq!(recover_signer("Hello World", &other).unwrap(), "0x14791697260e4c9a71f18484c9f997b308e59325");
+        assert_ne!(recover_signer("Hello World!", HELLO_SIGNATURE).unwrap(), ANVIL_ADDRESS);
+    }
+
+    #[test]
+    fn accepts_v_as_27_28_or_0_1() {
+        let v_28 = HELLO_SIGNATURE;
+        assert!(v_28.ends_with("1c"));
+        let v_1 = format!("{}01", &v_28[..v_28.len() - 2]);
+        assert_eq!(recover_signer("Hello World", &v_1).unwrap(), ANVIL_ADDRESS);
+
+        let flipped_27 = format!("{}1b", &v_28[..v_28.len() - 2]);
+        let flipped_0 = format!("{}00", &v_28[..v_28.len() - 2]);
+        assert_ne!(recover_signer("Hello World", &flipped_27).unwrap(), ANVIL_ADDRESS);
+        assert_eq!(recover_signer("Hello World", &flipped_27).unwrap(), recover_signer("Hello World", &flipped_0).unwrap());
+    }
+
+    #[test]
+    fn recovers_high_s_signatures() {
+        let bytes = hex::decode(&HELLO_SIGNATURE[2..]).unwrap();
+        let signature = Signature::from_slice(&bytes[..64]).unwrap();
+        let (r, s) = signature.split_scalars();
+        let high_s = Signature::from_scalars(r.to_bytes(), (-*s).to_bytes()).unwrap();
+        let flipped_v = if bytes[64] == 27 { 28 } else { 27 };
+        let high_s = format!("0x{}{:02x}", hex::encode(high_s.to_bytes()), flipped_v);
+        assert_eq!(recover_signer("Hello World", &high_s).unwrap(), ANVIL_ADDRESS);
+    }
+
+    #[test]
+    fn rejects_malformed_signatures() {
+        assert!(recover_signer("Hello World", "0x1234").is_err());
+        assert!(recover_signer("Hello World", "not hex").is_err());
+        let bad_v = format!("{}05", &HELLO_SIGNATURE[..HELLO_SIGNATURE.len() - 2]);
+        assert!(recover_signer("Hello World", &bad_v).unwrap_err().to_string().contains("recovery id"));
+        let zero_r = format!("0x{}{}", "00".repeat(32), &HELLO_SIGNATURE[66..]);
+        assert!(recover_signer("Hello World", &zero_r).is_err());
+    }
+
+    #[tokio::test]
+    async fn binds_once_per_challenge() {
+        let content = Arc::new(Mutex::new(String::new()));
+        let binder = binder(&gist_server("octocat", content.clone()).await);
+
+        let challenge = binder.issue_challenge("octocat", ANVIL_ADDRESS).unwrap();
+        let signature = sign(ANVIL_KEY, &challenge.message);
+        *content.lock().unwrap() = signature.clone();
+
+        let binding = binder.verify(&challenge.nonce, &signature, "https://gist.github.com/octocat/abc123", None).await.unwrap();
+        assert_eq!(binding.wallet_address, ANVIL_ADDRESS);
+        assert_eq!(binder.binding("octocat").unwrap().signature, signature);
+
+        let reused = binder.verify(&challenge.nonce, &signature, "https://gist.github.com/octocat/abc123", None).await;
+        assert!(reused.unwrap_err().to_string().contains("unknown or expired"));
+    }
+
+    #[tokio::test]
+    async fn rejects_expired_challenges_and_foreign_gists() {
+        let content = Arc::new(Mutex::new(String::new()));
+        let binder = binder(&gist_server("mallory", content.clone()).await);
+
+        let challenge = binder.issue_challenge("octocat", ANVIL_ADDRESS).unwrap();
+        let signature = sign(ANVIL_KEY, &challenge.message);
+        *content.lock().unwrap() = signature.clone();
+        binder.challenges.lock().unwrap().get_mut(&challenge.nonce).unwrap().expires_at = crate::clock::now() - Duration::seconds(1);
+        let expired = binder.verify(&challenge.nonce, &signature, "https://gist.github.com/abc123", None).await;
+        assert!(expired.unwrap_err().to_string().contains("unknown or expired"));
+
+        let challenge = binder.issue_challenge("octocat", ANVIL_ADDRESS).unwrap();
+        let signature = sign(ANVIL_KEY, &challenge.message);
+        *content.lock().unwrap() = signature.clone();
+        let foreign = binder.verify(&challenge.nonce, &signature, "https://gist.github.com/abc123", None).await;
+        assert!(foreign.unwrap_err().to_string().contains("not owned by octocat"));
+        assert!(binder.binding("octocat").is_none());
+    }
+
+    #[tokio::test]
+    async fn replaces_a_wallet_only_with_the_old_wallets_signature() {
+        const OTHER_KEY: &str = "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+        let content = Arc::new(Mutex::new(String::new()));
+        let binder = binder(&gist_server("octocat", content.clone()).await);
+        let gist = "https://gist.github.com/octocat/abc123";
+
+        let challenge = binder.issue_challenge("octocat", ANVIL_ADDRESS).unwrap();
+        let signature = sign(ANVIL_KEY, &challenge.message);
+        *content.lock().unwrap() = signature.clone();
+        binder.verify(&challenge.nonce, &signature, gist, None).await.unwrap();
+
+        let other_address = address_of(SigningKey::from_slice(&hex::decode(OTHER_KEY).unwrap()).unwrap().verifying_key());
+        let challenge = binder.issue_challenge("octocat", &other_address).unwrap();
+        let signature = sign(OTHER_KEY, &challenge.message);
+        *content.lock().unwrap() = signature.clone();
+        let unproven = binder.verify(&challenge.nonce, &signature, gist, None).await;
+        assert!(unproven.unwrap_err().to_string().contains("is bound to"));
+        assert_eq!(binder.binding("octocat").unwrap().wallet_address, ANVIL_ADDRESS);
+
+        let challenge = binder.issue_challenge("octocat", &other_address).unwrap();
+        let signature = sign(OTHER_KEY, &challenge.message);
+        *content.lock().unwrap() = signature.clone();
+        let previous = sign(ANVIL_KEY, &challenge.message);
+        binder.verify(&challenge.nonce, &signature, gist, Some(&previous)).await.unwrap();
+        assert_eq!(binder.binding("octocat").unwrap().wallet_address, other_address);
+    }
+
+    #[tokio::test]
+    async fn unbinding_allows_a_fresh_binding() {
+        const OTHER_KEY: &str = "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+        let content = Arc::new(Mutex::new(String::new()));
+        let binder = binder(&gist_server("octocat", content.clone()).await);
+        let gist = "https://gist.github.com/octocat/abc123";
+
+        let challenge = binder.issue_challenge("octocat", ANVIL_ADDRESS).unwrap();
+        let signature = sign(ANVIL_KEY, &challenge.message);
+        *content.lock().unwrap() = signature.clone();
+        binder.verify(&challenge.nonce, &signature, gist, None).await.unwrap();
+
+        assert_eq!(binder.unbind("OctoCat").unwrap().unwrap().wallet_address, ANVIL_ADDRESS);
+        assert!(binder.binding("octocat").is_none());
+        assert!(binder.unbind("octocat").unwrap().is_none());
+
+        // The old key is not needed any more
+        let other_address = address_of(SigningKey::from_slice(&hex::decode(OTHER_KEY).unwrap()).unwrap().verifying_key());
+        let challenge = binder.issue_challenge("octocat", &other_address).unwrap();
+        let signature = sign(OTHER_KEY, &challenge.message);
+        *content.lock().unwrap() = signature.clone();
+        binder.verify(&challenge.nonce, &signature, gist, None).await.unwrap();
+        assert_eq!(binder.binding("octocat").unwrap().wallet_address, other_address);
+    }
+
+    #[test]
+    fn pending_challenges_are_capped_per_account() {
+        let binder = binder("http://127.0.0.1:9");
+        let nonces: Vec<String> = (0..MAX_PENDING_CHALLENGES_PER_ACCOUNT + 1).map(|_| {
+            // Age the earlier challenges so issue order decides which is oldest
+            for challenge in binder.challenges.lock().unwrap().values_mut() {
+                challenge.expires_at -= Duration::seconds(1);
+            }
+            binder.issue_challenge("octocat", ANVIL_ADDRESS).unwrap().nonce
+        }).collect();
+        binder.issue_challenge("hubot", ANVIL_ADDRESS).unwrap();
+
+        let challenges = binder.challenges.lock().unwrap();
+        assert_eq!(challenges.len(), MAX_PENDING_CHALLENGES_PER_ACCOUNT + 1);
+        assert!(!challenges.contains_key(&nonces[0]));
+        assert!(nonces[1..].iter().all(|nonce| challenges.contains_key(nonce)));
+    }
+}